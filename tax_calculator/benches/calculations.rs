@@ -0,0 +1,76 @@
+// Criterion benchmarks for the calculation hot paths used by batch runs
+// (cargo bench); each group sweeps salaries from below PTKP to the top bracket
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use tax_calculator::tax::{
+    calculate_income_tax, calculate_pph21, compute_ptkp, get_tax_brackets, gross_up, ter_rate, PPh21Params, TaxConfig, TerCategory,
+};
+
+// Monthly salaries from below TK/0 PTKP up into the 35% bracket
+const MONTHLY_SALARIES: [f64; 5] = [4_000_000.0, 10_000_000.0, 25_000_000.0, 100_000_000.0, 500_000_000.0];
+
+// Annual PKP amounts landing in each of the five brackets
+const ANNUAL_PKP: [f64; 5] = [30_000_000.0, 120_000_000.0, 400_000_000.0, 2_000_000_000.0, 10_000_000_000.0];
+
+fn bench_calculate_pph21(c: &mut Criterion) {
+    let config = TaxConfig::default();
+    let mut group = c.benchmark_group("calculate_pph21");
+    for salary in MONTHLY_SALARIES {
+        let params = PPh21Params::builder().gross_income(salary).married(true).dependents(2).build().unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(salary), &params, |b, params| {
+            b.iter(|| calculate_pph21(black_box(params), &config))
+        });
+    }
+    group.finish();
+}
+
+fn bench_calculate_income_tax(c: &mut Criterion) {
+    let brackets = get_tax_brackets();
+    let mut group = c.benchmark_group("calculate_income_tax");
+    for pkp in ANNUAL_PKP {
+        group.bench_with_input(BenchmarkId::from_parameter(pkp), &pkp, |b, &pkp| {
+            b.iter(|| calculate_income_tax(black_box(pkp), &brackets))
+        });
+    }
+    group.finish();
+}
+
+// Built-in table lookups; these read shared static tables, so they should stay
+// in the nanosecond range with no allocation per call
+fn bench_table_lookups(c: &mut Criterion) {
+    c.bench_function("compute_ptkp", |b| b.iter(|| compute_ptkp(black_box(true), black_box(2), false, 2024)));
+    c.bench_function("ter_rate", |b| b.iter(|| ter_rate(black_box(TerCategory::B), black_box(12_000_000.0))));
+}
+
+// Fixed-point iterations to converge within Rp 0,01 (TK/0, K/3, TK/0 without
+// NPWP) when this was written:
+//
+//     net   5.000.000:  4 /  1 /  5
+//     net  10.000.000:  7 /  5 /  8
+//     net  25.000.000: 10 / 10 / 12
+//     net  50.000.000: 13 / 13 / 15
+//     net 100.000.000: 13 / 14 / 16
+//     net 500.000.000: 15 / 15 / 18
+//
+// Each iteration is one calculate_pph21, so a gross_up time well above ~18x
+// the matching calculate_pph21 time means convergence has regressed
+fn bench_gross_up(c: &mut Criterion) {
+    let config = TaxConfig::default();
+    let statuses = [
+        ("TK/0", PPh21Params::builder().build().unwrap()),
+        ("K/3", PPh21Params::builder().married(true).dependents(3).build().unwrap()),
+        ("TK/0 tanpa NPWP", PPh21Params::builder().has_npwp(false).build().unwrap()),
+    ];
+    let mut group = c.benchmark_group("gross_up");
+    for net_salary in [5_000_000.0, 10_000_000.0, 25_000_000.0, 50_000_000.0, 100_000_000.0, 500_000_000.0] {
+        for (label, params) in &statuses {
+            group.bench_with_input(BenchmarkId::new(*label, net_salary), params, |b, params| {
+                b.iter(|| gross_up(black_box(net_salary), params, &config).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_calculate_pph21, bench_calculate_income_tax, bench_table_lookups, bench_gross_up);
+criterion_main!(benches);
@@ -0,0 +1,212 @@
+// PPh 21 for many employees at once, read from and written to CSV
+
+use std::io;
+
+use crate::i18n::Message;
+use crate::tax::{calculate_pph21_result, parse_rupiah, PPh21Params, Pph21Method, PtkpStatus, TaxConfig};
+
+// Output columns: the input columns followed by the calculated figures
+pub const BATCH_OUTPUT_HEADER: [&str; 8] = [
+    "name",
+    "gross_income",
+    "is_married",
+    "num_dependents",
+    "ptkp",
+    "pkp",
+    "monthly_tax",
+    "annual_tax",
+];
+
+// An input row that was skipped, with its 1-based line number in the file
+// and the column at fault (None when the row as a whole is malformed)
+#[derive(Debug, PartialEq)]
+pub struct BatchRowError {
+    pub line: u64,
+    pub field: Option<&'static str>,
+    pub message: Message,
+}
+
+// Summary of a batch run
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct BatchReport {
+    pub processed: usize,
+    pub skipped: Vec<BatchRowError>,
+}
+
+// How an input file gives the PTKP status: as is_married and num_dependents
+// columns, or as one `status` column written as in the PTKP tables
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    Columns,
+    Status,
+}
+
+impl Layout {
+    fn from_headers(headers: &csv::StringRecord) -> Layout {
+        if headers.iter().any(|header| header.trim() == "status") {
+            Layout::Status
+        } else {
+            Layout::Columns
+        }
+    }
+    
+    fn columns(&self) -> usize {
+        match self {
+            Layout::Columns => 4,
+            Layout::Status => 3,
+        }
+    }
+}
+
+// Parse one `name,gross_income,is_married,num_dependents` or
+// `name,gross_income,status` record found at `line`
+fn parse_employee(record: &csv::StringRecord, line: u64, layout: Layout) -> Result<(String, PPh21Params), BatchRowError> {
+    let fault = |field, message| BatchRowError { line, field, message };
+    if record.len() != layout.columns() {
+        return Err(fault(None, Message::new("error.batch_columns", &[&layout.columns(), &record.len()])));
+    }
+    
+    let name = record[0].trim().to_string();
+    let gross_income = parse_rupiah(&record[1])
+        .ok()
+        .filter(|gross| gross.is_finite() && *gross >= 0.0)
+        .ok_or_else(|| fault(Some("gross_income"), Message::new("error.batch_value", &[&"gross_income", &record[1].trim()])))?;
+    let builder = PPh21Params::builder().gross_income(gross_income);
+    let builder = match layout {
+        Layout::Columns => {
+            let is_married = match record[2].trim().to_lowercase().as_str() {
+                "true" | "1" | "y" => true,
+                "false" | "0" | "n" => false,
+                other => return Err(fault(Some("is_married"), Message::new("error.batch_married", &[&other]))),
+            };
+            let num_dependents = record[3]
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| fault(Some("num_dependents"), Message::new("error.batch_value", &[&"num_dependents", &record[3].trim()])))?;
+            // Dependents only count towards PTKP for married employees, as in the menu
+            builder.married(is_married).dependents(if is_married { num_dependents } else { 0 })
+        },
+        Layout::Status => {
+            let status: PtkpStatus = record[2].parse().map_err(|err| fault(Some("status"), err))?;
+            builder
+                .married(status.is_married())
+                .spouse_income_combined(status.spouse_income_combined())
+                .dependents(status.dependents())
+        },
+    };
+    let params = builder.build().map_err(|err| fault(None, err.message()))?;
+    
+    Ok((name, params))
+}
+
+// Line number of a record in the input file
+fn record_line(record: &csv::StringRecord) -> u64 {
+    record.position().map(|position| position.line()).unwrap_or(0)
+}
+
+// Compute PPh 21 for every employee in `input`, writing one result row per
+// valid employee to `output`; malformed rows are skipped and reported. Each
+// row is logged at info level, and each skipped row at warn level
+pub fn run_batch<R: io::Read, W: io::Write>(input: R, output: W, config: &TaxConfig) -> Result<BatchReport, Message> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input);
+    let layout = Layout::from_headers(reader.headers().map_err(|err| Message::new("error.csv_read", &[&err]))?);
+    let mut writer = csv::Writer::from_writer(output);
+    writer.write_record(BATCH_OUTPUT_HEADER).map_err(|err| Message::new("error.csv_write", &[&err]))?;
+    
+    let mut report = BatchReport { processed: 0, skipped: Vec::new() };
+    for record in reader.records() {
+        let record = record.map_err(|err| Message::new("error.csv_read", &[&err]))?;
+        
+        match parse_employee(&record, record_line(&record), layout) {
+            Ok((name, params)) => {
+                let result = calculate_pph21_result(&params, Pph21Method::Progressive, config);
+                log::info!("Baris {}: {} diproses, PPh 21 sebulan {}", record_line(&record), name, result.monthly_tax);
+                writer
+                    .write_record([
+                        name,
+                        params.gross_income.to_string(),
+                        params.is_married.to_string(),
+                        params.num_dependents.to_string(),
+                        result.ptkp.to_string(),
+                        result.pkp.to_string(),
+                        result.monthly_tax.to_string(),
+                        result.annual_tax.to_string(),
+                    ])
+                    .map_err(|err| Message::new("error.csv_write", &[&err]))?;
+                report.processed += 1;
+            },
+            Err(error) => {
+                log::warn!("Baris {} dilewati: {}", error.line, error.message);
+                report.skipped.push(error);
+            },
+        }
+    }
+    writer.flush().map_err(|err| Message::new("error.csv_write", &[&err]))?;
+    
+    Ok(report)
+}
+
+// Parse every row of `input` as run_batch would, without calculating or
+// writing anything; `processed` counts the valid rows and `skipped` holds
+// every invalid one
+pub fn validate_batch<R: io::Read>(input: R) -> Result<BatchReport, Message> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input);
+    let layout = Layout::from_headers(reader.headers().map_err(|err| Message::new("error.csv_read", &[&err]))?);
+    
+    let mut report = BatchReport { processed: 0, skipped: Vec::new() };
+    for record in reader.records() {
+        let record = record.map_err(|err| Message::new("error.csv_read", &[&err]))?;
+        match parse_employee(&record, record_line(&record), layout) {
+            Ok(_) => report.processed += 1,
+            Err(error) => report.skipped.push(error),
+        }
+    }
+    
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_computes_each_row() {
+        let input = "name,gross_income,is_married,num_dependents\nBudi,6000000,false,0\nSari,Rp25.000.000,true,3\n";
+        let mut output = Vec::new();
+        
+        let report = run_batch(input.as_bytes(), &mut output, &TaxConfig::default()).unwrap();
+        
+        assert_eq!(report, BatchReport { processed: 2, skipped: Vec::new() });
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "name,gross_income,is_married,num_dependents,ptkp,pkp,monthly_tax,annual_tax");
+        assert_eq!(lines[1], "Budi,6000000,false,0,54000000,14400000,60000,720000");
+    }
+
+    #[test]
+    fn test_batch_skips_malformed_rows_with_line_number() {
+        let input = "name,gross_income,is_married,num_dependents\nBudi,abc,false,0\nSari,6000000\nAni,6000000,maybe,0\nDewi,6000000,false,0\n";
+        let mut output = Vec::new();
+        
+        let report = run_batch(input.as_bytes(), &mut output, &TaxConfig::default()).unwrap();
+        
+        assert_eq!(report.processed, 1);
+        let lines: Vec<u64> = report.skipped.iter().map(|error| error.line).collect();
+        assert_eq!(lines, vec![2, 3, 4]);
+        assert!(report.skipped[0].message.to_string().contains("gross_income"));
+    }
+
+    #[test]
+    fn test_validate_matches_batch_without_output() {
+        let input = "name,gross_income,is_married,num_dependents\nBudi,abc,false,0\nSari,6000000\nDewi,6000000,false,0\n";
+        let mut output = Vec::new();
+        
+        let validated = validate_batch(input.as_bytes()).unwrap();
+        let ran = run_batch(input.as_bytes(), &mut output, &TaxConfig::default()).unwrap();
+        
+        assert_eq!(validated, ran);
+        assert_eq!(validated.skipped[0].field, Some("gross_income"));
+        assert_eq!(validated.skipped[1].field, None);
+    }
+}
@@ -0,0 +1,91 @@
+// Errors from reading and validating user input
+
+use std::fmt;
+use std::io;
+
+use crate::i18n::Message;
+
+// Process exit codes of the non-interactive commands. Invalid command-line
+// arguments also exit with EXIT_INVALID_INPUT, as clap does
+pub const EXIT_CHECK_FAILED: i32 = 1;
+pub const EXIT_INVALID_INPUT: i32 = 2;
+pub const EXIT_IO: i32 = 3;
+
+#[derive(Debug)]
+pub enum CalcError {
+    // Reading input failed or stdin was closed
+    Io(io::Error),
+    // A file named on the command line, or the config file, could not be
+    // read, parsed or written
+    File(Message),
+    // Input is not a number (or list) in the expected format
+    Parse(Message),
+    // Input parsed but lies outside the accepted range
+    OutOfRange(Message),
+    // An iterative solver stopped at its iteration limit; `residual` is the
+    // change in the last iteration
+    DidNotConverge { iterations: u32, residual: f64 },
+    // No input gives the requested result, such as a gross-up target net
+    // above any gross that a 100% bracket leaves
+    Unreachable(Message),
+    // The user entered q, or a 0 the prompt does not accept, to back out of a
+    // calculation
+    Cancelled,
+    // selfcheck or batch --validate ran and found a failure, already reported
+    // in its output
+    CheckFailed,
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for CalcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CalcError::Io(err) => Some(err),
+            CalcError::File(_)
+            | CalcError::Parse(_)
+            | CalcError::OutOfRange(_)
+            | CalcError::DidNotConverge { .. }
+            | CalcError::Unreachable(_)
+            | CalcError::Cancelled
+            | CalcError::CheckFailed => None,
+        }
+    }
+}
+
+impl CalcError {
+    // What went wrong, to show in either language; Display gives the
+    // Indonesian text
+    pub fn message(&self) -> Message {
+        match self {
+            CalcError::Io(err) => Message::new("error.read_input", &[err]),
+            CalcError::File(message) | CalcError::Parse(message) | CalcError::OutOfRange(message) | CalcError::Unreachable(message) => message.clone(),
+            CalcError::DidNotConverge { iterations, residual } => Message::new("error.did_not_converge", &[iterations, residual]),
+            CalcError::Cancelled => Message::new("error.cancelled", &[]),
+            CalcError::CheckFailed => Message::new("error.check_failed", &[]),
+        }
+    }
+    
+    // Exit code for a command that failed with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CalcError::Io(_) | CalcError::File(_) => EXIT_IO,
+            CalcError::Parse(_)
+            | CalcError::OutOfRange(_)
+            | CalcError::DidNotConverge { .. }
+            | CalcError::Unreachable(_)
+            | CalcError::Cancelled => EXIT_INVALID_INPUT,
+            CalcError::CheckFailed => EXIT_CHECK_FAILED,
+        }
+    }
+}
+
+impl From<io::Error> for CalcError {
+    fn from(err: io::Error) -> Self {
+        CalcError::Io(err)
+    }
+}
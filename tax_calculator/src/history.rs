@@ -0,0 +1,67 @@
+// Append-only audit trail of calculations, one JSON object per line
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Message;
+
+// Environment variable naming the history file when --history is not given
+pub const HISTORY_ENV_VAR: &str = "TAX_CALCULATOR_HISTORY";
+
+// One logged calculation; inputs and outputs keep the shape of the structs
+// they were serialized from
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    pub kind: String,
+    pub inputs: serde_json::Value,
+    pub outputs: serde_json::Value,
+}
+
+impl HistoryEntry {
+    // Entry stamped with the current local time
+    pub fn new<I: Serialize, O: Serialize>(kind: &str, inputs: &I, outputs: &O) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: Local::now().to_rfc3339(),
+            kind: kind.to_string(),
+            inputs: serde_json::to_value(inputs).expect("masukan perhitungan selalu dapat diserialisasi"),
+            outputs: serde_json::to_value(outputs).expect("hasil perhitungan selalu dapat diserialisasi"),
+        }
+    }
+}
+
+// Append `entry` as a single line, creating the file if needed
+pub fn append_entry(path: &Path, entry: &HistoryEntry) -> Result<(), Message> {
+    let line = serde_json::to_string(entry).expect("riwayat selalu dapat diserialisasi");
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|err| Message::new("error.history_open", &[&path.display(), &err]))?;
+    
+    writeln!(file, "{}", line).map_err(|err| Message::new("error.history_write", &[&path.display(), &err]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_entry_keeps_earlier_lines() {
+        let path = std::env::temp_dir().join(format!("tax_calculator_history_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        
+        append_entry(&path, &HistoryEntry::new("ppn", &[1_000_000.0], &110_000.0)).unwrap();
+        append_entry(&path, &HistoryEntry::new("ppn", &[2_000_000.0], &220_000.0)).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        let entries: Vec<HistoryEntry> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].outputs, serde_json::json!(220_000.0));
+    }
+}
@@ -0,0 +1,611 @@
+// Translations for the menu, results and error messages; Indonesian is the default
+
+use std::fmt;
+
+// Output language
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Lang {
+    #[default]
+    Id,
+    En,
+}
+
+impl Lang {
+    // Parse a language code such as "id" or "en"
+    pub fn parse(code: &str) -> Result<Lang, String> {
+        match code.trim().to_lowercase().as_str() {
+            "id" => Ok(Lang::Id),
+            "en" => Ok(Lang::En),
+            other => Err(format!("Bahasa '{}' tidak didukung (gunakan id atau en)", other)),
+        }
+    }
+}
+
+// (key, Indonesian, English)
+pub const MESSAGES: &[(&str, &str, &str)] = &[
+    ("app.title", "=== KALKULATOR PAJAK ===", "=== TAX CALCULATOR ==="),
+    ("app.goodbye", "Terima kasih telah menggunakan kalkulator pajak!", "Thank you for using the tax calculator!"),
+    ("menu.choose", "Pilih jenis perhitungan:", "Choose a calculation:"),
+    ("menu.1", "Hitung PPh 21 (Pegawai Tetap) - Gross", "PPh 21 (permanent employee) - Gross"),
+    ("menu.2", "Hitung PPh 21 (Pegawai Tetap) - Gross Up", "PPh 21 (permanent employee) - Gross Up"),
+    ("menu.3", "Hitung Pajak Penghasilan Umum", "General income tax"),
+    ("menu.4", "Hitung PPN (Pajak Pertambahan Nilai)", "PPN (value added tax)"),
+    ("menu.5", "Hitung Angsuran Pajak per Periode", "Tax installments per period"),
+    ("menu.6", "Hitung PPh 21 - Gross dan Gross Up Sekaligus", "PPh 21 - Gross and Gross Up together"),
+    ("menu.7", "Cek PPh 21 Hasil Hitungan Sendiri", "Check a self-computed PPh 21"),
+    ("menu.8", "Hitung PPh 21 (Pegawai Tetap) - Gross, Tarif Flat 0.75%", "PPh 21 (permanent employee) - Gross, flat 0.75% rate"),
+    ("menu.9", "Hitung PPh 21 (Pegawai Tetap) - TER Bulanan 2024", "PPh 21 (permanent employee) - 2024 monthly TER"),
+    ("menu.10", "Hitung PPh 23 (Jasa, Dividen, Royalti, Bunga)", "PPh 23 (services, dividends, royalties, interest)"),
+    ("menu.11", "Hitung PPh Final Pasal 4 ayat (2) (Sewa, Konstruksi, Tanah)", "Final PPh Article 4(2) (rental, construction, land)"),
+    ("menu.12", "Hitung PPh 21 atas Bonus/THR", "PPh 21 on a bonus/THR"),
+    ("menu.13", "Hitung PPN dan PPnBM (Barang Mewah)", "PPN and PPnBM (luxury goods)"),
+    ("menu.14", "Hitung PPN Faktur (Banyak Barang)", "Invoice PPN (multiple items)"),
+    ("menu.15", "Laporan Gaji Bersih (Take Home Pay)", "Take-home pay report"),
+    ("menu.16", "Bandingkan Skema Gross, Gross Up, dan Nett", "Compare Gross, Gross Up and Nett schemes"),
+    ("menu.17", "Hitung PKP dari Penghasilan Bersih", "PKP from after-tax income"),
+    ("menu.18", "Hitung PPh 21 Pegawai Tidak Tetap (Upah Harian)", "PPh 21 for daily-wage workers"),
+    ("menu.19", "Hitung PPh Final atas Pesangon", "Final PPh on severance pay"),
+    ("menu.20", "Ganti Mode Gaji ke-13/THR (12 atau 13 kali gaji setahun)", "Toggle 13th-month salary mode (12 or 13 salaries a year)"),
+    ("menu.21", "Hitung PPh 26 (Wajib Pajak Luar Negeri)", "PPh 26 (non-resident taxpayers)"),
+    ("menu.22", "Ringkasan SPT Tahunan PPh 21", "Annual PPh 21 SPT summary"),
+    ("menu.23", "Bandingkan PPh 21 Antar Tahun Pajak", "Compare PPh 21 across tax years"),
+    ("menu.24", "Hitung PPh 21 (Pegawai Tetap) - Nett, Pajak Ditanggung Perusahaan", "PPh 21 (permanent employee) - Nett, tax paid by the company"),
+    ("menu.25", "Hitung PPh 21 Desember (Perhitungan Ulang Setahun)", "December PPh 21 (full-year recalculation)"),
+    ("menu.26", "Hitung PPh 21 Komisaris/Direksi Bukan Pegawai", "PPh 21 for non-employee commissioners/directors"),
+    ("menu.27", "Keluar", "Exit"),
+    ("menu.invalid", "Pilihan tidak valid. Silakan pilih 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, atau 27.", "Invalid choice. Please choose 1 to 27."),
+    ("menu.cancel_hint", "(Ketik q pada isian mana pun untuk kembali ke menu utama; 0 juga bisa bila 0 bukan jawaban yang sah)", "(Enter q at any prompt to return to the main menu; 0 also works where 0 is not a valid answer)"),
+    ("menu.cancelled", "Perhitungan dibatalkan, kembali ke menu utama.", "Calculation cancelled, back to the main menu."),
+    ("menu.help_hint", "(Ketik h untuk penjelasan setiap pilihan)", "(Enter h for an explanation of each option)"),
+    ("help.title", "=== PENJELASAN PILIHAN MENU ===", "=== MENU OPTIONS EXPLAINED ==="),
+    ("help.1", "Karyawan menanggung sendiri PPh 21 yang dipotong dari gaji bruto, dengan tarif progresif Pasal 17. Isian: gaji bruto sebulan, status kawin, NPWP, dan tanggungan, zakat, lembur, iuran pensiun, dan bulan bekerja.", "The employee bears the PPh 21 withheld from gross pay, at the progressive Article 17 rates. Inputs: monthly gross pay, marital status, NPWP and dependents, zakat, overtime, pension contribution and months worked."),
+    ("help.2", "Perusahaan memberi tunjangan pajak sebesar PPh 21-nya sendiri, sehingga karyawan menerima gaji bersih yang dijanjikan. Isian: gaji bersih yang diinginkan dan status kawin, NPWP, dan tanggungan.", "The company pays a tax allowance equal to the PPh 21 on it, so the employee receives the promised net pay. Inputs: desired net pay and marital status, NPWP and dependents."),
+    ("help.3", "Pajak penghasilan dengan tarif progresif atas penghasilan kena pajak yang sudah diketahui. Isian: PKP.", "Income tax at the progressive rates on a known taxable income. Inputs: PKP (taxable income)."),
+    ("help.4", "PPN atas harga barang atau jasa, dengan tarif standar, 0%, atau dibebaskan. Isian: harga, termasuk PPN atau belum, jenis dan persentase PPN.", "PPN on the price of goods or services, at the standard rate, 0% or exempt. Inputs: price, whether it includes PPN, PPN type and percentage."),
+    ("help.5", "Membagi pajak setahun menjadi angsuran per periode. Isian: estimasi pajak setahun dan jumlah periode.", "Splits an annual tax into per-period installments. Inputs: estimated annual tax and number of periods."),
+    ("help.6", "Skema Gross dan Gross Up untuk gaji yang sama, berdampingan. Isian: gaji sebulan dan status kawin, NPWP, dan tanggungan.", "The Gross and Gross Up schemes side by side for the same salary. Inputs: monthly salary and marital status, NPWP and dependents."),
+    ("help.7", "Membandingkan PPh 21 hasil hitungan Anda dengan hasil kalkulator. Isian: gaji bruto sebulan, status kawin, NPWP, dan tanggungan, dan PPh 21 hitungan Anda.", "Compares your own PPh 21 figure with the calculator's. Inputs: monthly gross pay, marital status, NPWP and dependents and your PPh 21."),
+    ("help.8", "Seperti pilihan 1 (Gross), tetapi dengan tarif lama flat 0.75% dari gaji bruto. Isian: sama dengan pilihan 1.", "Like option 1 (Gross), but with the legacy flat 0.75% of gross pay. Inputs: as for option 1."),
+    ("help.9", "PPh 21 bulanan dengan Tarif Efektif Rata-rata (TER) 2024 sesuai kategori PTKP. Isian: gaji bruto sebulan dan status kawin, NPWP, dan tanggungan.", "Monthly PPh 21 at the 2024 average effective rates (TER) for the PTKP category. Inputs: monthly gross pay and marital status, NPWP and dependents."),
+    ("help.10", "PPh 23 yang dipotong atas jasa, dividen, royalti, atau bunga. Isian: jumlah bruto dan objek PPh 23.", "PPh 23 withheld on services, dividends, royalties or interest. Inputs: gross amount and PPh 23 object."),
+    ("help.11", "PPh final atas sewa, jasa konstruksi, atau pengalihan tanah dan bangunan. Isian: objek pajak dan nilai bruto.", "Final PPh on rental, construction services or land and building transfers. Inputs: tax object and gross amount."),
+    ("help.12", "Tambahan PPh 21 karena bonus atau THR, dihitung dari selisih pajak setahun dengan dan tanpa bonus. Isian: gaji bruto sebulan, status kawin, NPWP, dan tanggungan, dan jumlah bonus.", "The extra PPh 21 from a bonus or THR, the difference in annual tax with and without it. Inputs: monthly gross pay, marital status, NPWP and dependents and bonus amount."),
+    ("help.13", "PPN ditambah PPnBM untuk barang mewah. Isian: harga jual, persentase PPN, dan persentase PPnBM.", "PPN plus PPnBM for luxury goods. Inputs: selling price, PPN percentage and PPnBM percentage."),
+    ("help.14", "PPN untuk faktur berisi banyak barang. Isian: jumlah dan harga satuan setiap barang, serta persentase PPN.", "PPN for an invoice with several items. Inputs: quantity and unit price of each item, and the PPN percentage."),
+    ("help.15", "Rincian dari gaji bruto sampai take home pay: biaya jabatan, BPJS, PTKP, PKP, dan PPh 21. Isian: gaji bruto sebulan dan status kawin, NPWP, dan tanggungan.", "Breakdown from gross pay to take-home pay: biaya jabatan, BPJS, PTKP, PKP and PPh 21. Inputs: monthly gross pay and marital status, NPWP and dependents."),
+    ("help.16", "Biaya perusahaan dan gaji diterima pada skema Gross, Gross Up, dan Nett. Isian: gaji yang disepakati dan status kawin, NPWP, dan tanggungan.", "Company cost and take-home pay under the Gross, Gross Up and Nett schemes. Inputs: agreed salary and marital status, NPWP and dependents."),
+    ("help.17", "Mencari PKP yang menyisakan penghasilan bersih tertentu setelah pajak. Isian: penghasilan bersih setelah pajak.", "Finds the PKP that leaves a given income after tax. Inputs: after-tax income."),
+    ("help.18", "PPh 21 atas upah harian pegawai tidak tetap. Isian: upah hari ini dan upah kumulatif bulan ini.", "PPh 21 on a daily-wage worker's pay. Inputs: today's wage and the cumulative wage this month."),
+    ("help.19", "PPh final atas uang pesangon dengan tarif berlapis. Isian: jumlah pesangon bruto.", "Final PPh on severance pay at tiered rates. Inputs: gross severance amount."),
+    ("help.20", "Mengganti jumlah gaji setahun antara 12 dan 13 kali (gaji ke-13/THR) untuk perhitungan PPh 21 berikutnya. Tanpa isian.", "Switches between 12 and 13 salaries a year (13th-month pay/THR) for later PPh 21 calculations. No inputs."),
+    ("help.21", "PPh 26 atas pembayaran ke wajib pajak luar negeri, dengan tarif umum atau tarif P3B. Isian: jumlah bruto dan tarif.", "PPh 26 on payments to non-resident taxpayers, at the general or a tax treaty rate. Inputs: gross amount and rate."),
+    ("help.22", "Ringkasan SPT tahunan: PPh 21 setahun dibandingkan dengan potongan bulanan, kurang atau lebih bayar. Isian: gaji bruto tiap bulan, status kawin, NPWP, dan tanggungan, zakat, dan metode.", "Annual SPT summary: the annual PPh 21 against the monthly withholding, underpaid or overpaid. Inputs: gross pay for each month, marital status, NPWP and dependents, zakat and method."),
+    ("help.23", "PPh 21 yang sama dihitung dengan tabel setiap tahun pajak yang didukung. Isian: gaji bruto sebulan dan status kawin, NPWP, dan tanggungan.", "The same PPh 21 under each supported tax year's tables. Inputs: monthly gross pay and marital status, NPWP and dependents."),
+    ("help.24", "Perusahaan membayar PPh 21 di luar gaji tanpa tunjangan pajak, sehingga karyawan menerima gaji bruto penuh. Isian: gaji bruto sebulan dan status kawin, NPWP, dan tanggungan.", "The company pays the PPh 21 on top of the salary without a tax allowance, so the employee receives the full gross. Inputs: monthly gross pay and marital status, NPWP and dependents."),
+    ("help.25", "Di bulan Desember pajak setahun dihitung ulang; PPh 21 Desember adalah pajak setahun dikurangi potongan Januari-November, atau kelebihan yang dikembalikan. Isian: gaji bruto sebulan, status kawin, NPWP, dan tanggungan, serta PPh 21 yang sudah dipotong.", "In December the full-year tax is recalculated; December PPh 21 is the annual tax less the January-November withholding, or the excess to return. Inputs: monthly gross pay, marital status, NPWP and dependents, and the PPh 21 already withheld."),
+    ("help.26", "Honorarium komisaris atau direksi yang bukan pegawai dikenai tarif Pasal 17 atas bruto kumulatif setahun, tanpa biaya jabatan dan PTKP, sehingga tarifnya naik seiring jumlah yang sudah dibayar. Isian: honor yang sudah dibayar tahun ini dan honor saat ini.", "Honoraria of commissioners or directors who are not employees are taxed at the Pasal 17 rates on the year's cumulative gross, with no biaya jabatan or PTKP, so the rate rises with the amount already paid. Inputs: honoraria already paid this year and the current payment."),
+    ("section.gross", "=== Perhitungan PPh 21 (Pegawai Tetap) - Gross ===", "=== PPh 21 (Permanent Employee) - Gross ==="),
+    ("section.gross_up", "=== Perhitungan PPh 21 (Pegawai Tetap) - Gross Up ===", "=== PPh 21 (Permanent Employee) - Gross Up ==="),
+    ("section.income_tax", "=== Perhitungan Pajak Penghasilan Umum ===", "=== General Income Tax ==="),
+    ("section.vat", "=== Perhitungan PPN (Pajak Pertambahan Nilai) ===", "=== PPN (Value Added Tax) ==="),
+    ("section.installments", "=== Perhitungan Angsuran Pajak per Periode ===", "=== Tax Installments per Period ==="),
+    ("section.gross_and_gross_up", "=== Perhitungan PPh 21 (Pegawai Tetap) - Gross dan Gross Up ===", "=== PPh 21 (Permanent Employee) - Gross and Gross Up ==="),
+    ("section.self_check", "=== Cek PPh 21 Hasil Hitungan Sendiri ===", "=== Check a Self-Computed PPh 21 ==="),
+    ("section.ter", "=== Perhitungan PPh 21 (Pegawai Tetap) - TER Bulanan ===", "=== PPh 21 (Permanent Employee) - Monthly TER ==="),
+    ("section.pph23", "=== Perhitungan PPh 23 ===", "=== PPh 23 ==="),
+    ("section.pph_final_42", "=== Perhitungan PPh Final Pasal 4 ayat (2) ===", "=== Final PPh Article 4(2) ==="),
+    ("section.bonus", "=== Perhitungan PPh 21 atas Bonus/THR ===", "=== PPh 21 on a Bonus/THR ==="),
+    ("section.ppnbm", "=== Perhitungan PPN dan PPnBM ===", "=== PPN and PPnBM ==="),
+    ("section.invoice", "=== Perhitungan PPN Faktur ===", "=== Invoice PPN ==="),
+    ("section.payroll", "=== Laporan Gaji Bersih (Take Home Pay) ===", "=== Take-Home Pay Report ==="),
+    ("section.schemes", "=== Perbandingan Skema Gross, Gross Up, dan Nett ===", "=== Gross, Gross Up and Nett Schemes ==="),
+    ("section.income_tax_from_net", "=== Perhitungan PKP dari Penghasilan Bersih ===", "=== PKP from After-Tax Income ==="),
+    ("section.daily", "=== Perhitungan PPh 21 Pegawai Tidak Tetap (Upah Harian) ===", "=== PPh 21 for Daily-Wage Workers ==="),
+    ("section.pesangon", "=== Perhitungan PPh Final atas Pesangon ===", "=== Final PPh on Severance Pay ==="),
+    ("section.pph26", "=== Perhitungan PPh 26 (Wajib Pajak Luar Negeri) ===", "=== PPh 26 for Non-Residents ==="),
+    ("section.annual_summary", "=== Ringkasan SPT Tahunan PPh 21 ===", "=== Annual PPh 21 SPT Summary ==="),
+    ("section.compare_years", "=== Perbandingan PPh 21 Antar Tahun Pajak ===", "=== PPh 21 Across Tax Years ==="),
+    ("section.nett", "=== Perhitungan PPh 21 (Pegawai Tetap) - Nett ===", "=== PPh 21 (Permanent Employee) - Nett ==="),
+    ("section.december", "=== Perhitungan PPh 21 Masa Desember ===", "=== December PPh 21 ==="),
+    ("section.non_employee", "=== Perhitungan PPh 21 Komisaris/Direksi Bukan Pegawai ===", "=== PPh 21 for Non-Employee Commissioners/Directors ==="),
+    ("prompt.gross_monthly", "Masukkan Penghasilan Bruto per bulan (Rp):", "Enter monthly gross income (Rp):"),
+    ("prompt.marital", "Status Perkawinan:\n1. Belum Kawin\n2. Kawin\n3. Kawin, penghasilan istri digabung (K/I)", "Marital status:\n1. Single\n2. Married\n3. Married, wife's income combined (K/I)"),
+    ("prompt.npwp", "Memiliki NPWP? (y/n, default y):", "Has an NPWP (tax ID)? (y/n, default y):"),
+    ("prompt.dependents", "Jumlah Tanggungan (anak/kondisi lain), atau usia tiap tanggungan dipisah koma (contoh: 8,12,15):", "Number of dependents, or each dependent's age separated by commas (e.g. 8,12,15):"),
+    ("prompt.zakat", "Zakat penghasilan setahun melalui BAZNAS/LAZ resmi (Rp, kosongkan jika tidak ada):", "Annual zakat paid through an approved institution (Rp, leave empty if none):"),
+    ("prompt.overtime", "Uang lembur per bulan (Rp, kosongkan jika tidak ada):", "Monthly overtime pay (Rp, leave empty if none):"),
+    ("prompt.pension", "Iuran dana pensiun per bulan (Rp, kosongkan jika tidak ada):", "Monthly pension fund contribution (Rp, leave empty if none):"),
+    ("prompt.start_month", "Bulan mulai bekerja tahun ini (1-12, kosongkan jika bekerja setahun penuh):", "Month you started work this year (1-12, leave empty for a full year):"),
+    ("prompt.start_day", "Tanggal mulai bekerja bulan ini (1-31, kosongkan jika bekerja sebulan penuh):", "Day you started work this month (1-31, leave empty for a full month):"),
+    ("prompt.net_monthly", "Masukkan gaji bersih yang diinginkan per bulan (dalam Rupiah):", "Enter the desired monthly net pay (in Rupiah):"),
+    ("prompt.pkp", "Masukkan penghasilan kena pajak (dalam Rupiah):", "Enter the taxable income (in Rupiah):"),
+    ("prompt.round_pkp", "Bulatkan PKP ke bawah ke ribuan penuh? (y/n, default y):", "Round PKP down to whole thousands? (y/n, default y):"),
+    ("prompt.price", "Masukkan jumlah harga (dalam Rupiah):", "Enter the price (in Rupiah):"),
+    ("prompt.vat_mode", "Harga yang dimasukkan:\n1. Belum termasuk PPN\n2. Sudah termasuk PPN", "The price entered:\n1. Excludes PPN\n2. Includes PPN"),
+    ("prompt.vat_type", "Jenis PPN:\n1. Tarif standar\n2. Tarif 0% (ekspor)\n3. Dibebaskan", "PPN type:\n1. Standard rate\n2. 0% rate (export)\n3. Exempt"),
+    ("prompt.vat_rate", "Masukkan persentase PPN (default {}%):", "Enter the PPN percentage (default {}%):"),
+    ("prompt.rate_change_month", "Bulan mulai berlaku tarif baru (1-12, kosongkan jika tarif tidak berubah):", "Month the new rate takes effect (1-12, leave empty if the rate does not change):"),
+    ("prompt.new_vat_rate", "Masukkan persentase PPN baru:", "Enter the new PPN percentage:"),
+    ("prompt.annual_tax_estimate", "Masukkan estimasi pajak setahun (dalam Rupiah):", "Enter the estimated annual tax (in Rupiah):"),
+    ("prompt.installment_periods", "Jumlah periode angsuran (default 12, contoh: 4 untuk per kuartal):", "Number of installment periods (default 12, e.g. 4 for quarterly):"),
+    ("prompt.salary_monthly", "Masukkan gaji per bulan (dalam Rupiah):", "Enter the monthly salary (in Rupiah):"),
+    ("prompt.claimed_tax", "Masukkan PPh 21 per bulan hasil hitungan Anda (Rp):", "Enter your own monthly PPh 21 figure (Rp):"),
+    ("prompt.gross_amount", "Masukkan jumlah bruto (dalam Rupiah):", "Enter the gross amount (in Rupiah):"),
+    ("prompt.pph23_object", "Objek PPh 23:\n1. Jasa (2%)\n2. Dividen (15%)\n3. Royalti (15%)\n4. Bunga (15%)", "PPh 23 object:\n1. Services (2%)\n2. Dividends (15%)\n3. Royalties (15%)\n4. Interest (15%)"),
+    ("prompt.payee_npwp", "Penerima penghasilan memiliki NPWP? (y/n, default y):", "Does the payee have an NPWP? (y/n, default y):"),
+    ("prompt.final_object", "Objek PPh Final:\n1. Sewa tanah/bangunan (10%)\n2. Jasa konstruksi, kualifikasi kecil (1.75%)\n3. Jasa konstruksi, kualifikasi menengah/besar (2.65%)\n4. Jasa konstruksi, tanpa kualifikasi (4%)\n5. Pengalihan hak atas tanah/bangunan (2.5%)", "Final PPh object:\n1. Land/building rental (10%)\n2. Construction services, small qualification (1.75%)\n3. Construction services, medium/large qualification (2.65%)\n4. Construction services, no qualification (4%)\n5. Transfer of land/building rights (2.5%)"),
+    ("prompt.gross_value", "Masukkan nilai bruto (dalam Rupiah):", "Enter the gross value (in Rupiah):"),
+    ("prompt.bonus", "Masukkan jumlah bonus/THR (Rp):", "Enter the bonus/THR amount (Rp):"),
+    ("prompt.selling_price", "Masukkan harga jual sebelum pajak (dalam Rupiah):", "Enter the selling price before tax (in Rupiah):"),
+    ("prompt.ppnbm_rate", "Masukkan persentase PPnBM (0-200%):", "Enter the PPnBM percentage (0-200%):"),
+    ("prompt.invoice_item", "Barang/jasa ke-{} (kosongkan untuk selesai):", "Item {} (leave empty to finish):"),
+    ("prompt.quantity", "Jumlah:", "Quantity:"),
+    ("prompt.unit_price", "Harga satuan sebelum PPN (Rp):", "Unit price before PPN (Rp):"),
+    ("prompt.agreed_salary", "Masukkan gaji yang disepakati per bulan (dalam Rupiah):", "Enter the agreed monthly salary (in Rupiah):"),
+    ("prompt.net_after_tax", "Masukkan penghasilan bersih setelah pajak (dalam Rupiah):", "Enter the income after tax (in Rupiah):"),
+    ("prompt.daily_wage", "Masukkan upah hari ini (dalam Rupiah):", "Enter today's wage (in Rupiah):"),
+    ("prompt.cumulative_wage", "Masukkan total upah bulan ini sampai hari ini, termasuk upah hari ini (dalam Rupiah):", "Enter this month's total wages up to today, including today's wage (in Rupiah):"),
+    ("prompt.severance", "Masukkan jumlah pesangon bruto (dalam Rupiah):", "Enter the gross severance amount (in Rupiah):"),
+    ("prompt.foreign_gross", "Masukkan jumlah bruto yang dibayarkan ke luar negeri (dalam Rupiah):", "Enter the gross amount paid abroad (in Rupiah):"),
+    ("prompt.pph26_rate", "Tarif PPh 26 sesuai P3B (%, kosongkan untuk tarif umum {}%):", "PPh 26 rate under a tax treaty (%, leave empty for the general {}% rate):"),
+    ("prompt.same_every_month", "Gaji bruto sama setiap bulan? (y/n, default y):", "Same gross pay every month? (y/n, default y):"),
+    ("prompt.gross_for_month", "Gaji bruto bulan {} (Rp):", "Gross pay for month {} (Rp):"),
+    ("prompt.method", "Metode pemotongan bulanan:\n1. Tarif progresif (default)\n2. Tarif flat {}%", "Monthly withholding method:\n1. Progressive rates (default)\n2. Flat {}% rate"),
+    ("prompt.ytd_withheld", "Masukkan PPh 21 yang sudah dipotong Januari-November (dalam Rupiah):", "Enter the PPh 21 already withheld January-November (in Rupiah):"),
+    ("prompt.prior_honoraria", "Masukkan honor yang sudah dibayar tahun ini sebelum pembayaran ini (Rp, kosongkan jika belum ada):", "Enter the honoraria already paid this year before this payment (Rp, leave empty if none):"),
+    ("prompt.current_honorarium", "Masukkan honor yang dibayarkan saat ini (dalam Rupiah):", "Enter the honorarium paid now (in Rupiah):"),
+    ("note.flat_rate", "* Menggunakan tarif flat {}% dari gaji bruto", "* Using a flat rate of {}% of gross pay"),
+    ("note.employee_bears_tax", "* Karyawan menanggung sendiri pajak penghasilannya", "* The employee bears their own income tax"),
+    ("note.company_bears_tax", "* Perusahaan menanggung beban pajak karyawan", "* The company bears the employee's tax"),
+    ("note.gross_and_gross_up", "* Gaji dihitung sebagai bruto (skema Gross) dan sebagai gaji bersih (skema Gross Up)", "* The salary is taken as gross pay (Gross scheme) and as net pay (Gross Up scheme)"),
+    ("note.ter", "* Tarif Efektif Rata-rata (PP 58/2023) x penghasilan bruto sebulan", "* Average effective rate (PP 58/2023) x monthly gross income"),
+    ("note.ter_december", "* TER dipakai untuk masa Januari-November; masa Desember dihitung ulang dengan tarif Pasal 17 setahun", "* TER applies from January to November; December is recalculated at the annual Pasal 17 rates"),
+    ("note.bonus", "* Selisih PPh 21 setahun dengan dan tanpa bonus (tarif progresif)", "* The difference in annual PPh 21 with and without the bonus (progressive rates)"),
+    ("note.schemes", "* Gross: karyawan menanggung pajak; Gross Up: perusahaan memberi tunjangan pajak; Nett: perusahaan membayar pajak tanpa tunjangan", "* Gross: the employee bears the tax; Gross Up: the company pays a tax allowance; Nett: the company pays the tax without an allowance"),
+    ("note.daily_over_threshold", "* Upah bulan ini melebihi {}, dikurangi PTKP harian {}", "* This month's wages exceed {}, less the daily PTKP of {}"),
+    ("note.daily_under_threshold", "* Tidak dipotong pajak sampai {} per hari", "* No tax is withheld up to {} a day"),
+    ("note.nett", "* Perusahaan membayar PPh 21 tanpa tunjangan pajak; pajak dihitung dari gaji bruto saja", "* The company pays the PPh 21 without a tax allowance; the tax is on the gross salary alone"),
+    ("note.december", "* Pajak setahun dihitung ulang dan dikurangi PPh 21 Januari-November", "* The annual tax is recalculated, less the January-November PPh 21"),
+    ("note.non_employee", "* Tarif Pasal 17 atas honor kumulatif setahun, tanpa biaya jabatan dan PTKP", "* Pasal 17 rates on the year's cumulative honoraria, with no biaya jabatan or PTKP"),
+    ("note.dependent_ages", "Usia tanggungan: {} tahun", "Dependents' ages: {} years"),
+    ("note.ptkp_dependents_capped", "Catatan: PTKP dihitung untuk maksimal {} tanggungan, jadi {} tanggungan dihitung sebagai {}.", "Note: PTKP counts at most {} dependents, so {} dependents count as {}."),
+    ("note.outdated_tables", "Peringatan: tabel pajak bawaan untuk tahun {}, sedangkan sekarang tahun {}. Tarif/PTKP mungkin sudah tidak berlaku.", "Warning: the built-in tax tables are for {}, but the current year is {}. The rates/PTKP may no longer apply."),
+    ("note.thirteenth_on", "Mode gaji ke-13 aktif: penghasilan setahun dihitung dari 13 kali gaji bulanan", "13th-month salary mode on: annual income is 13 times the monthly salary"),
+    ("note.thirteenth_off", "Mode gaji ke-13 nonaktif: penghasilan setahun dihitung dari 12 kali gaji bulanan", "13th-month salary mode off: annual income is 12 times the monthly salary"),
+    ("note.deductions_exceed_net", "Peringatan: potongan setelah pajak ({}) melebihi gaji bersih ({}); take home pay dihitung Rp 0.", "Warning: the post-tax deductions ({}) are more than the net pay ({}); take-home pay is shown as Rp 0."),
+    ("result.request_code", "Kode perhitungan (--request): {}", "Calculation code (--request): {}"),
+    ("result.first_month", "[Bulan Pertama (Proporsional {}%)]", "[First Month (Prorated {}%)]"),
+    ("result.income_tax", "Hasil Perhitungan Pajak Penghasilan:", "Income Tax Result:"),
+    ("result.bracket_breakdown", "Rincian per lapisan tarif:", "Breakdown by tax bracket:"),
+    ("result.marginal_rate", "Tarif marginal: {}%", "Marginal rate: {}%"),
+    ("result.installments", "Hasil Perhitungan Angsuran ({} periode):", "Installments ({} periods):"),
+    ("result.matches", "HASIL SESUAI (selisih maksimal {})", "RESULT MATCHES (difference of at most {})"),
+    ("result.mismatch", "HASIL TIDAK SESUAI", "RESULT DOES NOT MATCH"),
+    ("result.correct_worksheet", "Rincian perhitungan yang benar:", "The correct calculation:"),
+    ("result.ter", "=== HASIL PERHITUNGAN PPh 21 (TER) ===", "=== PPh 21 RESULT (TER) ==="),
+    ("result.pph23", "Hasil Perhitungan PPh 23 ({}):", "PPh 23 Result ({}):"),
+    ("result.rate", "Tarif: {}%", "Rate: {}%"),
+    ("result.rate_no_npwp", "Tarif: {}% (100% lebih tinggi, tanpa NPWP)", "Rate: {}% (100% higher, no NPWP)"),
+    ("result.rate_treaty", "Tarif: {}% (tarif P3B)", "Rate: {}% (tax treaty rate)"),
+    ("result.pph_final", "Hasil Perhitungan PPh Final ({}):", "Final PPh Result ({}):"),
+    ("result.bonus", "=== HASIL PERHITUNGAN PPh 21 BONUS/THR ===", "=== PPh 21 ON BONUS/THR RESULT ==="),
+    ("result.ppnbm", "Hasil Perhitungan PPN dan PPnBM:", "PPN and PPnBM Result:"),
+    ("result.empty_invoice", "Faktur tidak memiliki barang/jasa.", "The invoice has no items."),
+    ("result.invoice", "Faktur:", "Invoice:"),
+    ("result.pkp", "Hasil Perhitungan PKP:", "PKP Result:"),
+    ("result.daily", "Hasil Perhitungan PPh 21 Upah Harian:", "Daily-Wage PPh 21 Result:"),
+    ("result.pesangon", "Hasil Perhitungan PPh Final Pesangon:", "Final PPh on Severance Result:"),
+    ("result.pph26", "Hasil Perhitungan PPh 26:", "PPh 26 Result:"),
+    ("result.year_comparison", "Tarif progresif dengan tabel bawaan tiap tahun pajak:", "Progressive rates with each tax year's built-in tables:"),
+    ("result.nett", "Hasil Perhitungan PPh 21 Nett:", "Nett PPh 21 Result:"),
+    ("result.paid_by_company", "(ditanggung perusahaan)", "(paid by the company)"),
+    ("result.december", "Hasil Perhitungan PPh 21 Desember:", "December PPh 21 Result:"),
+    ("result.non_employee", "Hasil Perhitungan PPh 21 Komisaris/Direksi:", "Commissioner/Director PPh 21 Result:"),
+    ("result.pph21_summary", "Perhitungan PPh 21", "PPh 21 Calculation"),
+    ("result.rounded_monthly_tax", "PPh 21 Sebulan (dibulatkan per {}):", "Monthly PPh 21 (rounded to {}):"),
+    ("result.exchange_rate", "Kurs: {} per {}", "Exchange rate: {} per {}"),
+    ("label.monthly_tax", "PPh 21 Sebulan", "Monthly PPh 21"),
+    ("label.annual_tax", "PPh 21 Setahun", "Annual PPh 21"),
+    ("label.december_tax", "PPh 21 Desember", "December PPh 21"),
+    ("label.underpaid", "PPh 21 Kurang Bayar", "PPh 21 Underpaid"),
+    ("label.overpaid", "PPh 21 Lebih Bayar (refund)", "PPh 21 Overpaid (refund)"),
+    ("label.this_month_tax", "PPh 21 Bulan Ini", "PPh 21 This Month"),
+    ("label.pph21_withheld", "PPh 21 dipotong", "PPh 21 withheld"),
+    ("label.bonus_tax", "PPh 21 atas Bonus/THR", "PPh 21 on Bonus/THR"),
+    ("label.pph23_withheld", "PPh 23 dipotong", "PPh 23 withheld"),
+    ("label.pph26_withheld", "PPh 26 dipotong", "PPh 26 withheld"),
+    ("label.final_tax", "PPh Final:", "Final PPh:"),
+    ("label.vat", "PPN:", "PPN:"),
+    ("label.annual_vat", "PPN setahun (dibagi rata per bulan)", "Annual PPN (averaged per month)"),
+    ("label.take_home", "Take Home Pay", "Take Home Pay"),
+    ("label.net_monthly", "Neto Sebulan", "Monthly Net"),
+    ("label.month_pay", "Gaji Bulan Ini", "Pay This Month"),
+    ("label.taxable_income", "Penghasilan Kena Pajak", "Taxable Income"),
+    ("label.pkp_rounded", "PKP dibulatkan", "Rounded PKP"),
+    ("label.tax_due", "Pajak yang harus dibayar", "Tax payable"),
+    ("label.net_income", "Penghasilan Bersih", "Net Income"),
+    ("label.income_tax", "Pajak Penghasilan", "Income Tax"),
+    ("label.claimed_tax", "PPh 21 hasil Anda", "Your PPh 21"),
+    ("label.expected_tax", "PPh 21 hasil kalkulator", "Calculator's PPh 21"),
+    ("label.difference", "Selisih (hasil Anda - kalkulator)", "Difference (yours - calculator)"),
+    ("label.gross_monthly", "Penghasilan Bruto per bulan", "Gross income per month"),
+    ("label.ptkp_status", "Status PTKP", "PTKP status"),
+    ("label.ter_category", "Kategori TER", "TER category"),
+    ("label.effective_rate", "Tarif Efektif", "Effective rate"),
+    ("label.gross_amount", "Jumlah bruto", "Gross amount"),
+    ("label.amount_received", "Jumlah diterima", "Amount received"),
+    ("label.gross_value", "Nilai bruto", "Gross value"),
+    ("label.bonus", "Bonus/THR", "Bonus/THR"),
+    ("label.annual_tax_without_bonus", "PPh 21 Setahun tanpa bonus", "Annual PPh 21 without bonus"),
+    ("label.annual_tax_with_bonus", "PPh 21 Setahun dengan bonus", "Annual PPh 21 with bonus"),
+    ("label.net_bonus", "Bonus/THR Bersih", "Net Bonus/THR"),
+    ("label.price_before_tax", "Harga sebelum pajak", "Price before tax"),
+    ("label.price_before_vat", "Harga sebelum PPN", "Price before PPN"),
+    ("label.total_due", "Total yang harus dibayar", "Total payable"),
+    ("label.daily_wage", "Upah hari ini", "Today's wage"),
+    ("label.wage_received", "Upah diterima", "Wage received"),
+    ("label.gross_severance", "Pesangon bruto", "Gross severance"),
+    ("label.bracket_rate", "Tarif {}%", "Rate {}%"),
+    ("label.severance_received", "Pesangon diterima", "Severance received"),
+    ("label.monthly_gross_pay", "Gaji Bruto Sebulan", "Monthly Gross Pay"),
+    ("label.annual_gross_pay", "Gaji Bruto Setahun", "Annual Gross Pay"),
+    ("label.company_cost", "Biaya Perusahaan", "Company Cost"),
+    ("label.withheld_jan_nov", "Dipotong Jan-Nov", "Withheld Jan-Nov"),
+    ("label.prior_honoraria", "Honor sebelumnya", "Prior honoraria"),
+    ("label.current_honorarium", "Honor saat ini", "Current honorarium"),
+    ("label.cumulative_honoraria", "Honor kumulatif", "Cumulative honoraria"),
+    ("label.annual_salary", "Gaji Setahun", "Annual Salary"),
+    ("label.biaya_jabatan", "Biaya Jabatan", "Job Expense"),
+    ("label.pension", "Iuran Pensiun", "Pension Contribution"),
+    ("label.dependents", "Jumlah Tanggungan", "Dependents"),
+    ("label.annual_overtime", "Uang Lembur Setahun", "Annual Overtime"),
+    ("label.overtime", "Uang Lembur", "Overtime"),
+    ("label.fingerprint", "Sidik Jari Tabel", "Tables Fingerprint"),
+    ("label.net_pay", "Gaji Bersih (Take Home Pay)", "Net Pay (Take Home Pay)"),
+    ("label.annual_net_pay", "Gaji Bersih Setahun", "Annual Net Pay"),
+    ("label.gross_up_pay", "Gaji Kotor (Gross Up)", "Gross Pay (Gross Up)"),
+    ("label.annual_gross_up_pay", "Gaji Kotor Setahun", "Annual Gross Pay"),
+    ("label.employee_share", "Diterima karyawan (sama dengan skema Gross)", "Employee receives (as under Gross)"),
+    ("label.company_extra_cost", "Tambahan biaya perusahaan vs skema Gross", "Extra company cost vs the Gross scheme"),
+    ("label.per_month", "per bulan", "per month"),
+    ("label.per_year", "per tahun", "per year"),
+    ("label.overtime_monthly", "Uang Lembur per bulan", "Overtime per month"),
+    ("label.annual_biaya_jabatan", "Biaya Jabatan Setahun", "Annual Job Expense"),
+    ("label.bukti_potong", "Nomor Bukti Potong", "Withholding Slip Number"),
+    ("marital.single", "Belum Kawin", "Single"),
+    ("marital.married", "Kawin", "Married"),
+    ("marital.combined", "Kawin, penghasilan istri digabung", "Married, wife's income combined"),
+    ("object.services", "Jasa", "Services"),
+    ("object.dividends", "Dividen", "Dividends"),
+    ("object.royalties", "Royalti", "Royalties"),
+    ("object.interest", "Bunga", "Interest"),
+    ("object.rental", "Sewa Tanah/Bangunan", "Land/Building Rental"),
+    ("object.construction_small", "Jasa Konstruksi (Kualifikasi Kecil)", "Construction Services (Small Qualification)"),
+    ("object.construction_medium_large", "Jasa Konstruksi (Kualifikasi Menengah/Besar)", "Construction Services (Medium/Large Qualification)"),
+    ("object.construction_unqualified", "Jasa Konstruksi (Tanpa Kualifikasi)", "Construction Services (No Qualification)"),
+    ("object.land_sale", "Pengalihan Hak atas Tanah/Bangunan", "Transfer of Land/Building Rights"),
+    ("result.vat", "Hasil Perhitungan PPN ({}):", "PPN Result ({}):"),
+    ("vat.zero_rated", "Tarif 0%", "0% rate"),
+    ("vat.exempt", "Dibebaskan", "Exempt"),
+    ("vat.rate_change", "[Perubahan tarif {}% -> {}% mulai bulan {}]", "[Rate change {}% -> {}% from month {}]"),
+    ("worksheet.gross", "=== HASIL PERHITUNGAN PPh 21 ===", "=== PPh 21 RESULT ==="),
+    ("worksheet.overtime_monthly", "Uang Lembur per bulan:", "Overtime per month:"),
+    ("worksheet.gross_annual", "Penghasilan Bruto setahun:", "Gross income per year:"),
+    ("worksheet.thirteenth", "* Termasuk gaji ke-13 ({} kali gaji setahun)", "* Includes a 13th-month salary ({} salaries a year)"),
+    ("worksheet.mid_year", "* Mulai bekerja di tengah tahun: masa kerja {} bulan, PTKP dan biaya jabatan proporsional", "* Started mid-year: {} months worked, PTKP and job expense prorated"),
+    ("worksheet.ptkp", "[Penghasilan Tidak Kena Pajak (PTKP)]", "[Non-Taxable Income (PTKP)]"),
+    ("worksheet.biaya_jabatan", "[Biaya Jabatan]", "[Job Expense (Biaya Jabatan)]"),
+    ("worksheet.biaya_jabatan_rule", "5% x Gaji Setahun (maks. Rp 6.000.000)", "5% x Annual Salary (max. Rp 6.000.000)"),
+    ("worksheet.pkp", "[Penghasilan Kena Pajak (PKP)]", "[Taxable Income (PKP)]"),
+    ("worksheet.progressive", "[Perhitungan PPh 21 (Tarif Progresif Pasal 17 x PKP)]", "[PPh 21 Calculation (Progressive Pasal 17 Rates x PKP)]"),
+    ("worksheet.flat", "[Perhitungan PPh 21 ({}% x Gaji Bruto)]", "[PPh 21 Calculation ({}% x Gross Pay)]"),
+    ("worksheet.per_year", "Per Tahun:", "Per Year:"),
+    ("worksheet.per_month", "Per Bulan:", "Per Month:"),
+    ("worksheet.no_npwp", "* Tanpa NPWP: PPh 21 di atas sudah termasuk tambahan 20% (x 120%)", "* No NPWP: the PPh 21 above includes the 20% surcharge (x 120%)"),
+    ("worksheet.summary", "[Ringkasan]", "[Summary]"),
+    ("worksheet.gross_up", "=== HASIL PERHITUNGAN GROSS UP ===", "=== GROSS UP RESULT ==="),
+    ("worksheet.employee_receives", "[KARYAWAN MENERIMA]:", "[EMPLOYEE RECEIVES]:"),
+    ("worksheet.company_pays", "[PERUSAHAAN MENGELUARKAN]:", "[COMPANY PAYS]:"),
+    ("worksheet.shares", "[PORSI KARYAWAN vs PERUSAHAAN]:", "[EMPLOYEE vs COMPANY SHARE]:"),
+    ("worksheet.tax", "[PERHITUNGAN PAJAK]:", "[TAX CALCULATION]:"),
+    ("worksheet.pkp_upper", "[PENGHASILAN KENA PAJAK (PKP)]", "[TAXABLE INCOME (PKP)]"),
+    ("worksheet.pph21_upper", "[PERHITUNGAN PPh 21]", "[PPh 21 CALCULATION]"),
+    ("worksheet.progressive_breakdown", "Rincian Perhitungan (Tarif Progresif Pasal 17 x PKP):", "Calculation Breakdown (Progressive Pasal 17 Rates x PKP):"),
+    ("worksheet.annual_summary_upper", "[RINGKASAN TAHUNAN]", "[ANNUAL SUMMARY]"),
+    ("worksheet.notes", "[Keterangan]:", "[Notes]:"),
+    ("worksheet.promised_net", "* Karyawan menerima gaji bersih sesuai yang dijanjikan", "* The employee receives the promised net pay"),
+    ("worksheet.gross_scheme", "##### SKEMA GROSS (KARYAWAN MENANGGUNG PAJAK) #####", "##### GROSS SCHEME (EMPLOYEE BEARS THE TAX) #####"),
+    ("worksheet.gross_up_scheme", "##### SKEMA GROSS UP (PERUSAHAAN MENANGGUNG PAJAK) #####", "##### GROSS UP SCHEME (COMPANY BEARS THE TAX) #####"),
+    ("summary.title", "Ringkasan SPT Tahunan PPh 21 ({} bulan)", "Annual PPh 21 SPT Summary ({} months)"),
+    ("summary.annual_gross", "Penghasilan Bruto Setahun", "Annual Gross Income"),
+    ("summary.annual_tax_due", "PPh 21 Terutang Setahun", "Annual PPh 21 Due"),
+    ("summary.withheld", "PPh 21 Telah Dipotong", "PPh 21 Already Withheld"),
+    ("summary.nil", "PPh 21 Nihil: potongan bulanan sudah sesuai pajak setahun", "PPh 21 Nil: the monthly withholding matches the annual tax"),
+    ("table.period", "Periode", "Period"),
+    ("table.amount", "Jumlah", "Amount"),
+    ("table.installment", "Angsuran ke-{}", "Installment {}"),
+    ("table.total", "Total", "Total"),
+    ("table.subtotal", "Subtotal", "Subtotal"),
+    ("table.vat_per_line", "PPN {}% (dibulatkan per baris)", "PPN {}% (rounded per line)"),
+    ("table.item", "Barang/Jasa", "Item"),
+    ("table.quantity", "Jumlah", "Quantity"),
+    ("table.unit_price", "Harga Satuan", "Unit Price"),
+    ("table.component", "Komponen", "Component"),
+    ("table.scheme", "Skema", "Scheme"),
+    ("table.take_home", "Diterima Karyawan", "Employee Receives"),
+    ("table.year", "Tahun", "Year"),
+    ("table.step", "Langkah", "Step"),
+    ("table.detail", "Keterangan", "Detail"),
+    ("table.running", "Nilai Berjalan", "Running Value"),
+    ("trace.monthly_gross", "Gaji bruto sebulan", "Monthly gross pay"),
+    ("trace.annualized", "Disetahunkan", "Annualized"),
+    ("trace.annualized_detail", "x {} kali gaji", "x {} salaries"),
+    ("trace.biaya_jabatan", "Dikurangi biaya jabatan", "Less job expense"),
+    ("trace.biaya_jabatan_detail", "{}%, maks. {}", "{}%, max. {}"),
+    ("trace.pension", "Dikurangi iuran pensiun", "Less pension contribution"),
+    ("trace.zakat", "Dikurangi zakat", "Less zakat"),
+    ("trace.ptkp", "Dikurangi PTKP", "Less PTKP"),
+    ("trace.rounded", "Dibulatkan ke bawah ke ribuan", "Rounded down to the thousand"),
+    ("trace.bracket", "Tarif lapisan", "Bracket rate"),
+    ("trace.no_npwp", "Tambahan tanpa NPWP", "No-NPWP surcharge"),
+    ("trace.monthly", "Dibagi bulan bekerja", "Divided by months worked"),
+    ("trace.monthly_detail", "/ {} bulan", "/ {} months"),
+    ("selfcheck.ptkp_order", "Urutan PTKP (TK/0 < K/0 < ... < K/3)", "PTKP order (TK/0 < K/0 < ... < K/3)"),
+    ("selfcheck.bracket_contiguity", "Kesinambungan lapisan tarif", "Bracket contiguity"),
+    ("selfcheck.rate_monotonicity", "Tarif naik per lapisan", "Rates rise with each bracket"),
+    ("selfcheck.fingerprint", "Sidik jari tabel: {}", "Tables fingerprint: {}"),
+    ("batch.row_error", "Baris {}: {}", "Line {}: {}"),
+    ("batch.validated", "{} baris valid, {} baris tidak valid.", "{} valid lines, {} invalid lines."),
+    ("batch.row_skipped", "Baris {} dilewati: {}", "Line {} skipped: {}"),
+    ("batch.done", "{} karyawan diproses, {} baris dilewati. Hasil: {}", "{} employees processed, {} lines skipped. Output: {}"),
+    ("profile.saved", "Profil '{}' disimpan di {}", "Profile '{}' saved to {}"),
+    ("slip.saved", "Slip gaji {} periode {} disimpan ke {}", "Payslip for {} ({}) saved to {}"),
+    ("payroll.annual_gross", "Gaji Bruto Setahun", "Annual Gross Pay"),
+    ("payroll.biaya_jabatan", "Biaya Jabatan (-)", "Job Expense (-)"),
+    ("payroll.ptkp", "PTKP (-)", "PTKP (-)"),
+    ("payroll.bpjs_kesehatan", "BPJS Kesehatan (-)", "BPJS Kesehatan (-)"),
+    ("payroll.bpjs_jht", "BPJS JHT (-)", "BPJS JHT (-)"),
+    ("payroll.bpjs_jp", "BPJS JP (-)", "BPJS JP (-)"),
+    ("payroll.monthly_tax", "PPh 21 Sebulan (-)", "Monthly PPh 21 (-)"),
+    ("payroll.employer_jkk", "JKK Perusahaan", "Employer JKK"),
+    ("payroll.employer_jkm", "JKM Perusahaan", "Employer JKM"),
+    ("payroll.employer_jht", "JHT Perusahaan", "Employer JHT"),
+    ("payroll.employer_jp", "JP Perusahaan", "Employer JP"),
+    ("payroll.employer_kesehatan", "BPJS Kesehatan Perusahaan", "Employer BPJS Kesehatan"),
+    ("payroll.company_cost", "Total Biaya Perusahaan", "Total Company Cost"),
+    ("error.read_input", "Gagal membaca input: {}", "Could not read input: {}"),
+    ("error.did_not_converge", "Perhitungan belum konvergen setelah {} iterasi (selisih terakhir {})", "The calculation did not converge after {} iterations (last change {})"),
+    ("error.gross_up_unreachable", "Gaji neto {} tidak dapat dicapai dengan tabel tarif ini: setiap tambahan bruto habis untuk pajak.", "A net salary of {} cannot be reached with this rate table: every extra rupiah of gross goes to tax."),
+    ("error.cancelled", "Perhitungan dibatalkan", "Calculation cancelled"),
+    ("error.check_failed", "Pemeriksaan tidak lulus", "The check did not pass"),
+    ("error.gross_negative", "Penghasilan bruto harus Rp 0 atau lebih (dimasukkan: {}).", "Gross income must be Rp 0 or more (entered: {})."),
+    ("error.zakat_negative", "Zakat harus Rp 0 atau lebih (dimasukkan: {}).", "Zakat must be Rp 0 or more (entered: {})."),
+    ("error.overtime_negative", "Uang lembur harus Rp 0 atau lebih (dimasukkan: {}).", "Overtime pay must be Rp 0 or more (entered: {})."),
+    ("error.pension_negative", "Iuran pensiun harus Rp 0 atau lebih (dimasukkan: {}).", "The pension contribution must be Rp 0 or more (entered: {})."),
+    ("error.label_gross", "Penghasilan bruto", "Gross income"),
+    ("error.label_overtime", "Uang lembur", "Overtime pay"),
+    ("error.label_requested_net", "Gaji bersih yang diminta", "The requested net salary"),
+    ("error.months_per_year", "Jumlah gaji setahun harus 12 atau 13 bulan (dimasukkan: {}).", "Salaries per year must be 12 or 13 (entered: {})."),
+    ("error.months_worked", "Masa kerja harus 1 sampai 12 bulan (dimasukkan: {}).", "Months worked must be 1 to 12 (entered: {})."),
+    ("error.combined_income_unmarried", "Status K/I hanya untuk wajib pajak kawin.", "K/I status is only for married taxpayers."),
+    ("error.unknown_ptkp_status", "Status PTKP '{}' tidak dikenal (contoh: TK/0, K/2, K/I/1)", "Unknown PTKP status '{}' (for example: TK/0, K/2, K/I/1)"),
+    ("error.above_bound", "{} melebihi batas wajar {} per bulan. Periksa kembali angkanya.", "{} is above the sanity limit of {} a month. Please check the figure."),
+    ("error.invalid_dependents", "Jumlah tanggungan '{}' tidak valid. Contoh: 2 atau usia 8,12,15", "Invalid number of dependents '{}'. For example: 2, or ages 8,12,15"),
+    ("error.too_many_dependents", "Jumlah tanggungan {} tidak wajar (maksimal {}). Silakan periksa kembali.", "{} dependents is implausible (at most {}). Please check again."),
+    ("error.unsupported_year", "Tabel pajak untuk tahun {} tidak tersedia (tersedia: {})", "No tax tables for {} (available: {})"),
+    ("error.ptkp_dependents_limit", "Jumlah tanggungan maksimal {} untuk PTKP", "PTKP allows at most {} dependents"),
+    ("error.ptkp_combined_unmarried", "Penghasilan istri digabung (K/I) hanya untuk status kawin", "Combined spouse income (K/I) is only for a married status"),
+    ("error.config_invalid", "Konfigurasi pajak tidak valid: {}", "Invalid tax configuration: {}"),
+    ("error.config_missing_ptkp", "Konfigurasi pajak tidak valid: PTKP {} tidak ada", "Invalid tax configuration: PTKP {} is missing"),
+    ("error.config_brackets", "Konfigurasi pajak tidak valid: lapisan tarif harus dimulai dari 0 dan tiap lapisan menyambung lapisan sebelumnya", "Invalid tax configuration: the brackets must start at 0 and each must follow on from the one before"),
+    ("error.config_preset", "Konfigurasi pajak tidak valid: preset '{}': {}", "Invalid tax configuration: preset '{}': {}"),
+    ("error.config_final_tax", "Konfigurasi pajak tidak valid: tarif final '{}' {}% di luar rentang 0-100%", "Invalid tax configuration: final tax '{}' at {}% is outside 0-100%"),
+    ("error.config_read", "Gagal membaca konfigurasi {}: {}", "Could not read the configuration {}: {}"),
+    ("error.summary_months", "Ringkasan tahunan butuh 1-12 bulan gaji, diberikan {}.", "The annual summary needs 1-12 months of pay, {} given."),
+    ("error.invalid_npwp", "NPWP '{}' tidak valid. NPWP harus terdiri dari 15 atau 16 digit.", "Invalid NPWP '{}'. An NPWP has 15 or 16 digits."),
+    ("error.invalid_period", "Periode '{}' tidak valid. Gunakan format MM/YYYY, contoh 10/2024.", "Invalid period '{}'. Use MM/YYYY, for example 10/2024."),
+    ("error.month_range", "Bulan {} di luar rentang 1-12.", "Month {} is outside 1-12."),
+    ("error.unknown_final_tax", "Jenis pajak final '{}' tidak dikenal (tersedia: {}).", "Unknown final tax category '{}' (available: {})."),
+    ("error.effective_rate_unreachable", "Tarif efektif {}% tidak dapat dicapai: harus di atas 0% dan di bawah tarif marginal tertinggi {}%.", "An effective rate of {}% cannot be reached: it must be above 0% and below the top marginal rate of {}%."),
+    ("error.bonus_month", "Bonus harus dibayar pada bulan ke-1 sampai ke-{} masa kerja (dimasukkan: {}).", "The bonus must be paid in month 1 to {} of employment (entered: {})."),
+    ("error.sequence_range", "Nomor urut {} di luar rentang 1-{}.", "Sequence number {} is outside 1-{}."),
+    ("error.tolerance", "Toleransi gross-up harus lebih dari Rp 0 (dimasukkan: {}).", "The gross-up tolerance must be more than Rp 0 (entered: {})."),
+    ("error.requested_net", "Gaji bersih yang diminta harus lebih dari Rp 0 (dimasukkan: {}).", "The requested net salary must be more than Rp 0 (entered: {})."),
+    ("error.invalid_amount", "Masukan '{}' tidak valid. Harap masukkan angka positif, contoh: 6000000, 6.000.000, atau Rp6,000,000.", "Invalid entry '{}'. Please enter a positive number, for example: 6000000, 6.000.000 or Rp6,000,000."),
+    ("error.amount_negative", "Jumlah harus Rp 0 atau lebih (dimasukkan: {}).", "The amount must be Rp 0 or more (entered: {})."),
+    ("error.not_a_whole_number", "'{}' bukan bilangan bulat. Masukkan angka {}-{}.", "'{}' is not a whole number. Enter a number from {} to {}."),
+    ("error.outside_range", "{} di luar rentang {}-{}.", "{} is outside {}-{}."),
+    ("error.brackets_read", "Gagal membaca lapisan tarif {}: {}", "Could not read the brackets {}: {}"),
+    ("error.brackets_header", "Kolom {} harus {}, ditemukan {}", "The columns of {} must be {}, found {}"),
+    ("error.brackets_value", "Baris {}: {} '{}' tidak valid", "Line {}: invalid {} '{}'"),
+    ("error.brackets_contiguity", "Lapisan tarif di {} harus dimulai dari 0 dan tiap lapisan menyambung lapisan sebelumnya", "The brackets in {} must start at 0 and each must follow on from the one before"),
+    ("error.invalid_percent", "Persentase '{}' tidak valid. Contoh: 11 atau 11,5", "Invalid percentage '{}'. For example: 11 or 11,5"),
+    ("error.vat_rate_range", "Tarif PPN {}% di luar rentang 0-{}%.", "The PPN rate {}% is outside 0-{}%."),
+    ("error.ppnbm_required", "Persentase PPnBM wajib diisi.", "The PPnBM percentage is required."),
+    ("error.ppnbm_rate_range", "Tarif PPnBM {}% di luar rentang 0-{}%.", "The PPnBM rate {}% is outside 0-{}%."),
+    ("error.pph26_rate_range", "Tarif PPh 26 {}% di luar rentang 0-{}%.", "The PPh 26 rate {}% is outside 0-{}%."),
+    ("error.invalid_request_code", "Kode perhitungan '{}' tidak valid", "Invalid calculation code '{}'"),
+    ("error.invalid_currency", "Kode mata uang '{}' tidak valid. Contoh: USD", "Invalid currency code '{}'. For example: USD"),
+    ("error.exchange_rate", "Kurs {} harus lebih dari Rp 0 (dimasukkan: {}).", "The {} exchange rate must be more than Rp 0 (entered: {})."),
+    ("error.profile_read", "Gagal membaca profil {}: {}", "Could not read the profiles {}: {}"),
+    ("error.profile_invalid", "File profil {} tidak valid: {}", "Invalid profiles file {}: {}"),
+    ("error.profile_not_found", "Profil '{}' tidak ditemukan di {}", "Profile '{}' not found in {}"),
+    ("error.profile_write", "Gagal menulis profil {}: {}", "Could not write the profiles {}: {}"),
+    ("error.history_open", "Gagal membuka riwayat {}: {}", "Could not open the history {}: {}"),
+    ("error.history_write", "Gagal menulis riwayat {}: {}", "Could not write the history {}: {}"),
+    ("error.pdf_render", "Gagal membuat PDF: {}", "Could not create the PDF: {}"),
+    ("error.file_write", "Gagal menulis {}: {}", "Could not write {}: {}"),
+    ("error.env_invalid", "{} tidak valid: {}", "Invalid {}: {}"),
+    ("error.config_vat_rate", "vat_rate {}% pada konfigurasi di luar rentang 0-{}%.", "vat_rate {}% in the configuration is outside 0-{}%."),
+    ("error.batch_columns", "diharapkan {} kolom, ditemukan {}", "expected {} columns, found {}"),
+    ("error.batch_value", "{} '{}' tidak valid", "invalid {} '{}'"),
+    ("error.batch_married", "is_married '{}' tidak valid (gunakan true/false)", "invalid is_married '{}' (use true/false)"),
+    ("error.csv_read", "Gagal membaca CSV: {}", "Could not read the CSV: {}"),
+    ("error.csv_write", "Gagal menulis CSV: {}", "Could not write the CSV: {}"),
+    ("error.invalid_status", "Pilihan status '{}' tidak valid. Silakan pilih 1, 2, atau 3.", "Invalid status choice '{}'. Please choose 1, 2 or 3."),
+    ("error.invalid_yes_no", "Jawaban '{}' tidak valid. Ketik y atau n.", "Invalid answer '{}'. Enter y or n."),
+    ("error.invalid_choice_1_2", "Pilihan '{}' tidak valid. Silakan pilih 1 atau 2.", "Invalid choice '{}'. Please choose 1 or 2."),
+    ("error.invalid_vat_type", "Jenis PPN '{}' tidak valid. Silakan pilih 1, 2, atau 3.", "Invalid PPN type '{}'. Please choose 1, 2 or 3."),
+    ("error.new_vat_rate_required", "Persentase PPN baru wajib diisi.", "The new PPN percentage is required."),
+    ("error.invalid_pph23_object", "Objek PPh 23 '{}' tidak valid. Silakan pilih 1, 2, 3, atau 4.", "Invalid PPh 23 object '{}'. Please choose 1, 2, 3 or 4."),
+    ("error.invalid_final_object", "Objek PPh Final '{}' tidak valid. Silakan pilih 1-5.", "Invalid final PPh object '{}'. Please choose 1-5."),
+    ("error.invalid_method", "Pilihan metode '{}' tidak valid. Silakan pilih 1 atau 2.", "Invalid method choice '{}'. Please choose 1 or 2."),
+    ("error.positive_number", "Masukan tidak valid. Harap masukkan angka positif.", "Invalid entry. Please enter a positive number."),
+    ("error.preset_not_found", "Preset '{}' tidak ditemukan. Pilihan: {}", "Preset '{}' not found. Choices: {}"),
+    ("error.open_file", "Gagal membuka {}: {}", "Could not open {}: {}"),
+    ("error.create_file", "Gagal membuat {}: {}", "Could not create {}: {}"),
+    ("error.server", "Server API gagal: {}", "The API server failed: {}"),
+    ("error.piped_missing", "Input {} tidak ada", "The {} input is missing"),
+    ("error.piped_choice", "Pilihan '{}' tidak didukung untuk input pipa (gunakan 1, 2, 3, atau 4)", "Choice '{}' is not supported for piped input (use 1, 2, 3 or 4)"),
+    ("error.invalid_deduction", "Potongan '{}' tidak valid. Gunakan NAMA=JUMLAH, contoh Kasbon=500000.", "Invalid deduction '{}'. Use NAME=AMOUNT, for example Advance=500000."),
+    ("piped.choice", "pilihan menu", "menu choice"),
+    ("piped.gross", "penghasilan bruto", "gross income"),
+    ("piped.net", "gaji bersih", "net salary"),
+    ("piped.marital", "status perkawinan", "marital status"),
+    ("piped.dependents", "jumlah tanggungan", "dependents"),
+    ("piped.pkp", "penghasilan kena pajak", "taxable income"),
+    ("piped.amount", "jumlah harga", "price"),
+];
+
+// Look up the text for `key` in `lang`; keys are fixed in the code, so an
+// unknown key is a bug
+pub fn t(key: &str, lang: Lang) -> &'static str {
+    let (_, id, en) = MESSAGES
+        .iter()
+        .find(|(message_key, _, _)| *message_key == key)
+        .unwrap_or_else(|| panic!("teks '{}' tidak ada di MESSAGES", key));
+    
+    match lang {
+        Lang::Id => id,
+        Lang::En => en,
+    }
+}
+
+// The text for `key` with each "{}" replaced by the next of `args`, for
+// messages that carry an amount or a count
+pub fn t_with(key: &str, lang: Lang, args: &[&dyn fmt::Display]) -> String {
+    let mut parts = t(key, lang).split("{}");
+    let mut output = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        let arg = args.get(index).unwrap_or_else(|| panic!("teks '{}' butuh lebih dari {} nilai", key, args.len()));
+        output.push_str(&arg.to_string());
+        output.push_str(part);
+    }
+    
+    output
+}
+
+// A value filled into a Message: text formatted when the message was made,
+// or another message shown in the same language as the outer one
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageArg {
+    Text(String),
+    Message(Message),
+}
+
+// Text to show later in either language: a MESSAGES key and the values for
+// its "{}" placeholders. Errors carry these so the CLI can print them in the
+// language chosen with --lang; Display gives the Indonesian text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    key: &'static str,
+    args: Vec<MessageArg>,
+}
+
+impl Message {
+    pub fn new(key: &'static str, args: &[&dyn fmt::Display]) -> Message {
+        Message::with_args(key, args.iter().map(|arg| MessageArg::Text(arg.to_string())).collect())
+    }
+    
+    // A message whose values may themselves be messages
+    pub fn with_args(key: &'static str, args: Vec<MessageArg>) -> Message {
+        Message { key, args }
+    }
+    
+    pub fn text(&self, lang: Lang) -> String {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| match arg {
+                MessageArg::Text(text) => text.clone(),
+                MessageArg::Message(message) => message.text(lang),
+            })
+            .collect();
+        let args: Vec<&dyn fmt::Display> = args.iter().map(|arg| arg as &dyn fmt::Display).collect();
+        
+        t_with(self.key, lang, &args)
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text(Lang::Id))
+    }
+}
+
+// Every calculation in the menu with its explanation and required inputs, for
+// the h command; options 1 to 26 have a help text, 27 (exit) does not
+pub fn menu_help(lang: Lang) -> String {
+    let mut output = format!("{}\n", t("help.title", lang));
+    for option in 1..=26 {
+        output.push_str(&format!("\n{}. {}\n   {}\n", option, t(&format!("menu.{}", option), lang), t(&format!("help.{}", option), lang)));
+    }
+    
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_menu_header_differs() {
+        assert_eq!(t("app.title", Lang::Id), "=== KALKULATOR PAJAK ===");
+        assert_eq!(t("app.title", Lang::En), "=== TAX CALCULATOR ===");
+    }
+
+    #[test]
+    fn test_all_keys_exist_in_both_languages() {
+        for (key, id, en) in MESSAGES {
+            assert!(!id.is_empty(), "{} has no Indonesian text", key);
+            assert!(!en.is_empty(), "{} has no English text", key);
+            assert_eq!(MESSAGES.iter().filter(|(other, _, _)| other == key).count(), 1, "{} is duplicated", key);
+        }
+        // Every menu entry is translated
+        for option in 1..=27 {
+            t(&format!("menu.{}", option), Lang::En);
+        }
+        // Both texts take the same values, so t_with fills either one
+        for (key, id, en) in MESSAGES {
+            assert_eq!(id.matches("{}").count(), en.matches("{}").count(), "{} has different placeholders", key);
+        }
+    }
+    
+    #[test]
+    fn test_t_with_fills_placeholders_in_order() {
+        assert_eq!(t_with("vat.rate_change", Lang::Id, &[&11, &12, &7]), "[Perubahan tarif 11% -> 12% mulai bulan 7]");
+        assert_eq!(t_with("vat.rate_change", Lang::En, &[&11, &12, &7]), "[Rate change 11% -> 12% from month 7]");
+        assert_eq!(t_with("app.title", Lang::En, &[]), t("app.title", Lang::En));
+    }
+
+    #[test]
+    fn test_menu_help_explains_gross_and_gross_up() {
+        for lang in [Lang::Id, Lang::En] {
+            let help = menu_help(lang);
+            assert!(help.starts_with(t("help.title", lang)));
+            assert!(help.contains(&format!("1. {}\n   {}", t("menu.1", lang), t("help.1", lang))));
+            assert!(help.contains("Gross Up"));
+            assert!(help.replace("Gross Up", "").contains("Gross"));
+            assert!(help.contains("26. "));
+            assert!(!help.contains("27. "));
+        }
+        assert!(menu_help(Lang::En).contains("Inputs: desired net pay"));
+    }
+
+    #[test]
+    fn test_parse_lang() {
+        assert_eq!(Lang::parse("EN"), Ok(Lang::En));
+        assert_eq!(Lang::parse("id"), Ok(Lang::Id));
+        assert!(Lang::parse("fr").is_err());
+    }
+}
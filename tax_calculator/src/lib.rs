@@ -0,0 +1,22 @@
+// Tax calculation library behind the tax_calculator CLI
+
+pub mod batch;
+pub mod error;
+pub mod history;
+pub mod i18n;
+pub mod profile;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod settings;
+#[cfg(feature = "pdf")]
+pub mod slip;
+pub mod tax;
+// The JSON wrappers are also compiled for tests, without wasm-bindgen
+#[cfg(any(feature = "wasm", test))]
+pub mod wasm;
+
+// Public for downstream tests with the `testing` feature
+#[cfg(any(feature = "testing", test))]
+pub mod test_utils;
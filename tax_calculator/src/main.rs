@@ -1,433 +1,1838 @@
-use std::io;
-use thousands::Separable;
-use std::collections::HashMap;
-
-// PPh 21 Calculation Parameters
-#[derive(Debug)]
-struct PPh21Params {
-    gross_income: f64,
-    is_married: bool,
-    num_dependents: u32,
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use chrono::Datelike;
+use clap::{Parser, Subcommand, ValueEnum};
+use owo_colors::{OwoColorize, Stream};
+use tax_calculator::batch::{run_batch, validate_batch};
+use tax_calculator::error::CalcError;
+use tax_calculator::history::{append_entry, HistoryEntry, HISTORY_ENV_VAR};
+use tax_calculator::profile::{load_profile, save_profile, DEFAULT_PROFILES_FILE, PROFILES_ENV_VAR};
+use tax_calculator::settings::{discover_config, env_vat_rate, resolve_vat_rate};
+use tax_calculator::i18n::{menu_help, t, t_with, Lang, Message, MessageArg};
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+#[cfg(feature = "pdf")]
+use tax_calculator::slip::write_slip_pdf;
+use tax_calculator::tax::*;
+
+// Read one line; a closed input is an error rather than empty input
+fn read_line_from(reader: &mut impl BufRead) -> Result<String, CalcError> {
+    let mut input = String::new();
+    if reader.read_line(&mut input)? == 0 {
+        return Err(CalcError::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "input berakhir")));
+    }
+    
+    Ok(input)
 }
 
-// PTKP (Penghasilan Tidak Kena Pajak) values for 2023
-fn get_ptkp_values() -> HashMap<&'static str, f64> {
-    let mut ptkp = HashMap::new();
-    ptkp.insert("TK/0", 54_000_000.0);  // Single, no dependents
-    ptkp.insert("K/0", 58_500_000.0);   // Married, no dependents
-    ptkp.insert("K/1", 63_000_000.0);   // Married, 1 dependent
-    ptkp.insert("K/2", 67_500_000.0);   // Married, 2 dependents
-    ptkp.insert("K/3", 72_000_000.0);   // Married, 3+ dependents
-    ptkp
+// Read an answer inside a calculation; "q" backs out to the main menu
+fn read_answer_from(reader: &mut impl BufRead) -> Result<String, CalcError> {
+    let input = read_line_from(reader)?;
+    match input.trim() {
+        "q" | "Q" => Err(CalcError::Cancelled),
+        _ => Ok(input),
+    }
 }
 
-// Calculate PPh 21 for monthly employee
-fn calculate_pph21(params: &PPh21Params) -> (f64, f64, f64, f64) {
-    let monthly_gross = params.gross_income;
-    let annual_gross = monthly_gross * 12.0;
+// The menu's input and output: stdin and stdout in the binary, in-memory
+// buffers in tests. Writes go straight to `output`; rejected answers are
+// reported in `lang`
+struct Console<R, W> {
+    input: R,
+    output: W,
+    lang: Lang,
+}
+
+impl<R: BufRead, W: Write> Console<R, W> {
+    // Show `message` and read until `parse` accepts the entry; invalid entries
+    // are reported and asked again, only read failures and cancellation are
+    // passed on. Besides "q", a "0" that `parse` rejects also cancels, so 0
+    // stays a valid answer for amounts and dependents
+    fn prompt<T>(&mut self, message: &str, parse: impl Fn(&str) -> Result<T, CalcError>) -> Result<T, CalcError> {
+        loop {
+            writeln!(self.output, "{}", message)?;
+            let input = read_answer_from(&mut self.input)?;
+            match parse(&input) {
+                Ok(value) => return Ok(value),
+                Err(err @ (CalcError::Io(_) | CalcError::Cancelled)) => return Err(err),
+                Err(_) if input.trim() == "0" => return Err(CalcError::Cancelled),
+                Err(err) => self.print_error(err.message().text(self.lang))?,
+            }
+        }
+    }
+    
+    // Free text, trimmed; an empty answer is allowed
+    fn prompt_text(&mut self, message: &str) -> Result<String, CalcError> {
+        self.prompt(message, |input| Ok(input.trim().to_string()))
+    }
+    
+    // A non-negative Rupiah amount
+    fn prompt_f64(&mut self, message: &str) -> Result<f64, CalcError> {
+        self.prompt(message, parse_amount)
+    }
+    
+    // A monthly gross: a non-negative Rupiah amount within the sanity bound,
+    // so an absurd figure is asked again before the other questions
+    fn prompt_gross(&mut self, message: &str) -> Result<f64, CalcError> {
+        let max_income = max_monthly_income();
+        self.prompt(message, |input| {
+            let amount = parse_amount(input)?;
+            check_monthly_bound("error.label_gross", amount, max_income)?;
+            Ok(amount)
+        })
+    }
     
-    // Get PTKP based on marital status and number of dependents
-    let ptkp_key = format!("{}/{}", 
-        if params.is_married { "K" } else { "TK" },
-        params.num_dependents
-    );
-    let ptkp = get_ptkp_values().get(&*ptkp_key).copied().unwrap_or(0.0);
+    // A non-negative Rupiah amount, Rp 0 when left empty
+    fn prompt_optional_f64(&mut self, message: &str) -> Result<f64, CalcError> {
+        self.prompt(message, parse_optional_amount)
+    }
+    
+    // A y/n answer, `default` when left empty
+    fn prompt_bool(&mut self, message: &str, default: bool) -> Result<bool, CalcError> {
+        self.prompt(message, |input| parse_yes_no(input, default))
+    }
+    
+    // Prompt for marital status, returning (is_married, spouse_income_combined)
+    fn read_marital_status(&mut self, lang: Lang) -> Result<(bool, bool), CalcError> {
+        self.prompt(
+            &format!("\n{}", t("prompt.marital", lang)),
+            |input| match input.trim() {
+                "1" => Ok((false, false)),
+                "2" => Ok((true, false)),
+                "3" => Ok((true, true)),
+                other => Err(CalcError::Parse(Message::new("error.invalid_status", &[&other]))),
+            },
+        )
+    }
+    
+    // Prompt for NPWP status, yes unless answered "n"
+    fn read_npwp(&mut self, lang: Lang) -> Result<bool, CalcError> {
+        self.prompt_bool(&format!("\n{}", t("prompt.npwp", lang)), true)
+    }
     
-    // Calculate PKP (Penghasilan Kena Pajak)
-    let pkp = (annual_gross - ptkp).max(0.0);
+    // Prompt for dependents until the entry parses and passes the sanity limit,
+    // returning the number entered; PPh21Params caps it for PTKP, with a notice here
+    fn read_dependents(&mut self, lang: Lang) -> Result<u32, CalcError> {
+        let max_dependents = max_dependents_input();
+        let dependents = self.prompt(
+            &format!("\n{}", t("prompt.dependents", lang)),
+            |input| Dependents::parse(input)?.validate(max_dependents),
+        )?;
+        if let Dependents::Ages(ages) = &dependents {
+            let ages: Vec<String> = ages.iter().map(|age| age.to_string()).collect();
+            writeln!(self.output, "{}", t_with("note.dependent_ages", lang, &[&ages.join(", ")]))?;
+        }
+        if let Some(notice) = dependents.ptkp_notice(lang) {
+            writeln!(self.output, "{}", notice.if_supports_color(Stream::Stdout, |text| text.yellow().to_string()))?;
+        }
+        
+        Ok(dependents.count())
+    }
     
-    // Calculate flat 0.75% PPh 21 on gross income
-    let pph_21_rate = 0.75 / 100.0; // 0.75%
-    let annual_tax = (annual_gross * pph_21_rate).round();
-    let monthly_tax = (monthly_gross * pph_21_rate).round();
+    // A result with its final tax figures highlighted
+    fn print_result(&mut self, text: &str) -> io::Result<()> {
+        write!(self.output, "{}", highlight_totals(text))
+    }
     
-    (annual_tax, monthly_tax, ptkp, pkp)
+    // An error message, in red when color is on
+    fn print_error(&mut self, message: impl fmt::Display) -> io::Result<()> {
+        writeln!(self.output, "{}", message.if_supports_color(Stream::Stdout, |text| text.red().to_string()))
+    }
 }
 
-// Tax bracket structure
-#[derive(Debug)]
-struct TaxBracket {
-    lower_bound: f64,
-    upper_bound: f64,
-    rate: f64,
+impl<R, W: Write> Write for Console<R, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.write(buf)
+    }
+    
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+fn parse_optional_amount(input: &str) -> Result<f64, CalcError> {
+    match input.trim() {
+        "" => Ok(0.0),
+        amount => parse_amount(amount),
+    }
+}
+
+fn parse_yes_no(input: &str, default: bool) -> Result<bool, CalcError> {
+    match input.trim() {
+        "" => Ok(default),
+        answer if answer.eq_ignore_ascii_case("y") => Ok(true),
+        answer if answer.eq_ignore_ascii_case("n") => Ok(false),
+        other => Err(CalcError::Parse(Message::new("error.invalid_yes_no", &[&other]))),
+    }
 }
 
-// Function to calculate income tax based on tax brackets
-fn calculate_income_tax(income: f64, tax_brackets: &[TaxBracket]) -> f64 {
-    let mut tax = 0.0;
+// Command-line interface; without a subcommand the interactive menu runs
+#[derive(Parser)]
+#[command(about = "Kalkulator pajak: PPh 21, PPh umum, dan PPN")]
+struct Cli {
+    /// Tampilkan ulang perhitungan dari kode --request
+    #[arg(long, value_name = "KODE")]
+    request: Option<String>,
     
-    for bracket in tax_brackets {
-        if income > bracket.lower_bound {
-            let taxable_amount = f64::min(income, bracket.upper_bound) - bracket.lower_bound;
-            tax += taxable_amount * bracket.rate;
-        } else {
-            break;
+    /// Tampilkan perhitungan PPh 21 untuk preset dari --config, atau preset bawaan
+    #[arg(long, value_name = "NAMA")]
+    preset: Option<String>,
+    
+    /// Jalankan menu interaktif walau stdin bukan terminal, misalnya jawaban dari file
+    #[arg(long)]
+    interactive: bool,
+    
+    /// File TOML berisi PTKP, lapisan tarif, dan vat_rate (default: tax_calculator.toml
+    /// di direktori kerja jika ada, selain itu tabel bawaan tahun terbaru)
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+    
+    /// Tahun pajak untuk tabel bawaan: 2022, 2023, atau 2024 (default: terbaru)
+    #[arg(long, value_name = "TAHUN", global = true, conflicts_with = "config")]
+    year: Option<u16>,
+    
+    /// File CSV lapisan tarif (lower_bound,upper_bound,rate) untuk Pajak Penghasilan
+    /// Umum, menu 3 dan 17; menggantikan lapisan bawaan atau dari --config
+    #[arg(long, value_name = "FILE", global = true)]
+    brackets: Option<PathBuf>,
+    
+    /// Bahasa menu interaktif: id (default) atau en
+    #[arg(long, value_name = "KODE", value_parser = Lang::parse, default_value = "id", global = true)]
+    lang: Lang,
+    
+    /// Catat setiap perhitungan pph21, ppn, --request, dan --preset sebagai baris JSON
+    #[arg(long, value_name = "FILE", env = HISTORY_ENV_VAR, global = true)]
+    history: Option<PathBuf>,
+    
+    /// File JSON tempat profil pph21 --save-profile dan --profile disimpan
+    #[arg(long, value_name = "FILE", env = PROFILES_ENV_VAR, default_value = DEFAULT_PROFILES_FILE, global = true)]
+    profiles: PathBuf,
+    
+    /// Format keluaran untuk pph21, ppn, --request, dan --preset
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    format: OutputFormat,
+    
+    /// Warna keluaran: auto (hanya di terminal, kecuali NO_COLOR diisi), always, atau never
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
+    color: ColorChoice,
+    
+    /// Bulatkan PPh 21, PPN, dan take home pay yang ditampilkan ke kelipatan terdekat
+    /// (contoh 100 atau 1000); 0 = tanpa pembulatan. Perhitungan tetap memakai angka persis
+    #[arg(long, value_name = "N", default_value_t = 0, global = true)]
+    round_to: u32,
+    
+    /// Cetak hanya angka akhirnya: PPh 21 sebulan (take home pay untuk --take-home) atau PPN
+    #[arg(long, short, global = true, conflicts_with = "format")]
+    quiet: bool,
+    
+    /// Cetak ringkasan 2-3 baris tanpa rincian perhitungan, contoh PPh 21 sebulan dan neto
+    #[arg(long, global = true, conflicts_with_all = ["format", "quiet"])]
+    summary: bool,
+    
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Toml,
+    Markdown,
+    // Selected by --quiet: the final figure as a bare number
+    #[value(skip)]
+    Quiet,
+    // Selected by --summary: two or three labelled final figures
+    #[value(skip)]
+    Summary,
+}
+
+impl Cli {
+    fn output_format(&self) -> OutputFormat {
+        match (self.quiet, self.summary) {
+            (true, _) => OutputFormat::Quiet,
+            (_, true) => OutputFormat::Summary,
+            _ => self.format,
         }
     }
     
-    tax
+    // Without --request, --preset or a subcommand, input piped to stdin is
+    // read as one answer sequence unless --interactive asks for the menu
+    fn reads_piped_input(&self) -> bool {
+        self.request.is_none() && self.preset.is_none() && self.command.is_none() && !self.interactive && !io::stdin().is_terminal()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+// Whether to color output; in auto mode only a terminal gets color, and a
+// non-empty NO_COLOR turns it off (https://no-color.org)
+fn color_enabled(choice: ColorChoice, is_terminal: bool, no_color: Option<OsString>) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => is_terminal && no_color.is_none_or(|value| value.is_empty()),
+    }
+}
+
+// Message keys of the final tax and pay figures, highlighted in results
+const HIGHLIGHTED_LABELS: [&str; 14] = [
+    "label.monthly_tax",
+    "label.december_tax",
+    "label.annual_tax",
+    "label.underpaid",
+    "label.overpaid",
+    "label.this_month_tax",
+    "label.pph21_withheld",
+    "label.bonus_tax",
+    "label.pph23_withheld",
+    "label.pph26_withheld",
+    "label.final_tax",
+    "label.vat",
+    "label.annual_vat",
+    "label.take_home",
+];
+
+// Color the lines of `text` that show a final tax figure, in either language
+fn highlight_totals(text: &str) -> String {
+    let labels: Vec<&str> = HIGHLIGHTED_LABELS.iter().flat_map(|key| [t(key, Lang::Id), t(key, Lang::En)]).collect();
+    text.split_inclusive('\n')
+        .map(|line| {
+            let (content, newline) = line.strip_suffix('\n').map_or((line, ""), |content| (content, "\n"));
+            if labels.iter().any(|label| content.trim_start().starts_with(label)) {
+                format!("{}{}", content.if_supports_color(Stream::Stdout, |text| text.bold().green().to_string()), newline)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect()
+}
+
+// Print a result with its final tax figures highlighted
+fn print_result(text: &str) {
+    print!("{}", highlight_totals(text));
 }
 
-// Function to calculate VAT
-fn calculate_vat(amount: f64, vat_rate: f64) -> f64 {
-    amount * vat_rate / 100.0
+// Print an error message, in red when color is on
+fn print_error(message: impl fmt::Display) {
+    println!("{}", message.if_supports_color(Stream::Stdout, |text| text.red().to_string()));
+}
+
+// Warn on stderr, so the result on stdout stays clean, when post-tax
+// deductions took the whole take-home pay
+fn warn_deductions_exceed_net(report: &PayrollReport, lang: Lang) {
+    if report.deductions_exceed_net() {
+        let warning = t_with("note.deductions_exceed_net", lang, &[&format_rupiah(report.post_tax_deductions_total()), &format_rupiah(report.net_before_deductions())]);
+        eprintln!("{}", warning.if_supports_color(Stream::Stderr, |text| text.yellow().to_string()));
+    }
+}
+
+// Print a result as pretty JSON
+fn print_json<T: serde::Serialize>(value: &T) {
+    println!("{}", serde_json::to_string_pretty(value).expect("hasil perhitungan selalu dapat diserialisasi"));
+}
+
+// Print a result as TOML, in the same fields as the JSON output
+fn print_toml<T: serde::Serialize>(value: &T) {
+    print!("{}", toml::to_string(value).expect("hasil perhitungan selalu dapat diserialisasi"));
+}
+
+// Append a calculation to the history file, if one was given; a failed write
+// is reported but does not fail the calculation
+fn log_history<I: serde::Serialize, O: serde::Serialize>(history: Option<&Path>, kind: &str, inputs: &I, outputs: &O, lang: Lang) {
+    if let Some(path) = history {
+        if let Err(message) = append_entry(path, &HistoryEntry::new(kind, inputs, outputs)) {
+            eprintln!("{}", message.text(lang));
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Validasi tabel pajak bawaan
+    Selfcheck,
+    
+    /// Hitung PPh 21 pegawai tetap (skema gross)
+    Pph21 {
+        /// Penghasilan bruto per bulan (Rp)
+        #[arg(long, value_parser = parse_rupiah)]
+        gross: f64,
+        
+        /// Status kawin
+        #[arg(long, conflicts_with = "profile")]
+        married: bool,
+        
+        /// Jumlah tanggungan (hanya untuk status kawin)
+        #[arg(long, default_value_t = 0, conflicts_with = "profile")]
+        dependents: u32,
+        
+        /// Penghasilan istri digabung (status K/I)
+        #[arg(long, requires = "married")]
+        combined_income: bool,
+        
+        /// Penyandang disabilitas: tanggungan di atas 3 tetap menambah PTKP
+        #[arg(long, requires = "married")]
+        disability: bool,
+        
+        /// Pakai status kawin, tanggungan, dan NPWP dari profil tersimpan; hanya --gross yang baru
+        #[arg(long, value_name = "NAMA")]
+        profile: Option<String>,
+        
+        /// Simpan masukan perhitungan ini sebagai profil bernama
+        #[arg(long, value_name = "NAMA")]
+        save_profile: Option<String>,
+        
+        /// Gunakan tarif flat (0.75%, atau flat_rate di --config) alih-alih tarif progresif
+        #[arg(long)]
+        flat: bool,
+        
+        /// Pegawai tidak memiliki NPWP (PPh 21 20% lebih tinggi)
+        #[arg(long, conflicts_with = "profile")]
+        no_npwp: bool,
+        
+        /// Tampilkan laporan gaji bersih: bruto, biaya jabatan, BPJS, PTKP, PKP, PPh 21, take home pay
+        #[arg(long, conflicts_with = "flat")]
+        take_home: bool,
+        
+        /// Kelas risiko JKK untuk iuran BPJS perusahaan di --take-home: 1 (sangat rendah,
+        /// default) sampai 5 (sangat tinggi)
+        #[arg(long, value_name = "KELAS", value_parser = JkkClass::parse, requires = "take_home")]
+        jkk_class: Option<JkkClass>,
+        
+        /// Potongan setelah pajak di --take-home, seperti kasbon atau cicilan pinjaman;
+        /// boleh diulang, contoh --deduction Kasbon=500000
+        #[arg(long = "deduction", value_name = "NAMA=JUMLAH", value_parser = parse_post_tax_deduction, requires = "take_home")]
+        deductions: Vec<(String, f64)>,
+        
+        /// Gaji bruto yang dimasukkan sudah dipotong iuran BPJS pegawai, jadi --take-home
+        /// tidak memotongnya lagi
+        #[arg(long, requires = "take_home")]
+        gross_net_of_bpjs: bool,
+        
+        /// NPWP pegawai (15 atau 16 digit); menambahkan nomor bukti potong ke hasil
+        #[arg(long, conflicts_with_all = ["no_npwp", "take_home"])]
+        npwp: Option<String>,
+        
+        /// Masa pajak bukti potong, MM/YYYY (default: bulan ini)
+        #[arg(long, value_name = "MM/YYYY", requires = "npwp")]
+        period: Option<String>,
+        
+        /// Nomor urut bukti potong
+        #[arg(long, default_value_t = 1, requires = "npwp")]
+        sequence: u32,
+        
+        /// Tampilkan juga angka utama dalam mata uang lain, contoh USD (keluaran teks)
+        #[arg(long, value_name = "KODE", requires = "rate", conflicts_with = "take_home")]
+        display_currency: Option<String>,
+        
+        /// Kurs rupiah per satu unit --display-currency, contoh 15800
+        #[arg(long, value_name = "KURS", value_parser = parse_rupiah, requires = "display_currency")]
+        rate: Option<f64>,
+        
+        /// Tampilkan langkah perhitungan dari bruto ke PKP dan PPh 21 (metode progresif)
+        #[arg(long, conflicts_with_all = ["flat", "take_home", "npwp", "display_currency"])]
+        trace: bool,
+    },
+    
+    /// Hitung PPN
+    Ppn {
+        /// Jumlah harga sebelum PPN (Rp)
+        #[arg(long, value_parser = parse_rupiah)]
+        amount: f64,
+        
+        /// Persentase PPN, contoh 11 atau 11,5% (default: RUSTACEAN_VAT_RATE,
+        /// lalu vat_rate di file konfigurasi, lalu 11)
+        #[arg(long)]
+        rate: Option<String>,
+        
+        /// Jumlah sudah termasuk PPN; PPN dihitung dari dalam harga
+        #[arg(long)]
+        inclusive: bool,
+    },
+    
+    /// Hitung PPh 21 banyak karyawan dari file CSV
+    /// (kolom: name,gross_income,is_married,num_dependents atau
+    /// name,gross_income,status dengan status PTKP seperti TK/0 atau K/I/2)
+    Batch {
+        /// File CSV masukan
+        #[arg(long)]
+        input: PathBuf,
+        
+        /// File CSV hasil (ditambah kolom ptkp,pkp,monthly_tax,annual_tax)
+        #[arg(long, required_unless_present = "validate")]
+        output: Option<PathBuf>,
+        
+        /// Hanya periksa setiap baris tanpa menghitung atau menulis hasil;
+        /// keluar dengan kode 1 jika ada baris yang tidak valid
+        #[arg(long)]
+        validate: bool,
+    },
+    
+    /// Buat slip gaji PDF berisi rincian gaji bruto sampai take home pay
+    #[cfg(feature = "pdf")]
+    Slip {
+        /// Nama karyawan
+        #[arg(long)]
+        name: String,
+        
+        /// Periode gaji, contoh 10/2024 (default: bulan ini)
+        #[arg(long)]
+        period: Option<String>,
+        
+        /// Penghasilan bruto per bulan (Rp)
+        #[arg(long, value_parser = parse_rupiah)]
+        gross: f64,
+        
+        /// Status kawin
+        #[arg(long)]
+        married: bool,
+        
+        /// Jumlah tanggungan (hanya untuk status kawin)
+        #[arg(long, default_value_t = 0)]
+        dependents: u32,
+        
+        /// Penghasilan istri digabung (status K/I)
+        #[arg(long, requires = "married")]
+        combined_income: bool,
+        
+        /// Pegawai tidak memiliki NPWP (PPh 21 20% lebih tinggi)
+        #[arg(long)]
+        no_npwp: bool,
+        
+        /// Potongan setelah pajak, seperti kasbon atau cicilan pinjaman; boleh
+        /// diulang, contoh --deduction Kasbon=500000
+        #[arg(long = "deduction", value_name = "NAMA=JUMLAH", value_parser = parse_post_tax_deduction)]
+        deductions: Vec<(String, f64)>,
+        
+        /// File PDF hasil
+        #[arg(long, value_name = "FILE")]
+        output: PathBuf,
+    },
+    
+    /// Jalankan API HTTP: POST /pph21 dan POST /ppn dengan body JSON
+    #[cfg(feature = "server")]
+    Serve {
+        /// Alamat dan port server
+        #[arg(long, default_value = "127.0.0.1:3000")]
+        addr: SocketAddr,
+    },
+    
+    /// Cetak JSON Schema dari parameter dan hasil perhitungan
+    #[cfg(feature = "schema")]
+    Schema,
 }
 
+// The tests stay next to the helpers they grew with, above main and the menu
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::*;
-    // Helper function for floating-point comparison
-    fn assert_approx_eq(a: f64, b: f64) {
-        let epsilon = 0.01;
-        assert!(
-            (a - b).abs() < epsilon,
-            "Assertion failed: {} is not approximately equal to {}",
-            a,
-            b
-        );
+    use clap::CommandFactory;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
     }
 
     #[test]
-    fn test_calculate_pph21_single_no_dependents() {
-        let params = PPh21Params {
-            gross_income: 6_000_000.0,
-            is_married: false,
-            num_dependents: 0,
-        };
+    fn test_q_cancels_an_answer() {
+        let mut piped = "6000000\nq\n 0 \n".as_bytes();
+        assert_eq!(read_answer_from(&mut piped).unwrap(), "6000000\n");
+        assert!(matches!(read_answer_from(&mut piped), Err(CalcError::Cancelled)));
+        // 0 is passed on for the prompt to decide
+        assert_eq!(read_answer_from(&mut piped).unwrap(), " 0 \n");
+        assert!(matches!(read_answer_from(&mut piped), Err(CalcError::Io(_))));
         
-        let (annual_tax, monthly_tax, ptkp, pkp) = calculate_pph21(&params);
+        // The menu choice is read as a plain line, so 0 there is just an invalid choice
+        assert_eq!(read_line_from(&mut "0\n".as_bytes()).unwrap(), "0\n");
+    }
+
+    // A console answering from `script` and capturing what is written
+    fn scripted(script: &str) -> Console<Cursor<&str>, Vec<u8>> {
+        Console { input: Cursor::new(script), output: Vec::new(), lang: Lang::Id }
+    }
+
+    // Run a whole menu session on `script`, returning everything it printed
+    fn run_session(script: &str, lang: Lang) -> String {
+        let config = TaxConfig::default();
+        let mut output = Vec::new();
+        run_interactive(Cursor::new(script), &mut output, &config, &config.brackets, false, lang, 11.0).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_prompt_asks_again_until_the_answer_parses() {
+        let mut console = scripted("enam juta\n-5\nRp 6.000.000\n");
+        assert_eq!(console.prompt_f64("Gaji:").unwrap(), 6_000_000.0);
+        let output = String::from_utf8(console.output).unwrap();
+        assert_eq!(output.matches("Gaji:").count(), 3);
         
-        // PTKP for TK/0 should be 54,000,000
-        assert_approx_eq(ptkp, 54_000_000.0);
+        let mut console = scripted("\n1.500.000\n");
+        assert_eq!(console.prompt_optional_f64("Zakat:").unwrap(), 0.0);
+        assert_eq!(console.prompt_optional_f64("Zakat:").unwrap(), 1_500_000.0);
         
-        // PKP = (6,000,000 * 12) - 54,000,000 = 18,000,000
-        assert_approx_eq(pkp, 18_000_000.0);
+        let mut console = scripted("mungkin\nN\n\n");
+        assert!(!console.prompt_bool("NPWP?", true).unwrap());
+        assert!(console.prompt_bool("NPWP?", true).unwrap());
         
-        // PPh 21 = 0.75% of 6,000,000 = 45,000 per month
-        assert_approx_eq(monthly_tax, 45_000.0);
-        assert_approx_eq(annual_tax, 540_000.0);
+        // Cancelling or running out of input ends the prompt instead of asking again
+        assert!(matches!(scripted("salah\nq\n").prompt_f64("Gaji:"), Err(CalcError::Cancelled)));
+        assert!(matches!(scripted("0\n").read_marital_status(Lang::Id), Err(CalcError::Cancelled)));
+        // ...but 0 is an answer where the prompt accepts it
+        assert_eq!(scripted("0\n").prompt_optional_f64("Zakat:").unwrap(), 0.0);
+        assert_eq!(scripted("0\n").read_dependents(Lang::Id).unwrap(), 0);
+        assert!(matches!(scripted("salah\n").prompt_f64("Gaji:"), Err(CalcError::Io(_))));
     }
 
     #[test]
-    fn test_calculate_pph21_married_with_dependents() {
-        let params = PPh21Params {
-            gross_income: 10_000_000.0,
-            is_married: true,
-            num_dependents: 2,
-        };
+    fn test_session_calculates_pph21_and_exits() {
+        // Menu 1 for TK/0 at Rp 6,000,000, every optional answer left empty
+        let output = run_session("1\n6000000\n1\n\n\n\n\n\n\n27\n", Lang::Id);
         
-        let (annual_tax, monthly_tax, ptkp, _) = calculate_pph21(&params);
+        assert!(output.starts_with("=== KALKULATOR PAJAK"), "{}", output);
+        assert!(output.contains("=== HASIL PERHITUNGAN PPh 21 ==="));
+        assert!(output.contains("Status TK/0"));
+        assert!(output.contains("PPh 21 Sebulan      :         Rp 60.000,00"));
+        assert_eq!(output.matches("Pilih jenis perhitungan:").count(), 2);
+        assert!(output.trim_end().ends_with("Terima kasih telah menggunakan kalkulator pajak!"));
+    }
+
+    #[test]
+    fn test_session_matches_golden_file() {
+        // PPh 21 for TK/0, then PPN on Rp 1,000,000, then exit; set
+        // UPDATE_GOLDEN=1 to rewrite the file, as for tests/golden.rs
+        let output = run_session("1\n6000000\n1\n\n\n\n\n\n\n4\n1000000\n\n\n\n\n27\n", Lang::Id);
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden/interactive_session.txt");
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, &output).unwrap();
+            return;
+        }
         
-        // PTKP for K/2 should be 67,500,000
-        assert_approx_eq(ptkp, 67_500_000.0);
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        assert_eq!(output, expected, "output differs from {}", path.display());
+    }
+
+    #[test]
+    fn test_english_session_translates_prompts_and_results() {
+        // The golden session's PPh 21 and PPN, then the bonus menu for TK/0
+        let output = run_session("1\n6000000\n1\n\n\n\n\n\n\n4\n1000000\n\n\n\n\n12\n6000000\n1\n\n6000000\n27\n", Lang::En);
         
-        // PPh 21 = 0.75% of 10,000,000 = 75,000 per month
-        assert_approx_eq(monthly_tax, 75_000.0);
-        assert_approx_eq(annual_tax, 900_000.0);
+        assert!(output.contains(t("prompt.start_month", Lang::En)));
+        assert!(output.contains(t("prompt.start_day", Lang::En)));
+        assert!(output.contains("=== PPh 21 RESULT ==="));
+        assert!(output.contains("Monthly PPh 21      :         Rp 60.000,00"));
+        assert!(output.contains("PPN Result (11%):"));
+        assert!(output.contains("PPh 21 on Bonus/THR"));
+        for indonesian in ["Bulan mulai bekerja", "Tanggal mulai bekerja", "HASIL PERHITUNGAN", "Hasil Perhitungan", "Masukkan", "Gaji Bruto", "Sebulan", "Setahun", "per tahun"] {
+            assert!(!output.contains(indonesian), "{:?} in English output:\n{}", indonesian, output);
+        }
+    }
+    
+    #[test]
+    fn test_english_session_reports_rejected_answers_in_english() {
+        let output = run_session("1\nabc\n6000000\n4\n1\n\n\n\n\n\n\n27\n", Lang::En);
+        
+        assert!(output.contains("Invalid entry 'abc'."), "{}", output);
+        assert!(output.contains("Invalid status choice '4'."), "{}", output);
+        assert!(!output.contains("tidak valid"), "{}", output);
+    }
+    
+    #[test]
+    fn test_session_reports_an_invalid_choice_then_exits() {
+        let output = run_session("99\nh\n27\n", Lang::Id);
+        
+        assert!(output.contains("Pilihan tidak valid."));
+        assert!(output.contains("=== PENJELASAN PILIHAN MENU ==="));
+        assert_eq!(output.matches("Pilih jenis perhitungan:").count(), 3);
+        assert!(output.trim_end().ends_with("Terima kasih telah menggunakan kalkulator pajak!"));
+        
+        // A cancelled calculation returns to the menu rather than ending the session
+        let output = run_session("4\nq\n27\n", Lang::Id);
+        assert!(output.contains(t("menu.cancelled", Lang::Id)));
+        assert_eq!(output.matches("Pilih jenis perhitungan:").count(), 2);
+    }
+
+    #[test]
+    fn test_session_asks_again_for_a_gross_above_the_bound() {
+        // Rp 2 trillion a month is rejected at the gross prompt itself, before
+        // the status is asked, and the valid gross typed next is used
+        let output = run_session("1\n2000000000000\n6000000\n1\n\n\n\n\n\n\n27\n", Lang::Id);
+        
+        assert_eq!(output.matches(t("prompt.gross_monthly", Lang::Id)).count(), 2);
+        assert_eq!(output.matches("Penghasilan bruto melebihi batas wajar").count(), 1);
+        let rejected = output.find("melebihi batas wajar").unwrap();
+        assert!(rejected < output.find(t("prompt.marital", Lang::Id)).unwrap());
+        assert!(output.contains("PPh 21 Sebulan      :         Rp 60.000,00"));
+        assert_eq!(output.matches("Pilih jenis perhitungan:").count(), 2);
     }
 
     #[test]
-    fn test_gross_up_calculation() {
-        // Test with net salary that should result in DPP of 6,045,340
-        let net_salary = 6_000_000.0;
-        let dpp = 6_045_340.0;
-        let expected_pph21 = ((dpp * 0.75_f64) / 100.0).round() as f64;
+    fn test_session_reaches_k0_with_zero_dependents() {
+        // Married with 0 typed for dependents is K/0, not a cancelled calculation
+        let output = run_session("1\n6000000\n2\n\n0\n\n\n\n\n\n27\n", Lang::Id);
         
-        // The gross up should be net_salary + pph21
-        let expected_gross = net_salary + expected_pph21;
+        assert!(output.contains("Status K/0"), "{}", output);
+        assert!(!output.contains(t("menu.cancelled", Lang::Id)));
+        assert_eq!(output.matches("=== HASIL PERHITUNGAN PPh 21 ===").count(), 1);
+    }
+
+    #[test]
+    fn test_session_prints_a_december_refund_as_lebih_bayar() {
+        // TK/0 at Rp 6,000,000 owes Rp 720,000 for the year; Rp 1,000,000
+        // withheld January to November leaves Rp 280,000 to refund
+        let output = run_session("25\n6000000\n1\n\n1000000\n27\n", Lang::Id);
         
-        // The actual PPh 21 should be 0.75% of the DPP
-        assert_approx_eq(expected_pph21, 45_340.0);
+        assert!(output.contains(&format!("{:<20}: {:>20}", "PPh 21 Lebih Bayar (refund)", "Rp 280.000,00")), "{}", output);
+        assert!(!output.contains("-280") && !output.contains(&format!("{:<20}:", "PPh 21 Desember")));
         
-        // The gross salary should be 6,045,340
-        assert_approx_eq(expected_gross, 6_045_340.0);
+        let output = run_session("25\n6000000\n1\n\n1000000\n27\n", Lang::En);
+        assert!(output.contains("PPh 21 Overpaid (refund)"), "{}", output);
+    }
+    
+    #[test]
+    fn test_session_reports_a_rejected_calculation_and_returns_to_the_menu() {
+        // Overtime above the sanity bound fails once every answer is in; the
+        // session reports it and the next calculation still runs
+        let output = run_session("1\n6000000\n1\n\n\n2000000000000\n\n\n\n1\n6000000\n1\n\n\n\n\n\n\n27\n", Lang::Id);
+        
+        assert!(output.contains("Uang lembur melebihi batas wajar"), "{}", output);
+        assert_eq!(output.matches("Pilih jenis perhitungan:").count(), 3);
+        assert_eq!(output.matches("=== HASIL PERHITUNGAN PPh 21 ===").count(), 1);
+        assert!(output.trim_end().ends_with("Terima kasih telah menggunakan kalkulator pajak!"));
     }
 
     #[test]
-    fn test_ptkp_values() {
-        let ptkp = get_ptkp_values();
+    fn test_cli_parses_pph21_subcommand() {
+        let cli = Cli::try_parse_from(["tax_calculator", "pph21", "--gross", "6000000", "--married", "--dependents", "2"]).unwrap();
+        match cli.command {
+            Some(Command::Pph21 { gross, married, dependents, combined_income, disability, profile, save_profile, flat, no_npwp, take_home, jkk_class, deductions, gross_net_of_bpjs, npwp, period, sequence, display_currency, rate, trace }) => {
+                assert_eq!(gross, 6_000_000.0);
+                assert!(married);
+                assert_eq!(dependents, 2);
+                assert!(!combined_income);
+                assert!(!disability);
+                assert_eq!((profile, save_profile), (None, None));
+                assert!(!flat);
+                assert!(!no_npwp);
+                assert!(!take_home);
+                assert_eq!(jkk_class, None);
+                assert!(deductions.is_empty());
+                assert!(!gross_net_of_bpjs);
+                assert_eq!((npwp, period, sequence), (None, None, 1));
+                assert_eq!((display_currency, rate), (None, None));
+                assert!(!trace);
+            },
+            _ => panic!("expected the pph21 subcommand"),
+        }
         
-        assert_eq!(ptkp.get("TK/0"), Some(&54_000_000.0));
-        assert_eq!(ptkp.get("K/0"), Some(&58_500_000.0));
-        assert_eq!(ptkp.get("K/1"), Some(&63_000_000.0));
-        assert_eq!(ptkp.get("K/2"), Some(&67_500_000.0));
-        assert_eq!(ptkp.get("K/3"), Some(&72_000_000.0));
+        assert!(Cli::try_parse_from(["tax_calculator", "ppn"]).is_err());
+        match Cli::try_parse_from(["tax_calculator", "ppn", "--amount", "Rp1.500.000"]).unwrap().command {
+            Some(Command::Ppn { amount, .. }) => assert_eq!(amount, 1_500_000.0),
+            _ => panic!("expected the ppn subcommand"),
+        }
+        assert!(Cli::try_parse_from(["tax_calculator", "pph21", "--gross", "1", "--combined-income"]).is_err());
+        assert!(Cli::try_parse_from(["tax_calculator", "pph21", "--gross", "1", "--flat", "--take-home"]).is_err());
+        assert!(Cli::try_parse_from(["tax_calculator", "pph21", "--gross", "1", "--npwp", "012345678901000", "--no-npwp"]).is_err());
+        assert!(Cli::try_parse_from(["tax_calculator", "pph21", "--gross", "1", "--period", "10/2024"]).is_err());
+        assert!(Cli::try_parse_from(["tax_calculator", "--format", "markdown", "pph21", "--gross", "1"]).unwrap().format == OutputFormat::Markdown);
+        assert_eq!(Cli::try_parse_from(["tax_calculator", "ppn", "--amount", "1", "--quiet"]).unwrap().output_format(), OutputFormat::Quiet);
+        assert!(Cli::try_parse_from(["tax_calculator", "--quiet", "--format", "json", "ppn", "--amount", "1"]).is_err());
+        assert!(Cli::try_parse_from(["tax_calculator", "--format", "quiet", "ppn", "--amount", "1"]).is_err());
+        assert_eq!(Cli::try_parse_from(["tax_calculator", "--summary", "ppn", "--amount", "1"]).unwrap().output_format(), OutputFormat::Summary);
+        assert!(Cli::try_parse_from(["tax_calculator", "--summary", "--format", "json", "ppn", "--amount", "1"]).is_err());
+        assert!(Cli::try_parse_from(["tax_calculator"]).unwrap().command.is_none());
+        assert_eq!(Cli::try_parse_from(["tax_calculator", "--year", "2022"]).unwrap().year, Some(2022));
+        assert!(Cli::try_parse_from(["tax_calculator", "--year", "2022", "--config", "tabel.toml"]).is_err());
     }
 
     #[test]
-    fn test_zero_income() {
-        let params = PPh21Params {
-            gross_income: 0.0,
-            is_married: false,
-            num_dependents: 0,
-        };
+    fn test_color_choice_respects_terminal_and_no_color() {
+        assert!(color_enabled(ColorChoice::Auto, true, None));
+        assert!(!color_enabled(ColorChoice::Auto, false, None));
+        assert!(!color_enabled(ColorChoice::Auto, true, Some(OsString::from("1"))));
+        // An empty NO_COLOR does not disable color
+        assert!(color_enabled(ColorChoice::Auto, true, Some(OsString::new())));
+        assert!(color_enabled(ColorChoice::Always, false, Some(OsString::from("1"))));
+        assert!(!color_enabled(ColorChoice::Never, true, None));
+    }
+
+    #[test]
+    fn test_highlight_totals_only_colors_tax_lines() {
+        owo_colors::set_override(true);
+        let output = highlight_totals("PKP                 :     Rp 14.400.000,00\nPPh 21 Sebulan      :         Rp 60.000,00\n");
+        owo_colors::unset_override();
         
-        let (annual_tax, monthly_tax, _, _) = calculate_pph21(&params);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines[0], "PKP                 :     Rp 14.400.000,00");
+        assert!(lines[1].starts_with('\u{1b}') && lines[1].contains("PPh 21 Sebulan"), "{:?}", lines[1]);
+        assert!(output.ends_with('\n'));
         
-        assert_approx_eq(annual_tax, 0.0);
-        assert_approx_eq(monthly_tax, 0.0);
+        // English results highlight the same figures
+        owo_colors::set_override(true);
+        let output = highlight_totals("Monthly PPh 21      :         Rp 60.000,00\n");
+        owo_colors::unset_override();
+        assert!(output.starts_with('\u{1b}'), "{:?}", output);
     }
 }
 
 fn main() {
-    println!("=== KALKULATOR PAJAK ===");
+    // Batch and gross-up logs, filtered by RUST_LOG, go to stderr apart from results
+    env_logger::init();
+    let cli = Cli::parse();
+    // Piped answers keep stdout for the bare result, so their errors go to stderr
+    let piped = cli.reads_piped_input();
+    let lang = cli.lang;
+    if let Err(err) = run(cli, piped) {
+        let message = err.message().text(lang);
+        if piped {
+            eprintln!("{}", message.if_supports_color(Stream::Stderr, |text| text.red().to_string()));
+        } else {
+            print_error(&message);
+        }
+        std::process::exit(err.exit_code());
+    }
+}
+
+// Everything after argument parsing; the error decides the exit code, see
+// CalcError::exit_code. `piped` is Cli::reads_piped_input, checked before
+// the subcommand is taken out of `cli`
+fn run(cli: Cli, piped: bool) -> Result<(), CalcError> {
+    let format = cli.output_format();
+    owo_colors::set_override(color_enabled(cli.color, io::stdout().is_terminal(), std::env::var_os("NO_COLOR")));
+    // An explicit --year skips the config file found in the working directory
+    let config_path = if cli.year.is_some() { None } else { discover_config(cli.config.as_deref(), Path::new(".")) };
+    let config = match (config_path.as_deref(), cli.year) {
+        (Some(path), _) => TaxConfig::load(path).map_err(CalcError::File)?,
+        (None, Some(year)) => TaxConfig::for_year(year).map_err(CalcError::Parse)?,
+        (None, None) => TaxConfig::default(),
+    };
+    
+    if let Some(code) = cli.request.as_deref() {
+        let params = decode_request(code)?;
+        let result = calculate_pph21_result(&params, Pph21Method::Progressive, &config);
+        log_history(cli.history.as_deref(), "pph21", &params, &result, cli.lang);
+        match format {
+            OutputFormat::Json => print_json(&result),
+            OutputFormat::Toml => print_toml(&result),
+            OutputFormat::Markdown => print!("{}", render_markdown_table(&pph21_result_rows(&result, cli.lang), cli.lang)),
+            OutputFormat::Text => print!("{}", render_pph21_summary(t("result.pph21_summary", cli.lang), &params, &config, cli.lang)),
+            OutputFormat::Quiet => println!("{}", result.monthly_tax),
+            OutputFormat::Summary => print!("{}", render_brief(&pph21_brief_rows(&result, cli.lang))),
+        }
+        return Ok(());
+    }
+    
+    if let Some(name) = cli.preset.as_deref() {
+        let preset = config.find_preset(name).ok_or_else(|| {
+            let names: Vec<&str> = config.presets.iter().map(|preset| preset.name.as_str()).collect();
+            CalcError::Parse(Message::new("error.preset_not_found", &[&name, &names.join(", ")]))
+        })?;
+        let params = preset.params()?;
+        let result = calculate_pph21_result(&params, Pph21Method::Progressive, &config);
+        log_history(cli.history.as_deref(), "pph21", &params, &result, cli.lang);
+        match format {
+            OutputFormat::Json => print_json(&result),
+            OutputFormat::Toml => print_toml(&result),
+            OutputFormat::Markdown => print!("{}", render_markdown_table(&pph21_result_rows(&result, cli.lang), cli.lang)),
+            OutputFormat::Text => print!("{}", render_preset(preset, &config, cli.lang)?),
+            OutputFormat::Quiet => println!("{}", result.monthly_tax),
+            OutputFormat::Summary => print!("{}", render_brief(&pph21_brief_rows(&result, cli.lang))),
+        }
+        return Ok(());
+    }
     
-    // PPh 21 Tax brackets (Indonesia 2023)
-    let tax_brackets = vec![
-        TaxBracket { lower_bound: 0.0, upper_bound: 50_000_000.0, rate: 0.05 },
-        TaxBracket { lower_bound: 50_000_000.0, upper_bound: 250_000_000.0, rate: 0.15 },
-        TaxBracket { lower_bound: 250_000_000.0, upper_bound: 500_000_000.0, rate: 0.25 },
-        TaxBracket { lower_bound: 500_000_000.0, upper_bound: f64::MAX, rate: 0.30 },
-    ];
+    match cli.command {
+        Some(Command::Selfcheck) => {
+            let results = run_selfcheck(&config.ptkp, &config.brackets, cli.lang);
+            for result in &results {
+                println!("{} {}", if result.passed { "PASS" } else { "FAIL" }, result.name);
+            }
+            println!("{}", t_with("selfcheck.fingerprint", cli.lang, &[&config.fingerprint()]));
+            if results.iter().any(|result| !result.passed) {
+                return Err(CalcError::CheckFailed);
+            }
+            return Ok(());
+        },
+        Some(Command::Pph21 { gross, married, dependents, combined_income, disability, profile, save_profile: save_as, flat, no_npwp, take_home, jkk_class, deductions, gross_net_of_bpjs, npwp, period, sequence, display_currency, rate, trace }) => {
+            let params = match profile {
+                Some(name) => load_profile(&cli.profiles, &name).map_err(CalcError::Parse)?.to_builder().gross_income(gross).build()?,
+                None => PPh21Params::builder()
+                    .gross_income(gross)
+                    .married(married)
+                    .dependents(if married { dependents } else { 0 })
+                    .spouse_income_combined(combined_income)
+                    .disability_adjustment(disability)
+                    .has_npwp(!no_npwp)
+                    .build()?,
+            };
+            if let Some(name) = save_as {
+                save_profile(&cli.profiles, &name, &params).map_err(CalcError::File)?;
+                // Reported on stderr so --format json and --quiet output stays clean
+                eprintln!("{}", t_with("profile.saved", cli.lang, &[&name, &cli.profiles.display()]));
+            }
+            if take_home {
+                let payroll = if gross_net_of_bpjs { full_payroll_net_of_bpjs } else { full_payroll_with_risk_class };
+                let report = payroll(&params, jkk_class.unwrap_or_default(), &config)
+                    .with_post_tax_deductions(deductions)
+                    .rounded_to(cli.round_to);
+                warn_deductions_exceed_net(&report, cli.lang);
+                log_history(cli.history.as_deref(), "payroll", &params, &report, cli.lang);
+                match format {
+                    OutputFormat::Json => print_json(&report),
+                    OutputFormat::Toml => print_toml(&report),
+                    OutputFormat::Markdown => print!("{}", render_markdown_table(&payroll_rows(&report, cli.lang), cli.lang)),
+                    OutputFormat::Text => print_result(&render_payroll(&report, cli.lang)),
+                    OutputFormat::Quiet => println!("{}", report.net_monthly),
+                    OutputFormat::Summary => print!("{}", render_brief(&payroll_brief_rows(&report, cli.lang))),
+                }
+                return Ok(());
+            }
+            if trace {
+                let mut trace = trace_pph21(&params, &config, cli.lang);
+                trace.result = trace.result.rounded_to(cli.round_to);
+                log_history(cli.history.as_deref(), "pph21", &params, &trace.result, cli.lang);
+                match format {
+                    OutputFormat::Json => print_json(&trace),
+                    OutputFormat::Toml => print_toml(&trace),
+                    OutputFormat::Markdown => print!("{}", render_markdown_table(&pph21_result_rows(&trace.result, cli.lang), cli.lang)),
+                    OutputFormat::Text => print!("{}", render_trace(&trace.steps, cli.lang)),
+                    OutputFormat::Quiet => println!("{}", trace.result.monthly_tax),
+                    OutputFormat::Summary => print!("{}", render_brief(&pph21_brief_rows(&trace.result, cli.lang))),
+                }
+                return Ok(());
+            }
+            let slip = npwp
+                .map(|npwp| {
+                    let (month, year) = match period {
+                        Some(period) => parse_period(&period)?,
+                        None => {
+                            let today = SystemClock.today();
+                            (today.month(), today.year())
+                        },
+                    };
+                    bukti_potong(&npwp, month, year, sequence)
+                })
+                .transpose()?;
+            let currency = display_currency.zip(rate).map(|(code, rate)| DisplayCurrency::new(&code, rate)).transpose()?;
+            let method = if flat { Pph21Method::Flat } else { Pph21Method::Progressive };
+            let result = calculate_pph21_result(&params, method, &config).rounded_to(cli.round_to);
+            log_history(cli.history.as_deref(), "pph21", &params, &result, cli.lang);
+            match (format, slip) {
+                (OutputFormat::Json, Some(bukti_potong)) => print_json(&Pph21WithBuktiPotong { result, bukti_potong }),
+                (OutputFormat::Json, None) => print_json(&result),
+                (OutputFormat::Toml, Some(bukti_potong)) => print_toml(&Pph21WithBuktiPotong { result, bukti_potong }),
+                (OutputFormat::Toml, None) => print_toml(&result),
+                (OutputFormat::Quiet, _) => println!("{}", result.monthly_tax),
+                (OutputFormat::Summary, _) => print!("{}", render_brief(&pph21_brief_rows(&result, cli.lang))),
+                (OutputFormat::Markdown, slip) => {
+                    if let Some(slip) = slip {
+                        println!("**{}:** {}  \n**NPWP:** {}\n", t("label.bukti_potong", cli.lang), slip.number, slip.npwp);
+                    }
+                    print!("{}", render_markdown_table(&pph21_result_rows(&result, cli.lang), cli.lang));
+                },
+                (OutputFormat::Text, slip) => {
+                    print_result(&render_gross_worksheet(&params, method, &config, cli.lang));
+                    if cli.round_to > 0 {
+                        print_result(&format!("{} {:>20}\n", t_with("result.rounded_monthly_tax", cli.lang, &[&cli.round_to]), format_rupiah(result.monthly_tax)));
+                    }
+                    if let Some(slip) = slip {
+                        println!("\n{}", render_bukti_potong(&slip, cli.lang));
+                    }
+                    if let Some(currency) = currency {
+                        print!("\n{}", render_converted_result(&result, &currency, cli.lang));
+                    }
+                },
+            }
+            return Ok(());
+        },
+        Some(Command::Ppn { amount, rate, inclusive }) => {
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(CalcError::OutOfRange(Message::new("error.positive_number", &[])));
+            }
+            let rate = resolve_vat_rate(rate.as_deref(), env_vat_rate().as_deref(), config.vat_rate)?;
+            let mode = if inclusive { VatMode::Inclusive } else { VatMode::Exclusive };
+            let result = calculate_vat_with_mode(amount, VatStatus::Standard(rate), mode).rounded_to(cli.round_to);
+            log_history(cli.history.as_deref(), "ppn", &serde_json::json!({ "amount": amount, "rate": rate, "inclusive": inclusive }), &result, cli.lang);
+            match format {
+                OutputFormat::Json => {
+                    print_json(&result);
+                    return Ok(());
+                },
+                OutputFormat::Toml => {
+                    print_toml(&result);
+                    return Ok(());
+                },
+                OutputFormat::Markdown => {
+                    print!("{}", render_markdown_table(&[
+                        (t("label.price_before_vat", cli.lang), result.base),
+                        ("PPN", result.vat),
+                        (t("label.total_due", cli.lang), result.base + result.vat),
+                    ], cli.lang));
+                    return Ok(());
+                },
+                OutputFormat::Quiet => {
+                    println!("{}", result.vat);
+                    return Ok(());
+                },
+                OutputFormat::Summary => {
+                    let vat_label = format!("PPN ({})", result.status.label(cli.lang));
+                    print!("{}", render_brief(&[(&vat_label, result.vat), (t("label.total_due", cli.lang), result.base + result.vat)]));
+                    return Ok(());
+                },
+                OutputFormat::Text => {},
+            }
+            print_result(&render_vat(&result, None, cli.lang));
+            return Ok(());
+        },
+        Some(Command::Batch { input, validate: true, .. }) => {
+            let report = File::open(&input)
+                .map_err(|err| Message::new("error.open_file", &[&input.display(), &err]))
+                .and_then(validate_batch)
+                .map_err(CalcError::File)?;
+            // Each message names the column at fault, if there is one
+            for error in &report.skipped {
+                eprintln!("{}", t_with("batch.row_error", cli.lang, &[&error.line, &error.message.text(cli.lang)]));
+            }
+            println!("{}", t_with("batch.validated", cli.lang, &[&report.processed, &report.skipped.len()]));
+            if !report.skipped.is_empty() {
+                return Err(CalcError::CheckFailed);
+            }
+            return Ok(());
+        },
+        Some(Command::Batch { input, output, validate: false }) => {
+            let output = output.expect("--output is required without --validate");
+            let files = File::open(&input)
+                .map_err(|err| Message::new("error.open_file", &[&input.display(), &err]))
+                .and_then(|input| {
+                    File::create(&output)
+                        .map(|output| (input, output))
+                        .map_err(|err| Message::new("error.create_file", &[&output.display(), &err]))
+                });
+            let report = files.and_then(|(input, output)| run_batch(input, output, &config)).map_err(CalcError::File)?;
+            for error in &report.skipped {
+                eprintln!("{}", t_with("batch.row_skipped", cli.lang, &[&error.line, &error.message.text(cli.lang)]));
+            }
+            println!("{}", t_with("batch.done", cli.lang, &[&report.processed, &report.skipped.len(), &output.display()]));
+            return Ok(());
+        },
+        #[cfg(feature = "pdf")]
+        Some(Command::Slip { name, period, gross, married, dependents, combined_income, no_npwp, deductions, output }) => {
+            let params = PPh21Params::builder()
+                .gross_income(gross)
+                .married(married)
+                .dependents(if married { dependents } else { 0 })
+                .spouse_income_combined(combined_income)
+                .has_npwp(!no_npwp)
+                .build()?;
+            let period = period.unwrap_or_else(|| SystemClock.today().format("%m/%Y").to_string());
+            let report = full_payroll(&params, &config).with_post_tax_deductions(deductions);
+            warn_deductions_exceed_net(&report, cli.lang);
+            write_slip_pdf(&output, &name, &period, &report).map_err(CalcError::File)?;
+            println!("{}", t_with("slip.saved", cli.lang, &[&name, &period, &output.display()]));
+            return Ok(());
+        },
+        #[cfg(feature = "server")]
+        Some(Command::Serve { addr }) => {
+            return tax_calculator::server::run(addr, config).map_err(|err| CalcError::File(Message::new("error.server", &[&err])));
+        },
+        #[cfg(feature = "schema")]
+        Some(Command::Schema) => {
+            println!("{}", serde_json::to_string_pretty(&tax_calculator::schema::schemas()).unwrap());
+            return Ok(());
+        },
+        None => {},
+    }
     
-    // Default VAT rate (in percentage)
-    let default_vat_rate = 11.0; // 11%
+    let default_vat_rate = resolve_vat_rate(None, env_vat_rate().as_deref(), config.vat_rate)?;
+    let income_tax_brackets = cli.brackets.as_deref().map(load_brackets_csv).transpose()?;
+    let income_tax_brackets = income_tax_brackets.as_deref().unwrap_or(&config.brackets);
+
+    // Piped input is read as a compact answer sequence with no prompts
+    if piped {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        println!("{}", run_piped(&input, &config, income_tax_brackets, default_vat_rate)?);
+        return Ok(());
+    }
+    
+    let warn_outdated = config_path.is_none() && cli.year.is_none();
+    match run_interactive(io::stdin().lock(), io::stdout(), &config, income_tax_brackets, warn_outdated, cli.lang, default_vat_rate) {
+        // Ctrl-D or the end of a piped session closes the menu as if Keluar was chosen
+        Err(CalcError::Io(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+            println!("\n{}", t("app.goodbye", cli.lang));
+            Ok(())
+        },
+        result => result,
+    }
+}
+
+// Answer a whitespace-separated menu sequence without prompts, returning just
+// the result: "1 <gross> <status> [dependents]" for monthly PPh 21, "2 <net>
+// <status> [dependents]" for the gross-up salary, "3 <pkp>" for income tax and
+// "4 <amount> [rate]" for PPN at `default_vat_rate` when the rate is left
+// out. Status is 1/2/3 as in the menu; dependents are only read for a
+// married status. Income tax uses `tax_brackets`
+fn run_piped(input: &str, config: &TaxConfig, tax_brackets: &[TaxBracket], default_vat_rate: f64) -> Result<f64, CalcError> {
+    let mut tokens = input.split_whitespace();
+    let mut next = |what: &'static str| {
+        tokens.next().ok_or_else(|| CalcError::Parse(Message::with_args("error.piped_missing", vec![MessageArg::Message(Message::new(what, &[]))])))
+    };
+    
+    let choice = next("piped.choice")?;
+    match choice {
+        "1" | "2" => {
+            let amount = if choice == "1" { parse_amount(next("piped.gross")?)? } else { validate_requested_net(next("piped.net")?)? };
+            let (is_married, spouse_income_combined) = match next("piped.marital")? {
+                "1" => (false, false),
+                "2" => (true, false),
+                "3" => (true, true),
+                other => return Err(CalcError::Parse(Message::new("error.invalid_status", &[&other]))),
+            };
+            let num_dependents = if is_married { Dependents::parse(next("piped.dependents")?)?.count() } else { 0 };
+            let params = PPh21Params::builder()
+                .gross_income(amount)
+                .married(is_married)
+                .dependents(num_dependents)
+                .spouse_income_combined(spouse_income_combined)
+                .build()?;
+            if choice == "1" {
+                Ok(calculate_pph21_with_method(&params, Pph21Method::Progressive, config).1)
+            } else {
+                gross_up(amount, &params, config)
+            }
+        },
+        "3" => Ok(calculate_income_tax_with_rounding(parse_amount(next("piped.pkp")?)?, tax_brackets, true)),
+        "4" => {
+            let amount = parse_amount(next("piped.amount")?)?;
+            let rate = parse_vat_rate(tokens.next().unwrap_or(""), default_vat_rate)?;
+            Ok(calculate_vat(amount, rate))
+        },
+        other => Err(CalcError::Parse(Message::new("error.piped_choice", &[&other]))),
+    }
+}
+
+// Interactive menu reading answers from `input` and writing prompts and
+// results to `output`; invalid entries are asked again, so only a read failure
+// (such as a closed stdin) ends it early. PPN prompts offer `default_vat_rate`,
+// and the general income tax options use `tax_brackets`
+fn run_interactive<R: BufRead, W: Write>(
+    input: R,
+    output: W,
+    config: &TaxConfig,
+    tax_brackets: &[TaxBracket],
+    warn_outdated: bool,
+    lang: Lang,
+    default_vat_rate: f64,
+) -> Result<(), CalcError> {
+    let mut console = Console { input, output, lang };
+    writeln!(console, "{}", t("app.title", lang))?;
+    let clock = SystemClock;
+    // The warning is about the default tables, so a loaded config or chosen year skips it
+    if let Some(warning) = outdated_tables_warning(&clock, lang).filter(|_| warn_outdated) {
+        writeln!(console, "{}", warning.if_supports_color(Stream::Stdout, |text| text.yellow().to_string()))?;
+    }
+    
+    // Salary payments per year for PPh 21, 13 in gaji ke-13/THR mode
+    let mut months_per_year = 12;
     
     loop {
-        println!("\nPilih jenis perhitungan:");
-        println!("1. Hitung PPh 21 (Pegawai Tetap) - Gross");
-        println!("2. Hitung PPh 21 (Pegawai Tetap) - Gross Up");
-        println!("3. Hitung Pajak Penghasilan Umum");
-        println!("4. Hitung PPN (Pajak Pertambahan Nilai)");
-        println!("5. Keluar");
-        
-        let mut choice = String::new();
-        io::stdin().read_line(&mut choice).expect("Gagal membaca input");
-        
-        match choice.trim() {
-            "1" => {
-                // PPh 21 Calculation (Gross)
-                println!("\n=== Perhitungan PPh 21 (Pegawai Tetap) - Gross ===");
-                println!("\n* Karyawan menanggung sendiri pajak penghasilannya");
+        writeln!(console, "\n{}", t("menu.choose", lang))?;
+        for option in 1..=27 {
+            writeln!(console, "{}. {}", option, t(&format!("menu.{}", option), lang))?;
+        }
+        writeln!(console, "{}", t("menu.cancel_hint", lang))?;
+        writeln!(console, "{}", t("menu.help_hint", lang))?;
+        
+        let choice = read_line_from(&mut console.input)?;
+        if choice.trim() == "27" {
+            writeln!(console, "\n{}", t("app.goodbye", lang))?;
+            return Ok(());
+        }
+        if choice.trim().eq_ignore_ascii_case("h") {
+            write!(console, "\n{}", menu_help(lang))?;
+            continue;
+        }
+        
+        // A calculation ends early with CalcError::Cancelled when q, or a 0 the
+        // prompt does not accept, is entered
+        let mut run_choice = || -> Result<(), CalcError> {
+            match choice.trim() {
+                "1" | "8" => {
+                    // PPh 21 Calculation (Gross), progressive or the legacy flat rate
+                    let method = if choice.trim() == "8" { Pph21Method::Flat } else { Pph21Method::Progressive };
+                    writeln!(console, "\n{}", t("section.gross", lang))?;
+                    if method == Pph21Method::Flat {
+                        writeln!(console, "{}", t_with("note.flat_rate", lang, &[&config.flat_rate]))?;
+                    }
+                    writeln!(console, "\n{}", t("note.employee_bears_tax", lang))?;
+                    
+                    // Get gross income
+                    let amount = console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?;
+                    
+                    // Get marital status
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    
+                    // Get number of dependents
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let zakat = console.prompt_optional_f64(&format!("\n{}", t("prompt.zakat", lang)))?;
+                    let overtime = console.prompt_optional_f64(&format!("\n{}", t("prompt.overtime", lang)))?;
+                    let pension_contribution = console.prompt_optional_f64(&format!("\n{}", t("prompt.pension", lang)))?;
+                    
+                    // Get start month for a mid-year start; the year is annualized over the months worked
+                    let months_worked = console.prompt(
+                        &format!("\n{}", t("prompt.start_month", lang)),
+                        |input| match input.trim() {
+                            "" => Ok(12),
+                            month => parse_in_range(month, 1, 12).map(|month| 13 - month as u8),
+                        },
+                    )?;
+                    
+                    // Get start day for a partial first month
+                    let month_days = days_in_month(clock.today());
+                    let factor = console.prompt(
+                        &format!("\n{}", t("prompt.start_day", lang)),
+                        |input| match input.trim() {
+                            "" => Ok(1.0),
+                            day => parse_in_range(day, 1, month_days).map(|day| proration_factor(day, month_days)),
+                        },
+                    )?;
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(amount)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .zakat(zakat)
+                        .overtime(overtime)
+                        .pension_contribution(pension_contribution)
+                        .months_per_year(months_per_year)
+                        .months_worked(months_worked)
+                        .build()?;
+                    
+                    console.print_result(&render_gross_worksheet(&params, method, config, lang))?;
+                    writeln!(console, "\n{}", t_with("result.request_code", lang, &[&encode_request(&params)]))?;
+                    
+                    if factor < 1.0 {
+                        writeln!(console, "\n{}", t_with("result.first_month", lang, &[&format!("{:.2}", factor * 100.0)]))?;
+                        writeln!(console, "{:<20}: {:>20}", t("label.month_pay", lang), format_rupiah(round_rupiah(amount * factor, config.rounding)))?;
+                        console.print_result(&format!(
+                            "{:<20}: {:>20}\n",
+                            t("label.this_month_tax", lang),
+                            format_rupiah(calculate_pph21_partial_month(&params, factor, method, config))
+                        ))?;
+                    }
+                },
+                "2" => {
+                    writeln!(console, "\n{}", t("section.gross_up", lang))?;
+                    writeln!(console, "{}", t("note.company_bears_tax", lang))?;
+                    let net_salary = console.prompt(&format!("\n{}", t("prompt.net_monthly", lang)), validate_requested_net)?;
+                    
+                    // Get marital status
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    
+                    // Get number of dependents
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(net_salary)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()?;
+                    let gross_salary = gross_up(net_salary, &params, config)?;
+                    console.print_result(&render_gross_up_worksheet(net_salary, gross_salary, &params, config, lang))?;
+                },
+                "3" => {
+                    writeln!(console, "\n{}", t("section.income_tax", lang))?;
+                    let amount = console.prompt_f64(t("prompt.pkp", lang))?;
+                    
+                    let round_pkp = console.prompt_bool(t("prompt.round_pkp", lang), true)?;
+                    
+                    let tax = calculate_income_tax_with_rounding(amount, tax_brackets, round_pkp);
+                    writeln!(console, "\n{}", t("result.income_tax", lang))?;
+                    writeln!(console, "{}: {:>20}", t("label.taxable_income", lang), format_rupiah(amount))?;
+                    if round_pkp {
+                        writeln!(console, "{}: {:>20}", t("label.pkp_rounded", lang), format_rupiah(round_down_pkp(amount)))?;
+                    }
+                    writeln!(console, "{}: {:>20}", t("label.tax_due", lang), format_rupiah(tax))?;
+                    writeln!(console, "\n{}", t("result.bracket_breakdown", lang))?;
+                    let pkp = if round_pkp { round_down_pkp(amount) } else { amount };
+                    write!(console, "{}", render_income_tax_breakdown(&income_tax_breakdown(pkp, tax_brackets)))?;
+                    writeln!(console, "\n{}: {:>20}", t("label.net_income", lang), format_rupiah(amount - tax))?;
+                    if let Some(bracket) = tax_brackets.iter().find(|bracket| bracket.contains(amount)) {
+                        writeln!(console, "{}", t_with("result.marginal_rate", lang, &[&(bracket.rate * 100.0)]))?;
+                    }
+                },
+                "4" => {
+                    writeln!(console, "\n{}", t("section.vat", lang))?;
+                    let amount = console.prompt_f64(t("prompt.price", lang))?;
+                    
+                    let vat_mode = console.prompt(&format!("\n{}", t("prompt.vat_mode", lang)), |input| match input.trim() {
+                        "" | "1" => Ok(VatMode::Exclusive),
+                        "2" => Ok(VatMode::Inclusive),
+                        other => Err(CalcError::Parse(Message::new("error.invalid_choice_1_2", &[&other]))),
+                    })?;
+                    
+                    let vat_type = console.prompt(&format!("\n{}", t("prompt.vat_type", lang)), |input| match input.trim() {
+                        choice @ ("" | "1" | "2" | "3") => Ok(choice.to_string()),
+                        other => Err(CalcError::Parse(Message::new("error.invalid_vat_type", &[&other]))),
+                    })?;
+                    
+                    let mut rate_change = None;
+                    let vat_status = match vat_type.as_str() {
+                        "2" => VatStatus::ZeroRated,
+                        "3" => VatStatus::Exempt,
+                        _ => {
+                            let vat_rate = console.prompt(
+                                &t_with("prompt.vat_rate", lang, &[&default_vat_rate]),
+                                |input| parse_vat_rate(input, default_vat_rate),
+                            )?;
+                            
+                            let change_month = console.prompt(
+                                t("prompt.rate_change_month", lang),
+                                |input| match input.trim() {
+                                    "" => Ok(None),
+                                    month => parse_in_range(month, 1, 12).map(Some),
+                                },
+                            )?;
+                            if let Some(effective_month) = change_month {
+                                let new_rate = console.prompt(t("prompt.new_vat_rate", lang), |input| match input.trim() {
+                                    "" => Err(CalcError::Parse(Message::new("error.new_vat_rate_required", &[]))),
+                                    rate => parse_vat_rate(rate, vat_rate),
+                                })?;
+                                rate_change = Some(RateChange { effective_month, new_rate });
+                            }
+                            
+                            VatStatus::Standard(vat_rate)
+                        }
+                    };
+                    
+                    let result = calculate_vat_with_mode(amount, vat_status, vat_mode);
+                    writeln!(console)?;
+                    console.print_result(&render_vat(&result, rate_change.as_ref(), lang))?;
+                },
                 
-                // Get gross income
-                println!("\nMasukkan Penghasilan Bruto per bulan (Rp):");
-                let mut income = String::new();
-                io::stdin().read_line(&mut income).expect("Gagal membaca input");
+                "5" => {
+                    writeln!(console, "\n{}", t("section.installments", lang))?;
+                    let annual = console.prompt_f64(t("prompt.annual_tax_estimate", lang))?;
+                    
+                    let periods = console.prompt(
+                        t("prompt.installment_periods", lang),
+                        |input| match input.trim() {
+                            "" => Ok(12),
+                            periods => parse_in_range(periods, 1, 12),
+                        },
+                    )?;
+                    
+                    writeln!(console, "\n{}", t_with("result.installments", lang, &[&periods]))?;
+                    let mut rows: Vec<Vec<TableCell>> = installments(annual, periods)
+                        .into_iter()
+                        .enumerate()
+                        .map(|(period, amount)| vec![
+                            TableCell::Text(t_with("table.installment", lang, &[&(period + 1)])),
+                            TableCell::Rupiah(amount),
+                        ])
+                        .collect();
+                    rows.push(vec![TableCell::Text(t("table.total", lang).to_string()), TableCell::Rupiah(annual)]);
+                    console.print_result(&render_table(&[t("table.period", lang), t("table.amount", lang)], &rows))?;
+                },
                 
-                // Get marital status
-                println!("\nStatus Perkawinan:");
-                println!("1. Belum Kawin");
-                println!("2. Kawin");
-                let mut status = String::new();
-                io::stdin().read_line(&mut status).expect("Gagal membaca input");
-                let is_married = status.trim() == "2";
+                "6" => {
+                    writeln!(console, "\n{}", t("section.gross_and_gross_up", lang))?;
+                    writeln!(console, "{}", t("note.gross_and_gross_up", lang))?;
+                    
+                    let amount = console.prompt(&format!("\n{}", t("prompt.salary_monthly", lang)), validate_requested_net)?;
+                    
+                    // Get marital status
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    
+                    // Get number of dependents
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(amount)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()?;
+                    console.print_result(&render_gross_and_gross_up(&params, config, lang)?)?;
+                },
                 
-                // Get number of dependents
-                let mut num_dependents = 0;
-                if is_married {
-                    println!("\nJumlah Tanggungan (anak/kondisi lain):");
-                    let mut deps = String::new();
-                    io::stdin().read_line(&mut deps).expect("Gagal membaca input");
-                    num_dependents = deps.trim().parse().unwrap_or(0);
-                    if num_dependents > 3 { num_dependents = 3; } // Max 3 dependents for tax purposes
-                }
+                "7" => {
+                    writeln!(console, "\n{}", t("section.self_check", lang))?;
+                    let amount = console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?;
+                    
+                    // Get marital status
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    
+                    // Get number of dependents
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let claimed = console.prompt_f64(&format!("\n{}", t("prompt.claimed_tax", lang)))?;
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(amount)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()?;
+                    let check = check_self_computed(&params, claimed, SELF_CHECK_TOLERANCE, config);
+                    
+                    writeln!(console, "\n{:<23}: {:>20}", t("label.claimed_tax", lang), format_rupiah(check.claimed))?;
+                    writeln!(console, "{:<23}: {:>20}", t("label.expected_tax", lang), format_rupiah(check.expected))?;
+                    if check.matches {
+                        writeln!(console, "\n{}", t_with("result.matches", lang, &[&format_rupiah(SELF_CHECK_TOLERANCE)]))?;
+                    } else {
+                        writeln!(console, "\n{}", t("result.mismatch", lang))?;
+                        writeln!(console, "{}: {:>20}", t("label.difference", lang), format_rupiah(check.difference))?;
+                        writeln!(console, "\n{}", t("result.correct_worksheet", lang))?;
+                        console.print_result(&render_gross_worksheet(&params, Pph21Method::Progressive, config, lang))?;
+                    }
+                },
                 
-                match income.trim().parse::<f64>() {
-                    Ok(amount) if amount >= 0.0 => {
-                        let params = PPh21Params {
-                            gross_income: amount,
-                            is_married,
-                            num_dependents,
-                        };
-                        
-                        let (annual_tax, monthly_tax, ptkp, pkp) = calculate_pph21(&params);
-                        let ptkp_key = format!("{}/{}", 
-                            if is_married { "K" } else { "TK" },
-                            num_dependents
-                        );
-                        
-                        println!("\n=== HASIL PERHITUNGAN PPh 21 ===");
-                        println!("Penghasilan Bruto per bulan: Rp{:>15}", amount.separate_with_commas());
-                        println!("Penghasilan Bruto setahun:  Rp{:>15}", (amount * 12.0).separate_with_commas());
-                        println!("\nStatus: {}", if is_married { "Kawin" } else { "Belum Kawin" });
-                        if is_married {
-                            println!("Jumlah Tanggungan: {}", num_dependents);
-                        }
-                        
-                        // Display PTKP and PKP details
-                        println!("\n[Penghasilan Tidak Kena Pajak (PTKP)]");
-                        println!("Status {:<5}: Rp{:>15} per tahun", ptkp_key, ptkp.separate_with_commas());
-                        
-                        println!("\n[Penghasilan Kena Pajak (PKP)]");
-                        println!("Gaji Setahun - PTKP: Rp{:>15} - Rp{:>15} = Rp{:>15}", 
-                            (amount * 12.0).separate_with_commas(),
-                            ptkp.separate_with_commas(),
-                            pkp.separate_with_commas());
-                        
-                        // Display PPh 21 calculation details
-                        println!("\n[Perhitungan PPh 21 (0.75% x Gaji Bruto)]");
-                        println!("Per Bulan: 0.75% x Rp{:>15} = Rp{:>15}", 
-                            amount.separate_with_commas(),
-                            monthly_tax.separate_with_commas());
-                        println!("Per Tahun: 0.75% x Rp{:>15} = Rp{:>15}", 
-                            (amount * 12.0).separate_with_commas(),
-                            annual_tax.separate_with_commas());
-                        
-                        // Summary
-                        println!("\n[Ringkasan]");
-                        println!("Gaji Bruto Setahun  : Rp{:>15}", (amount * 12.0).separate_with_commas());
-                        println!("PTKP                : Rp{:>15} (-)", ptkp.separate_with_commas());
-                        println!("PKP                 : Rp{:>15}", pkp.separate_with_commas());
-                        println!("PPh 21 Setahun      : Rp{:>15}", annual_tax.separate_with_commas());
-                        println!("PPh 21 Sebulan      : Rp{:>15}", monthly_tax.separate_with_commas());
-                    },
-                    _ => println!("Masukan tidak valid. Harap masukkan angka positif."),
-                }
-            },
-            "2" => {
-                println!("\n=== Perhitungan PPh 21 (Pegawai Tetap) - Gross Up ===");
-                println!("* Perusahaan menanggung beban pajak karyawan");
-                println!("\nMasukkan gaji bersih yang diinginkan per bulan (dalam Rupiah):");
-                let mut net_salary_input = String::new();
-                io::stdin().read_line(&mut net_salary_input).expect("Gagal membaca input");
+                "9" => {
+                    writeln!(console, "\n{}", t("section.ter", lang))?;
+                    writeln!(console, "{}", t("note.ter", lang))?;
+                    
+                    let amount = console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?;
+                    
+                    // Get marital status
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    
+                    // Get number of dependents
+                    let num_dependents = console.read_dependents(lang)?;
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(amount)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .build()?;
+                    let ter_category = TerCategory::from_status(params.is_married, params.num_dependents);
+                    let rate = ter_rate(ter_category, amount);
+                    let monthly_tax = calculate_pph21_ter(&params, ter_category, config);
+                    
+                    writeln!(console, "\n{}", t("result.ter", lang))?;
+                    writeln!(console, "{:<27}: {:>20}", t("label.gross_monthly", lang), format_rupiah(amount))?;
+                    writeln!(console, "{:<27}: {}", t("label.ptkp_status", lang), params.ptkp_label())?;
+                    writeln!(console, "{:<27}: {}", t("label.ter_category", lang), ter_category.label())?;
+                    writeln!(console, "{:<27}: {:>15}%", t("label.effective_rate", lang), rate)?;
+                    console.print_result(&format!("{:<27}: {:>20}\n", t("label.monthly_tax", lang), format_rupiah(monthly_tax)))?;
+                    writeln!(console, "\n{}", t("note.ter_december", lang))?;
+                },
                 
-                match net_salary_input.trim().parse::<f64>() {
-                    Ok(net_salary) if net_salary >= 0.0 => {
-                        // Get marital status
-                        println!("\nStatus Perkawinan:");
-                        println!("1. Belum Kawin");
-                        println!("2. Kawin");
-                        let mut status = String::new();
-                        io::stdin().read_line(&mut status).expect("Gagal membaca input");
-                        let is_married = status.trim() == "2";
-                        
-                        // Get number of dependents
-                        let mut num_dependents = 0;
-                        if is_married {
-                            println!("\nJumlah Tanggungan (anak/kondisi lain):");
-                            let mut deps = String::new();
-                            io::stdin().read_line(&mut deps).expect("Gagal membaca input");
-                            num_dependents = deps.trim().parse().unwrap_or(0);
-                            if num_dependents > 3 { num_dependents = 3; }
-                        }
-                        
-                        // Calculate PPh 21 for gross up using exact DPP
-                        let dpp: f64 = 6_045_340.0;  // Exact DPP as specified
-                        let pph_21_percent: f64 = 0.75;  // 0.75% rate
-                        let pph_21_monthly = (dpp * pph_21_percent / 100.0).round() as i64;  // 45,340
-                        
-                        // Calculate gross salary (net_salary + pph_21_monthly)
-                        let gross_salary = net_salary + pph_21_monthly as f64;
-                        
-                        // Get PTKP for display
-                        let ptkp_key = format!("{}/{}", 
-                            if is_married { "K" } else { "TK" },
-                            num_dependents
-                        );
-                        let ptkp = get_ptkp_values().get(&*ptkp_key).copied().unwrap_or(0.0);
-                        
-                        // Calculate PKP for display
-                        let annual_gross = gross_salary * 12.0;
-                        let pkp = (annual_gross - ptkp).max(0.0);
-                        
-                        // Calculate taxes
-                        let monthly_tax = pph_21_monthly as f64;
-                        let annual_tax = (monthly_tax * 12.0).round();
-                        
-                        let ptkp_key = format!("{}/{}", 
-                            if is_married { "K" } else { "TK" },
-                            num_dependents
-                        );
-                        
-                        println!("\n=== HASIL PERHITUNGAN GROSS UP ===");
-                        
-                        // Employee Receives Section
-                        println!("\n[KARYAWAN MENERIMA]:");
-                        println!("Gaji Bersih (Take Home Pay): Rp{:>15} per bulan", net_salary.separate_with_commas());
-                        println!("Gaji Bersih Setahun       : Rp{:>15}", (net_salary * 12.0).separate_with_commas());
-                        
-                        // Company Pays Section
-                        println!("\n[PERUSAHAAN MENGELUARKAN]:");
-                        println!("Gaji Kotor (Gross Up) : Rp{:>15} per bulan", gross_salary.separate_with_commas());
-                        println!("Gaji Kotor Setahun    : Rp{:>15}", (gross_salary * 12.0).separate_with_commas());
-                        
-                        // Tax Calculation Section
-                        println!("\n[PERHITUNGAN PAJAK]:");
-                        println!("Status              : {}", if is_married { "Kawin" } else { "Belum Kawin" });
-                        if is_married {
-                            println!("Jumlah Tanggungan   : {}", num_dependents);
+                "10" => {
+                    writeln!(console, "\n{}", t("section.pph23", lang))?;
+                    let amount = console.prompt_f64(t("prompt.gross_amount", lang))?;
+                    
+                    let object = console.prompt(&format!("\n{}", t("prompt.pph23_object", lang)), |input| match input.trim() {
+                        "1" => Ok(Pph23Object::Services),
+                        "2" => Ok(Pph23Object::Dividends),
+                        "3" => Ok(Pph23Object::Royalties),
+                        "4" => Ok(Pph23Object::Interest),
+                        other => Err(CalcError::Parse(Message::new("error.invalid_pph23_object", &[&other]))),
+                    })?;
+                    
+                    let has_npwp = console.prompt_bool(t("prompt.payee_npwp", lang), true)?;
+                    
+                    let tax = calculate_pph23(amount, object, has_npwp, config);
+                    writeln!(console, "\n{}", t_with("result.pph23", lang, &[&object.label(lang)]))?;
+                    writeln!(console, "{}: {:>20}", t("label.gross_amount", lang), format_rupiah(amount))?;
+                    if has_npwp {
+                        writeln!(console, "{}", t_with("result.rate", lang, &[&(object.rate() * 100.0)]))?;
+                    } else {
+                        writeln!(console, "{}", t_with("result.rate_no_npwp", lang, &[&(object.rate() * 200.0)]))?;
+                    }
+                    console.print_result(&format!("{}: {:>20}\n", t("label.pph23_withheld", lang), format_rupiah(tax)))?;
+                    writeln!(console, "{}: {:>20}", t("label.amount_received", lang), format_rupiah(amount - tax))?;
+                },
+                
+                "11" => {
+                    writeln!(console, "\n{}", t("section.pph_final_42", lang))?;
+                    let object = console.prompt(
+                        t("prompt.final_object", lang),
+                        |input| match input.trim() {
+                            "1" => Ok(PphFinal42Object::Rental),
+                            "2" => Ok(PphFinal42Object::Construction(ConstructionQualification::Small)),
+                            "3" => Ok(PphFinal42Object::Construction(ConstructionQualification::MediumLarge)),
+                            "4" => Ok(PphFinal42Object::Construction(ConstructionQualification::Unqualified)),
+                            "5" => Ok(PphFinal42Object::LandSale),
+                            other => Err(CalcError::Parse(Message::new("error.invalid_final_object", &[&other]))),
+                        },
+                    )?;
+                    let gross = console.prompt_f64(&format!("\n{}", t("prompt.gross_value", lang)))?;
+                    
+                    let tax = calculate_pph_final_42(gross, object, config);
+                    writeln!(console, "\n{}", t_with("result.pph_final", lang, &[&object.label(lang)]))?;
+                    writeln!(console, "{}: {:>20}", t("label.gross_value", lang), format_rupiah(gross))?;
+                    writeln!(console, "{}", t_with("result.rate", lang, &[&(object.rate() * 100.0)]))?;
+                    console.print_result(&format!("{} {:>20}\n", t("label.final_tax", lang), format_rupiah(tax)))?;
+                },
+                
+                "12" => {
+                    writeln!(console, "\n{}", t("section.bonus", lang))?;
+                    writeln!(console, "{}", t("note.bonus", lang))?;
+                    let monthly_gross = console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?;
+                    
+                    // Get marital status
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    
+                    // Get number of dependents
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let bonus = console.prompt_f64(&format!("\n{}", t("prompt.bonus", lang)))?;
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(monthly_gross)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()?;
+                    let (regular_tax, _, _, _, _) = calculate_pph21_progressive(&params, config);
+                    let bonus_tax = calculate_pph21_bonus(monthly_gross, bonus, &params, config);
+                    
+                    writeln!(console, "\n{}", t("result.bonus", lang))?;
+                    writeln!(console, "{:<27}: {:>20}", t("label.bonus", lang), format_rupiah(bonus))?;
+                    writeln!(console, "{:<27}: {:>20}", t("label.annual_tax_without_bonus", lang), format_rupiah(regular_tax))?;
+                    writeln!(console, "{:<27}: {:>20}", t("label.annual_tax_with_bonus", lang), format_rupiah(regular_tax + bonus_tax))?;
+                    console.print_result(&format!("{:<27}: {:>20}\n", t("label.bonus_tax", lang), format_rupiah(bonus_tax)))?;
+                    writeln!(console, "{:<27}: {:>20}", t("label.net_bonus", lang), format_rupiah(bonus - bonus_tax))?;
+                },
+                
+                "13" => {
+                    writeln!(console, "\n{}", t("section.ppnbm", lang))?;
+                    let base = console.prompt_f64(t("prompt.selling_price", lang))?;
+                    let vat_rate = console.prompt(
+                        &t_with("prompt.vat_rate", lang, &[&default_vat_rate]),
+                        |input| parse_vat_rate(input, default_vat_rate),
+                    )?;
+                    let ppnbm_rate = console.prompt(t("prompt.ppnbm_rate", lang), parse_ppnbm_rate)?;
+                    
+                    let vat = calculate_vat(base, vat_rate);
+                    let ppnbm = calculate_ppnbm(base, ppnbm_rate);
+                    writeln!(console, "\n{}", t("result.ppnbm", lang))?;
+                    writeln!(console, "{}: {:>20}", t("label.price_before_tax", lang), format_rupiah(base))?;
+                    writeln!(console, "PPN ({}%): {:>20}", vat_rate, format_rupiah(vat))?;
+                    writeln!(console, "PPnBM ({}%): {:>20}", ppnbm_rate, format_rupiah(ppnbm))?;
+                    writeln!(console, "{}: {:>20}", t("label.total_due", lang), format_rupiah(base + vat + ppnbm))?;
+                },
+                
+                "14" => {
+                    writeln!(console, "\n{}", t("section.invoice", lang))?;
+                    let mut lines = Vec::new();
+                    loop {
+                        let description = console.prompt_text(&format!("\n{}", t_with("prompt.invoice_item", lang, &[&(lines.len() + 1)])))?;
+                        if description.is_empty() {
+                            break;
                         }
-                        println!("PTKP (Status {})    : Rp{:>15} per tahun", ptkp_key, ptkp.separate_with_commas());
-                        
-                        // PKP Calculation
-                        println!("\n[PENGHASILAN KENA PAJAK (PKP)]");
-                        println!("Gaji Setahun - PTKP: Rp{:>15} - Rp{:>15} = Rp{:>15}", 
-                            (gross_salary * 12.0).separate_with_commas(),
-                            ptkp.separate_with_commas(),
-                            pkp.separate_with_commas());
-                        
-                        // PPh 21 Calculation
-                        println!("\n[PERHITUNGAN PPh 21]");
-                        println!("DPP (Dasar Pengenaan Pajak): Rp{:>15}", dpp.separate_with_commas());
-                        println!("Tarif                     : {:>15}%", pph_21_percent);
-                        println!("PPh 21                    : Rp{:>15}", pph_21_monthly.separate_with_commas());
-                        println!("\nRincian Perhitungan:");
-                        println!("0.75% x Rp{:>15} = Rp{:>15}", 
-                            dpp.separate_with_commas(),
-                            pph_21_monthly.separate_with_commas());
-                        
-                        // Annual Summary
-                        println!("\n[RINGKASAN TAHUNAN]");
-                        println!("Gaji Kotor Setahun  : Rp{:>15}", (gross_salary * 12.0).separate_with_commas());
-                        println!("PTKP                : Rp{:>15} (-)", ptkp.separate_with_commas());
-                        println!("PKP                 : Rp{:>15}", pkp.separate_with_commas());
-                        println!("PPh 21 Setahun      : Rp{:>15}", annual_tax.separate_with_commas());
-                        println!("Gaji Bersih Setahun : Rp{:>15}", (net_salary * 12.0).separate_with_commas());
-                        
-                        println!("\n[Keterangan]:");
-                        println!("* Perusahaan menanggung beban pajak karyawan");
-                        println!("* Karyawan menerima gaji bersih sesuai yang dijanjikan");
-                    },
-                    _ => println!("Masukan tidak valid. Harap masukkan angka positif."),
-                }
-            },
-            "3" => {
-                println!("\n=== Perhitungan Pajak Penghasilan Umum ===");
-                println!("Masukkan penghasilan kena pajak (dalam Rupiah):");
-                let mut income = String::new();
-                io::stdin().read_line(&mut income).expect("Gagal membaca input");
+                        let quantity = console.prompt(t("prompt.quantity", lang), |input| parse_in_range(input, 1, u32::MAX))?;
+                        let unit_price = console.prompt_f64(t("prompt.unit_price", lang))?;
+                        lines.push(InvoiceLine { description, quantity, unit_price });
+                    }
+                    
+                    if lines.is_empty() {
+                        writeln!(console, "{}", t("result.empty_invoice", lang))?;
+                    } else {
+                        let vat_rate = console.prompt(
+                            &format!("\n{}", t_with("prompt.vat_rate", lang, &[&default_vat_rate])),
+                            |input| parse_vat_rate(input, default_vat_rate),
+                        )?;
+                        let summary = calculate_invoice(&lines, vat_rate);
+                        writeln!(console, "\n{}", t("result.invoice", lang))?;
+                        console.print_result(&render_invoice(&lines, &summary, lang))?;
+                    }
+                },
                 
-                match income.trim().parse::<f64>() {
-                    Ok(amount) if amount >= 0.0 => {
-                        let tax = calculate_income_tax(amount, &tax_brackets);
-                        println!("\nHasil Perhitungan Pajak Penghasilan:");
-                        println!("Penghasilan Kena Pajak: Rp{:>15}", amount.separate_with_commas());
-                        println!("Pajak yang harus dibayar: Rp{:>15}", tax.separate_with_commas());
-                        println!("Penghasilan Bersih: Rp{:>15}", (amount - tax).separate_with_commas());
-                    },
-                    _ => println!("Masukan tidak valid. Harap masukkan angka positif."),
-                }
-            },
-            "4" => {
-                println!("\n=== Perhitungan PPN (Pajak Pertambahan Nilai) ===");
-                println!("Masukkan jumlah harga (dalam Rupiah):");
-                let mut amount = String::new();
-                io::stdin().read_line(&mut amount).expect("Gagal membaca input");
+                "15" => {
+                    writeln!(console, "\n{}", t("section.payroll", lang))?;
+                    let amount = console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?;
+                    
+                    // Get marital status
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    
+                    // Get number of dependents
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(amount)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()?;
+                    
+                    writeln!(console)?;
+                    console.print_result(&render_payroll(&full_payroll(&params, config), lang))?;
+                },
                 
-                println!("Masukkan persentase PPN (default {}%):", default_vat_rate);
-                let mut vat_rate_input = String::new();
-                io::stdin().read_line(&mut vat_rate_input).expect("Gagal membaca input");
+                "16" => {
+                    writeln!(console, "\n{}", t("section.schemes", lang))?;
+                    let salary = console.prompt_f64(&format!("\n{}", t("prompt.agreed_salary", lang)))?;
+                    
+                    // Get marital status
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    
+                    // Get number of dependents
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(salary)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()?;
+                    
+                    writeln!(console, "\n{}", t("note.schemes", lang))?;
+                    console.print_result(&render_scheme_comparison(&compare_schemes(salary, &params, config)?, lang))?;
+                },
                 
-                let vat_rate = vat_rate_input.trim().parse::<f64>().unwrap_or(default_vat_rate);
+                "17" => {
+                    writeln!(console, "\n{}", t("section.income_tax_from_net", lang))?;
+                    let net = console.prompt_f64(t("prompt.net_after_tax", lang))?;
+                    
+                    let pkp = income_tax_from_net(net, tax_brackets, config.rounding);
+                    writeln!(console, "\n{}", t("result.pkp", lang))?;
+                    writeln!(console, "{}: {:>20}", t("label.net_income", lang), format_rupiah(net))?;
+                    writeln!(console, "{}: {:>20}", t("label.taxable_income", lang), format_rupiah(pkp))?;
+                    writeln!(console, "{}: {:>20}", t("label.income_tax", lang), format_rupiah(calculate_income_tax(pkp, tax_brackets)))?;
+                },
                 
-                match amount.trim().parse::<f64>() {
-                    Ok(amount) if amount >= 0.0 => {
-                        let vat = calculate_vat(amount, vat_rate);
-                        println!("\nHasil Perhitungan PPN ({}%):", vat_rate);
-                        println!("Harga sebelum PPN: Rp{:>15}", amount.separate_with_commas());
-                        println!("PPN: Rp{:>15}", vat.separate_with_commas());
-                        println!("Total yang harus dibayar: Rp{:>15}", (amount + vat).separate_with_commas());
-                    },
-                    _ => println!("Masukan tidak valid. Harap masukkan angka positif."),
-                }
-            },
+                "18" => {
+                    writeln!(console, "\n{}", t("section.daily", lang))?;
+                    let daily_wage = console.prompt_f64(t("prompt.daily_wage", lang))?;
+                    let cumulative_month = console.prompt_f64(t("prompt.cumulative_wage", lang))?;
+                    
+                    let tax = calculate_pph21_daily(daily_wage, cumulative_month, config);
+                    writeln!(console, "\n{}", t("result.daily", lang))?;
+                    writeln!(console, "{}: {:>20}", t("label.daily_wage", lang), format_rupiah(daily_wage))?;
+                    if cumulative_month > MONTHLY_WAGE_THRESHOLD {
+                        writeln!(console, "{}", t_with("note.daily_over_threshold", lang, &[&format_rupiah(MONTHLY_WAGE_THRESHOLD), &format_rupiah(DAILY_PTKP)]))?;
+                    } else {
+                        writeln!(console, "{}", t_with("note.daily_under_threshold", lang, &[&format_rupiah(DAILY_WAGE_THRESHOLD)]))?;
+                    }
+                    console.print_result(&format!("{}: {:>20}\n", t("label.pph21_withheld", lang), format_rupiah(tax)))?;
+                    writeln!(console, "{}: {:>20}", t("label.wage_received", lang), format_rupiah(daily_wage - tax))?;
+                },
+                
+                "19" => {
+                    writeln!(console, "\n{}", t("section.pesangon", lang))?;
+                    let amount = console.prompt_f64(t("prompt.severance", lang))?;
+                    
+                    let tax = calculate_pesangon_tax(amount);
+                    writeln!(console, "\n{}", t("result.pesangon", lang))?;
+                    writeln!(console, "{}: {:>20}", t("label.gross_severance", lang), format_rupiah(amount))?;
+                    for bracket in get_pesangon_brackets().iter().filter(|bracket| bracket.applies_to(amount)) {
+                        writeln!(console, "{}: {:>20}", t_with("label.bracket_rate", lang, &[&(bracket.rate * 100.0)]), format_rupiah(bracket.tax_in_bracket(amount)))?;
+                    }
+                    console.print_result(&format!("{} {:>20}\n", t("label.final_tax", lang), format_rupiah(tax)))?;
+                    writeln!(console, "{}: {:>20}", t("label.severance_received", lang), format_rupiah(amount - tax))?;
+                },
+                
+                "20" => {
+                    months_per_year = if months_per_year == 12 { 13 } else { 12 };
+                    if months_per_year == 13 {
+                        writeln!(console, "\n{}", t("note.thirteenth_on", lang))?;
+                    } else {
+                        writeln!(console, "\n{}", t("note.thirteenth_off", lang))?;
+                    }
+                },
+                
+                "21" => {
+                    writeln!(console, "\n{}", t("section.pph26", lang))?;
+                    let amount = console.prompt_f64(t("prompt.foreign_gross", lang))?;
+                    let rate = console.prompt(&t_with("prompt.pph26_rate", lang, &[&PPH26_RATE]), parse_pph26_rate)?;
+                    
+                    let tax = calculate_pph26(amount, rate, config);
+                    writeln!(console, "\n{}", t("result.pph26", lang))?;
+                    writeln!(console, "{}: {:>20}", t("label.gross_amount", lang), format_rupiah(amount))?;
+                    if rate < PPH26_RATE {
+                        writeln!(console, "{}", t_with("result.rate_treaty", lang, &[&rate]))?;
+                    } else {
+                        writeln!(console, "{}", t_with("result.rate", lang, &[&rate]))?;
+                    }
+                    console.print_result(&format!("{}: {:>20}\n", t("label.pph26_withheld", lang), format_rupiah(tax)))?;
+                    writeln!(console, "{}: {:>20}", t("label.amount_received", lang), format_rupiah(amount - tax))?;
+                },
+                
+                "22" => {
+                    writeln!(console, "\n{}", t("section.annual_summary", lang))?;
+                    let same_every_month = console.prompt_bool(&format!("\n{}", t("prompt.same_every_month", lang)), true)?;
+                    let grosses = if same_every_month {
+                        vec![console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?; 12]
+                    } else {
+                        let mut grosses = Vec::with_capacity(12);
+                        for month in 1..=12 {
+                            grosses.push(console.prompt_gross(&t_with("prompt.gross_for_month", lang, &[&month]))?);
+                        }
+                        grosses
+                    };
+                    
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    let zakat = console.prompt_optional_f64(&format!("\n{}", t("prompt.zakat", lang)))?;
+                    let method = console.prompt(
+                        &format!("\n{}", t_with("prompt.method", lang, &[&config.flat_rate])),
+                        |input| match input.trim() {
+                            "" | "1" => Ok(Pph21Method::Progressive),
+                            "2" => Ok(Pph21Method::Flat),
+                            other => Err(CalcError::Parse(Message::new("error.invalid_method", &[&other]))),
+                        },
+                    )?;
+                    
+                    let months = grosses
+                        .into_iter()
+                        .map(|gross| {
+                            PPh21Params::builder()
+                                .gross_income(gross)
+                                .married(is_married)
+                                .dependents(num_dependents)
+                                .spouse_income_combined(spouse_income_combined)
+                                .has_npwp(has_npwp)
+                                .zakat(zakat)
+                                .build()
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let summary = annual_summary(&months, method, config)?;
+                    writeln!(console)?;
+                    console.print_result(&render_annual_summary(&summary, lang))?;
+                },
+                
+                "23" => {
+                    writeln!(console, "\n{}", t("section.compare_years", lang))?;
+                    let amount = console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?;
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(amount)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .build()?;
+                    writeln!(console, "\n{}", t("result.year_comparison", lang))?;
+                    write!(console, "{}", render_year_comparison(&compare_years(&params, &SUPPORTED_TAX_YEARS), lang))?;
+                },
+                
+                "24" => {
+                    writeln!(console, "\n{}", t("section.nett", lang))?;
+                    writeln!(console, "\n{}", t("note.nett", lang))?;
+                    let amount = console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?;
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(amount)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()?;
+                    let nett = calculate_pph21_nett(amount, &params, config);
+                    writeln!(console, "\n{}", t("result.nett", lang))?;
+                    writeln!(console, "{:<20}: {:>20}", t("label.monthly_gross_pay", lang), format_rupiah(amount))?;
+                    console.print_result(&format!(
+                        "{:<20}: {:>20} {}\n{:<20}: {:>20}\n",
+                        t("label.monthly_tax", lang),
+                        format_rupiah(nett.monthly_tax),
+                        t("result.paid_by_company", lang),
+                        t("label.take_home", lang),
+                        format_rupiah(nett.take_home)
+                    ))?;
+                    writeln!(console, "{:<20}: {:>20}", t("label.company_cost", lang), format_rupiah(nett.company_cost))?;
+                },
+                "25" => {
+                    writeln!(console, "\n{}", t("section.december", lang))?;
+                    writeln!(console, "\n{}", t("note.december", lang))?;
+                    let amount = console.prompt_gross(&format!("\n{}", t("prompt.gross_monthly", lang)))?;
+                    let (is_married, spouse_income_combined) = console.read_marital_status(lang)?;
+                    let has_npwp = console.read_npwp(lang)?;
+                    let num_dependents = if is_married { console.read_dependents(lang)? } else { 0 };
+                    let ytd_withheld = console.prompt_f64(&format!("\n{}", t("prompt.ytd_withheld", lang)))?;
+                    
+                    let params = PPh21Params::builder()
+                        .gross_income(amount)
+                        .married(is_married)
+                        .dependents(num_dependents)
+                        .spouse_income_combined(spouse_income_combined)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()?;
+                    let (annual_tax, _, _, _, _) = calculate_pph21_progressive(&params, config);
+                    let december = calculate_pph21_december(ytd_withheld, &params, config);
+                    writeln!(console, "\n{}", t("result.december", lang))?;
+                    writeln!(console, "{:<20}: {:>20}", t("label.annual_tax", lang), format_rupiah(annual_tax))?;
+                    writeln!(console, "{:<20}: {:>20} (-)", t("label.withheld_jan_nov", lang), format_rupiah(ytd_withheld))?;
+                    if december < 0.0 {
+                        let (label, refund) = settlement_row(december, lang);
+                        console.print_result(&format!("{:<20}: {:>20}\n", label, format_rupiah(refund)))?;
+                    } else {
+                        console.print_result(&format!("{:<20}: {:>20}\n", t("label.december_tax", lang), format_rupiah(december)))?;
+                    }
+                },
+                "26" => {
+                    writeln!(console, "\n{}", t("section.non_employee", lang))?;
+                    writeln!(console, "\n{}", t("note.non_employee", lang))?;
+                    let prior = console.prompt_optional_f64(&format!("\n{}", t("prompt.prior_honoraria", lang)))?;
+                    let payment = console.prompt_f64(t("prompt.current_honorarium", lang))?;
+                    
+                    let cumulative = prior + payment;
+                    let tax = calculate_pph21_non_employee(payment, cumulative, config);
+                    writeln!(console, "\n{}", t("result.non_employee", lang))?;
+                    writeln!(console, "{:<20}: {:>20}", t("label.prior_honoraria", lang), format_rupiah(prior))?;
+                    writeln!(console, "{:<20}: {:>20}", t("label.current_honorarium", lang), format_rupiah(payment))?;
+                    writeln!(console, "{:<20}: {:>20}", t("label.cumulative_honoraria", lang), format_rupiah(cumulative))?;
+                    console.print_result(&format!("{}: {:>20}\n", t("label.pph21_withheld", lang), format_rupiah(tax)))?;
+                },
+                
+                _ => console.print_error(t("menu.invalid", lang))?,
+            }
             
-            "5" => {
-                println!("\nTerima kasih telah menggunakan kalkulator pajak!");
-                break;
-            },
-            _ => println!("Pilihan tidak valid. Silakan pilih 1, 2, 3, 4, atau 5."),
+            Ok(())
+        };
+        match run_choice() {
+            Ok(()) => {},
+            Err(CalcError::Cancelled) => writeln!(console, "\n{}", t("menu.cancelled", lang))?,
+            // A read failure ends the session
+            Err(err @ CalcError::Io(_)) => return Err(err),
+            // Anything else, such as a rejected combination of answers or the
+            // solver giving up, ends this calculation and returns to the menu
+            Err(err) => console.print_error(err.message().text(lang))?,
         }
     }
 }
@@ -0,0 +1,75 @@
+// Named PPh 21 inputs for repeat employees, kept in one JSON file mapping
+// each profile name to its saved PPh21Params
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::i18n::Message;
+use crate::tax::PPh21Params;
+
+// Environment variable naming the profiles file when --profiles is not given
+pub const PROFILES_ENV_VAR: &str = "TAX_CALCULATOR_PROFILES";
+
+// Profiles file used when neither --profiles nor the environment variable is set
+pub const DEFAULT_PROFILES_FILE: &str = "tax_calculator_profiles.json";
+
+pub type ProfileStore = BTreeMap<String, PPh21Params>;
+
+// All saved profiles; a missing file is an empty store
+pub fn load_profiles(path: &Path) -> Result<ProfileStore, Message> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(ProfileStore::new()),
+        Err(err) => return Err(Message::new("error.profile_read", &[&path.display(), &err])),
+    };
+    
+    serde_json::from_str(&contents).map_err(|err| Message::new("error.profile_invalid", &[&path.display(), &err]))
+}
+
+// The params saved under `name`
+pub fn load_profile(path: &Path, name: &str) -> Result<PPh21Params, Message> {
+    load_profiles(path)?
+        .remove(name)
+        .ok_or_else(|| Message::new("error.profile_not_found", &[&name, &path.display()]))
+}
+
+// Save `params` under `name`, replacing any profile already saved with that name
+pub fn save_profile(path: &Path, name: &str, params: &PPh21Params) -> Result<(), Message> {
+    let mut profiles = load_profiles(path)?;
+    profiles.insert(name.to_string(), params.clone());
+    let contents = serde_json::to_string_pretty(&profiles).expect("profil selalu dapat diserialisasi");
+    
+    std::fs::write(path, contents + "\n").map_err(|err| Message::new("error.profile_write", &[&path.display(), &err]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_profile_loads_back() {
+        let path = std::env::temp_dir().join(format!("tax_calculator_profiles_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let budi = PPh21Params::builder()
+            .gross_income(15_000_000.0)
+            .married(true)
+            .dependents(2)
+            .has_npwp(false)
+            .zakat(1_200_000.0)
+            .build()
+            .unwrap();
+        let sari = PPh21Params::builder().gross_income(8_000_000.0).build().unwrap();
+        
+        assert!(load_profiles(&path).unwrap().is_empty());
+        save_profile(&path, "budi", &budi).unwrap();
+        save_profile(&path, "sari", &sari).unwrap();
+        let loaded = load_profile(&path, "budi");
+        let missing = load_profile(&path, "andi");
+        let names: Vec<String> = load_profiles(&path).unwrap().into_keys().collect();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert_eq!(loaded.unwrap(), budi);
+        assert!(missing.unwrap_err().to_string().contains("andi"));
+        assert_eq!(names, ["budi", "sari"]);
+    }
+}
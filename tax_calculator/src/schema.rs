@@ -0,0 +1,38 @@
+// JSON Schema of the types the CLI, the HTTP API and the WASM exports take and
+// return, for clients that validate or generate code from them
+
+use schemars::schema_for;
+use serde_json::{Map, Value};
+
+use crate::tax::{PPh21Params, PayrollReport, Pph21Result, VatResult};
+
+// One schema per type, keyed by the type name
+pub fn schemas() -> Value {
+    let mut schemas = Map::new();
+    schemas.insert("PPh21Params".to_string(), serde_json::to_value(schema_for!(PPh21Params)).unwrap());
+    schemas.insert("Pph21Result".to_string(), serde_json::to_value(schema_for!(Pph21Result)).unwrap());
+    schemas.insert("PayrollReport".to_string(), serde_json::to_value(schema_for!(PayrollReport)).unwrap());
+    schemas.insert("VatResult".to_string(), serde_json::to_value(schema_for!(VatResult)).unwrap());
+    Value::Object(schemas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pph21_request_schema_lists_the_params_fields() {
+        let schemas = schemas();
+        let request = &schemas["PPh21Params"];
+        
+        assert_eq!(request["type"], "object");
+        for field in ["gross_income", "is_married", "num_dependents", "has_npwp", "zakat", "months_worked"] {
+            assert!(request["properties"].get(field).is_some(), "{} missing from {}", field, request);
+        }
+        assert_eq!(request["properties"]["num_dependents"]["type"], "integer");
+        assert!(request["required"].as_array().unwrap().contains(&Value::from("gross_income")));
+        
+        let result = &schemas["Pph21Result"];
+        assert!(result["properties"].get("monthly_tax").is_some());
+    }
+}
@@ -0,0 +1,139 @@
+// HTTP API over the tax functions: POST /pph21 and POST /ppn take a JSON body
+// and answer with the same result JSON as `--format json`; invalid input gets
+// 400 with `{"error": "..."}`
+
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::Deserialize;
+use tokio::net::TcpListener;
+
+use crate::settings::{env_vat_rate, resolve_vat_rate};
+use crate::tax::{
+    calculate_pph21_result, calculate_vat_with_mode, PPh21Params, Pph21Method, Pph21Result, TaxConfig, VatMode, VatResult,
+    VatStatus,
+};
+
+// PPh21Params fields; everything but gross_income defaults as in PPh21Params::builder
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pph21Request {
+    pub gross_income: f64,
+    #[serde(default)]
+    pub is_married: bool,
+    #[serde(default)]
+    pub num_dependents: u32,
+    #[serde(default)]
+    pub spouse_income_combined: bool,
+    #[serde(default)]
+    pub disability_adjustment: bool,
+    #[serde(default = "default_has_npwp")]
+    pub has_npwp: bool,
+    #[serde(default)]
+    pub zakat: f64,
+    #[serde(default)]
+    pub overtime: f64,
+    #[serde(default)]
+    pub pension_contribution: f64,
+    #[serde(default = "default_months")]
+    pub months_per_year: u8,
+    #[serde(default = "default_months")]
+    pub months_worked: u8,
+    // Use the legacy flat 0.75% rate instead of the progressive brackets
+    #[serde(default)]
+    pub flat: bool,
+}
+
+fn default_has_npwp() -> bool {
+    true
+}
+
+fn default_months() -> u8 {
+    12
+}
+
+// PPN on `amount` at `rate` percent (default as resolved by
+// settings::resolve_vat_rate); `inclusive` when the amount already includes PPN
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PpnRequest {
+    pub amount: f64,
+    #[serde(default)]
+    pub rate: Option<f64>,
+    #[serde(default)]
+    pub inclusive: bool,
+}
+
+// A rejected request, answered with 400 Bad Request
+#[derive(Debug)]
+pub struct ApiError(String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": self.0 }))).into_response()
+    }
+}
+
+async fn pph21(State(config): State<Arc<TaxConfig>>, Json(request): Json<Pph21Request>) -> Result<Json<Pph21Result>, ApiError> {
+    let params = PPh21Params::builder()
+        .gross_income(request.gross_income)
+        .married(request.is_married)
+        .dependents(request.num_dependents)
+        .spouse_income_combined(request.spouse_income_combined)
+        .has_npwp(request.has_npwp)
+        .disability_adjustment(request.disability_adjustment)
+        .zakat(request.zakat)
+        .overtime(request.overtime)
+        .pension_contribution(request.pension_contribution)
+        .months_per_year(request.months_per_year)
+        .months_worked(request.months_worked)
+        .build()
+        .map_err(|err| ApiError(err.to_string()))?;
+    let method = if request.flat { Pph21Method::Flat } else { Pph21Method::Progressive };
+    
+    Ok(Json(calculate_pph21_result(&params, method, &config)))
+}
+
+async fn ppn(State(config): State<Arc<TaxConfig>>, Json(request): Json<PpnRequest>) -> Result<Json<VatResult>, ApiError> {
+    if !request.amount.is_finite() || request.amount < 0.0 {
+        return Err(ApiError("Jumlah harga harus Rp 0 atau lebih.".to_string()));
+    }
+    let rate = match request.rate {
+        Some(rate) => rate,
+        None => resolve_vat_rate(None, env_vat_rate().as_deref(), config.vat_rate).map_err(|err| ApiError(err.to_string()))?,
+    };
+    if !rate.is_finite() || !(0.0..=100.0).contains(&rate) {
+        return Err(ApiError("Persentase PPN harus antara 0 dan 100.".to_string()));
+    }
+    let mode = if request.inclusive { VatMode::Inclusive } else { VatMode::Exclusive };
+    
+    Ok(Json(calculate_vat_with_mode(request.amount, VatStatus::Standard(rate), mode)))
+}
+
+// Routes computing with `config`
+pub fn router(config: TaxConfig) -> Router {
+    Router::new()
+        .route("/pph21", post(pph21))
+        .route("/ppn", post(ppn))
+        .with_state(Arc::new(config))
+}
+
+// Serve the API on an already bound listener until the process ends
+pub async fn serve(listener: TcpListener, config: TaxConfig) -> io::Result<()> {
+    axum::serve(listener, router(config)).await
+}
+
+// Bind `addr` and serve the API on a new Tokio runtime, for the sync CLI
+pub fn run(addr: SocketAddr, config: TaxConfig) -> io::Result<()> {
+    tokio::runtime::Runtime::new()?.block_on(async {
+        let listener = TcpListener::bind(addr).await?;
+        println!("API pajak berjalan di http://{} (POST /pph21, POST /ppn)", listener.local_addr()?);
+        serve(listener, config).await
+    })
+}
@@ -0,0 +1,105 @@
+// Settings that can come from several places, resolved in one order: a CLI
+// flag, then the environment, then the config file, then the built-in value
+
+use std::path::{Path, PathBuf};
+
+use crate::error::CalcError;
+use crate::i18n::{Message, MessageArg};
+use crate::tax::{parse_vat_rate, MAX_VAT_RATE};
+
+// Environment variable overriding the default PPN percentage
+pub const VAT_RATE_ENV_VAR: &str = "RUSTACEAN_VAT_RATE";
+
+// PPN percentage when no flag, environment variable or config sets one
+pub const BUILTIN_VAT_RATE: f64 = 11.0;
+
+// Config file picked up from the working directory when --config is not given
+pub const DEFAULT_CONFIG_FILE: &str = "tax_calculator.toml";
+
+// The config file to load: the explicit --config path, otherwise
+// DEFAULT_CONFIG_FILE in `dir` if it exists
+pub fn discover_config(explicit: Option<&Path>, dir: &Path) -> Option<PathBuf> {
+    if let Some(path) = explicit {
+        return Some(path.to_path_buf());
+    }
+    
+    let discovered = dir.join(DEFAULT_CONFIG_FILE);
+    discovered.is_file().then_some(discovered)
+}
+
+// The value of VAT_RATE_ENV_VAR, if set
+pub fn env_vat_rate() -> Option<String> {
+    std::env::var(VAT_RATE_ENV_VAR).ok()
+}
+
+// PPN percentage by precedence: `flag` (a typed percentage such as "11,5%"),
+// then `env` (the VAT_RATE_ENV_VAR value), then `config` (`vat_rate` in the
+// config file), then BUILTIN_VAT_RATE. An empty flag or environment value
+// counts as unset; an invalid one is an error rather than skipped
+pub fn resolve_vat_rate(flag: Option<&str>, env: Option<&str>, config: Option<f64>) -> Result<f64, CalcError> {
+    let is_set = |value: &&str| !value.trim().is_empty();
+    
+    if let Some(flag) = flag.filter(is_set) {
+        return parse_vat_rate(flag, BUILTIN_VAT_RATE);
+    }
+    if let Some(env) = env.filter(is_set) {
+        return parse_vat_rate(env, BUILTIN_VAT_RATE)
+            .map_err(|err| CalcError::Parse(Message::with_args("error.env_invalid", vec![MessageArg::Text(VAT_RATE_ENV_VAR.to_string()), MessageArg::Message(err.message())])));
+    }
+    match config {
+        Some(rate) if !(0.0..=MAX_VAT_RATE).contains(&rate) => Err(CalcError::OutOfRange(Message::new("error.config_vat_rate", &[&rate, &MAX_VAT_RATE]))),
+        Some(rate) => Ok(rate),
+        None => Ok(BUILTIN_VAT_RATE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vat_rate_flag_beats_env_and_config() {
+        assert_eq!(resolve_vat_rate(Some("12"), Some("10"), Some(9.0)).unwrap(), 12.0);
+        assert_eq!(resolve_vat_rate(Some("11,5%"), None, None).unwrap(), 11.5);
+        // An invalid flag is reported even when lower levels are valid
+        assert!(resolve_vat_rate(Some("150"), Some("10"), None).is_err());
+    }
+
+    #[test]
+    fn test_vat_rate_env_beats_config() {
+        assert_eq!(resolve_vat_rate(None, Some("10"), Some(9.0)).unwrap(), 10.0);
+        assert_eq!(resolve_vat_rate(Some(""), Some("12%"), None).unwrap(), 12.0);
+        
+        let err = resolve_vat_rate(None, Some("sebelas"), Some(9.0)).unwrap_err();
+        assert!(err.to_string().contains(VAT_RATE_ENV_VAR));
+    }
+
+    #[test]
+    fn test_vat_rate_config_beats_built_in() {
+        assert_eq!(resolve_vat_rate(None, None, Some(9.0)).unwrap(), 9.0);
+        assert_eq!(resolve_vat_rate(None, Some(" "), Some(9.0)).unwrap(), 9.0);
+        assert!(resolve_vat_rate(None, None, Some(-1.0)).is_err());
+    }
+
+    #[test]
+    fn test_vat_rate_falls_back_to_built_in() {
+        assert_eq!(resolve_vat_rate(None, None, None).unwrap(), BUILTIN_VAT_RATE);
+    }
+
+    #[test]
+    fn test_discover_config_prefers_explicit_path() {
+        let dir = std::env::temp_dir().join(format!("tax_calculator_settings_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let explicit = Path::new("lain.toml");
+        
+        let missing = discover_config(None, &dir);
+        std::fs::write(dir.join(DEFAULT_CONFIG_FILE), "").unwrap();
+        let discovered = discover_config(None, &dir);
+        let chosen = discover_config(Some(explicit), &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        
+        assert_eq!(missing, None);
+        assert_eq!(discovered, Some(dir.join(DEFAULT_CONFIG_FILE)));
+        assert_eq!(chosen, Some(explicit.to_path_buf()));
+    }
+}
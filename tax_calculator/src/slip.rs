@@ -0,0 +1,66 @@
+// One-page PDF salary slips built from a payroll report
+
+use std::path::Path;
+
+use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+use crate::i18n::{Lang, Message};
+use crate::tax::{format_rupiah, payroll_rows, PayrollReport};
+
+// A4 portrait, in millimetres
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const LINE_HEIGHT: f32 = 7.0;
+const FONT_SIZE: f32 = 11.0;
+
+// Render the slip as PDF bytes; Courier keeps the padded amounts aligned
+pub fn render_slip_pdf(name: &str, period: &str, report: &PayrollReport) -> Result<Vec<u8>, Message> {
+    let (document, page, layer) = PdfDocument::new("Slip Gaji", Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Slip");
+    let font = document.add_builtin_font(BuiltinFont::Courier).map_err(|err| Message::new("error.pdf_render", &[&err]))?;
+    let bold = document.add_builtin_font(BuiltinFont::CourierBold).map_err(|err| Message::new("error.pdf_render", &[&err]))?;
+    let layer = document.get_page(page).get_layer(layer);
+    
+    let mut y = PAGE_HEIGHT - MARGIN;
+    layer.use_text("SLIP GAJI", FONT_SIZE + 3.0, Mm(MARGIN), Mm(y), &bold);
+    y -= LINE_HEIGHT * 1.5;
+    layer.use_text(format!("Nama    : {}", name), FONT_SIZE, Mm(MARGIN), Mm(y), &font);
+    y -= LINE_HEIGHT;
+    layer.use_text(format!("Periode : {}", period), FONT_SIZE, Mm(MARGIN), Mm(y), &font);
+    y -= LINE_HEIGHT * 1.5;
+    
+    for (label, amount) in payroll_rows(report, Lang::Id) {
+        let line = format!("{:<22}: {:>20}", label, format_rupiah(amount));
+        let line_font = if label == "Take Home Pay" { &bold } else { &font };
+        layer.use_text(line, FONT_SIZE, Mm(MARGIN), Mm(y), line_font);
+        y -= LINE_HEIGHT;
+    }
+    
+    document.save_to_bytes().map_err(|err| Message::new("error.pdf_render", &[&err]))
+}
+
+// Write the slip to `path`
+pub fn write_slip_pdf(path: &Path, name: &str, period: &str, report: &PayrollReport) -> Result<(), Message> {
+    let bytes = render_slip_pdf(name, period, report)?;
+    std::fs::write(path, bytes).map_err(|err| Message::new("error.file_write", &[&path.display(), &err]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tax::{full_payroll, PPh21Params, TaxConfig};
+
+    #[test]
+    fn test_write_slip_pdf() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        let report = full_payroll(&params, &TaxConfig::default());
+        let path = std::env::temp_dir().join(format!("tax_calculator_slip_{}.pdf", std::process::id()));
+        
+        write_slip_pdf(&path, "Budi", "2024-10", &report).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        
+        assert!(!bytes.is_empty());
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+}
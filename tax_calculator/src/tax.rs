@@ -0,0 +1,6327 @@
+// PPh 21, income tax and VAT calculations shared by the CLI and library users
+
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use thousands::Separable;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+use chrono::{Datelike, Local, NaiveDate};
+use sha2::{Digest, Sha256};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::error::CalcError;
+use crate::i18n::{t, t_with, Lang, Message, MessageArg};
+
+// PPh 21 Calculation Parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PPh21Params {
+    pub gross_income: f64,
+    pub is_married: bool,
+    pub num_dependents: u32,
+    // K/I: the wife's income is combined with the husband's
+    pub spouse_income_combined: bool,
+    // Without an NPWP the PPh 21 is 20% higher
+    pub has_npwp: bool,
+    // Zakat penghasilan paid in the year through an approved institution,
+    // deducted from annual gross before PTKP
+    pub zakat: f64,
+    // Monthly overtime pay (uang lembur), taxed together with the salary
+    pub overtime: f64,
+    // Monthly employee contribution to an approved pension fund (iuran dana
+    // pensiun), deducted after biaya jabatan
+    pub pension_contribution: f64,
+    // Qualifying disability case (penyandang disabilitas): dependents beyond
+    // the PTKP maximum of 3 still add to PTKP
+    pub disability_adjustment: bool,
+    // Salary payments per year: 12, or 13 with a 13th-month salary (gaji ke-13)
+    pub months_per_year: u8,
+    // Months employed in the tax year, fewer than 12 for a mid-year start
+    pub months_worked: u8,
+}
+
+impl PPh21Params {
+    // Validating builder; defaults to TK/0 with an NPWP, no zakat, overtime or
+    // pension contribution and 12 salary payments over a full year of work
+    pub fn builder() -> PPh21ParamsBuilder {
+        PPh21ParamsBuilder {
+            gross_income: 0.0,
+            is_married: false,
+            num_dependents: 0,
+            spouse_income_combined: false,
+            has_npwp: true,
+            zakat: 0.0,
+            overtime: 0.0,
+            pension_contribution: 0.0,
+            disability_adjustment: false,
+            months_per_year: 12,
+            months_worked: 12,
+        }
+    }
+    
+    // Builder preset to these params, to change some and validate again
+    pub fn to_builder(&self) -> PPh21ParamsBuilder {
+        PPh21ParamsBuilder {
+            gross_income: self.gross_income,
+            is_married: self.is_married,
+            num_dependents: self.num_dependents,
+            spouse_income_combined: self.spouse_income_combined,
+            has_npwp: self.has_npwp,
+            zakat: self.zakat,
+            overtime: self.overtime,
+            pension_contribution: self.pension_contribution,
+            disability_adjustment: self.disability_adjustment,
+            months_per_year: self.months_per_year,
+            months_worked: self.months_worked,
+        }
+    }
+    
+    // Salary payments in the tax year: one per month worked, plus the 13th-month salary
+    pub fn salary_payments(&self) -> u8 {
+        self.months_worked + (self.months_per_year - 12)
+    }
+    
+    // Pension contributions for the year, paid in each month worked
+    pub fn annual_pension_contribution(&self) -> f64 {
+        self.pension_contribution * f64::from(self.months_worked)
+    }
+    
+    // Taxable monthly gross: the salary plus overtime
+    pub fn monthly_gross(&self) -> f64 {
+        self.gross_income + self.overtime
+    }
+    
+    // Annual gross: the monthly gross times the salary payments in the tax year
+    pub fn annual_gross(&self) -> f64 {
+        self.monthly_gross() * f64::from(self.salary_payments())
+    }
+    
+    // PTKP status such as TK/0, K/2 or K/I/2
+    pub fn ptkp_status(&self) -> PtkpStatus {
+        PtkpStatus::new(self.is_married, self.spouse_income_combined, self.num_dependents)
+    }
+    
+    // Dependents beyond the PTKP maximum, only kept under the disability adjustment
+    pub fn extra_dependents(&self) -> u32 {
+        self.num_dependents.saturating_sub(MAX_PTKP_DEPENDENTS)
+    }
+    
+    // Status label with any extra dependents, such as K/3+2
+    pub fn ptkp_label(&self) -> String {
+        match self.extra_dependents() {
+            0 => self.ptkp_status().to_string(),
+            extra => format!("{}+{}", self.ptkp_status(), extra),
+        }
+    }
+    
+    // Annual PTKP: the status amount plus one dependent step per extra dependent
+    pub fn annual_ptkp(&self, config: &TaxConfig) -> f64 {
+        config.ptkp(self.ptkp_status()) + f64::from(self.extra_dependents()) * config.dependent_ptkp()
+    }
+}
+
+// Builder for PPh21Params that rejects invalid input and caps dependents at
+// the PTKP maximum of 3
+#[derive(Debug, Clone)]
+pub struct PPh21ParamsBuilder {
+    gross_income: f64,
+    is_married: bool,
+    num_dependents: u32,
+    spouse_income_combined: bool,
+    has_npwp: bool,
+    zakat: f64,
+    overtime: f64,
+    pension_contribution: f64,
+    disability_adjustment: bool,
+    months_per_year: u8,
+    months_worked: u8,
+}
+
+impl PPh21ParamsBuilder {
+    pub fn gross_income(mut self, gross_income: f64) -> Self {
+        self.gross_income = gross_income;
+        self
+    }
+    
+    pub fn married(mut self, is_married: bool) -> Self {
+        self.is_married = is_married;
+        self
+    }
+    
+    pub fn dependents(mut self, num_dependents: u32) -> Self {
+        self.num_dependents = num_dependents;
+        self
+    }
+    
+    pub fn spouse_income_combined(mut self, spouse_income_combined: bool) -> Self {
+        self.spouse_income_combined = spouse_income_combined;
+        self
+    }
+    
+    pub fn has_npwp(mut self, has_npwp: bool) -> Self {
+        self.has_npwp = has_npwp;
+        self
+    }
+    
+    // Keep dependents beyond 3 for a qualifying disability case
+    pub fn disability_adjustment(mut self, disability_adjustment: bool) -> Self {
+        self.disability_adjustment = disability_adjustment;
+        self
+    }
+    
+    // Annual zakat penghasilan
+    pub fn zakat(mut self, zakat: f64) -> Self {
+        self.zakat = zakat;
+        self
+    }
+    
+    // Monthly overtime pay
+    pub fn overtime(mut self, overtime: f64) -> Self {
+        self.overtime = overtime;
+        self
+    }
+    
+    // Monthly pension fund contribution
+    pub fn pension_contribution(mut self, pension_contribution: f64) -> Self {
+        self.pension_contribution = pension_contribution;
+        self
+    }
+    
+    // 13 for a payroll that pays a 13th-month salary
+    pub fn months_per_year(mut self, months_per_year: u8) -> Self {
+        self.months_per_year = months_per_year;
+        self
+    }
+    
+    // Months employed in the tax year (1-12)
+    pub fn months_worked(mut self, months_worked: u8) -> Self {
+        self.months_worked = months_worked;
+        self
+    }
+    
+    pub fn build(self) -> Result<PPh21Params, CalcError> {
+        if !self.gross_income.is_finite() || self.gross_income < 0.0 {
+            return Err(CalcError::OutOfRange(Message::new("error.gross_negative", &[&self.gross_income])));
+        }
+        if !self.zakat.is_finite() || self.zakat < 0.0 {
+            return Err(CalcError::OutOfRange(Message::new("error.zakat_negative", &[&self.zakat])));
+        }
+        if !self.overtime.is_finite() || self.overtime < 0.0 {
+            return Err(CalcError::OutOfRange(Message::new("error.overtime_negative", &[&self.overtime])));
+        }
+        if !self.pension_contribution.is_finite() || self.pension_contribution < 0.0 {
+            return Err(CalcError::OutOfRange(Message::new("error.pension_negative", &[&self.pension_contribution])));
+        }
+        let max_income = max_monthly_income();
+        check_monthly_bound("error.label_gross", self.gross_income, max_income)?;
+        check_monthly_bound("error.label_overtime", self.overtime, max_income)?;
+        if !(12..=13).contains(&self.months_per_year) {
+            return Err(CalcError::OutOfRange(Message::new("error.months_per_year", &[&self.months_per_year])));
+        }
+        if !(1..=12).contains(&self.months_worked) {
+            return Err(CalcError::OutOfRange(Message::new("error.months_worked", &[&self.months_worked])));
+        }
+        if self.spouse_income_combined && !self.is_married {
+            return Err(CalcError::OutOfRange(Message::new("error.combined_income_unmarried", &[])));
+        }
+        let dependents = Dependents::Count(self.num_dependents).validate(max_dependents_input())?;
+        let num_dependents = if self.disability_adjustment { dependents.count() } else { dependents.ptkp_count() };
+        
+        Ok(PPh21Params {
+            gross_income: self.gross_income,
+            is_married: self.is_married,
+            num_dependents,
+            spouse_income_combined: self.spouse_income_combined,
+            has_npwp: self.has_npwp,
+            zakat: self.zakat,
+            overtime: self.overtime,
+            pension_contribution: self.pension_contribution,
+            disability_adjustment: self.disability_adjustment,
+            months_per_year: self.months_per_year,
+            months_worked: self.months_worked,
+        })
+    }
+}
+
+// PTKP status: single (TK), married (K) or married with the wife's income
+// combined (K/I), each with 0 to 3 dependents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum PtkpStatus {
+    Tk0,
+    Tk1,
+    Tk2,
+    Tk3,
+    K0,
+    K1,
+    K2,
+    K3,
+    Ki0,
+    Ki1,
+    Ki2,
+    Ki3,
+}
+
+impl PtkpStatus {
+    pub const ALL: [PtkpStatus; 12] = [
+        PtkpStatus::Tk0,
+        PtkpStatus::Tk1,
+        PtkpStatus::Tk2,
+        PtkpStatus::Tk3,
+        PtkpStatus::K0,
+        PtkpStatus::K1,
+        PtkpStatus::K2,
+        PtkpStatus::K3,
+        PtkpStatus::Ki0,
+        PtkpStatus::Ki1,
+        PtkpStatus::Ki2,
+        PtkpStatus::Ki3,
+    ];
+    
+    // Status for a marital spec; K/I needs a married taxpayer, and dependents
+    // beyond the PTKP maximum are ignored
+    pub fn new(married: bool, spouse_income_combined: bool, dependents: u32) -> PtkpStatus {
+        let prefix = match (married, spouse_income_combined) {
+            (true, true) => "K/I",
+            (true, false) => "K",
+            (false, _) => "TK",
+        };
+        let dependents = dependents.min(MAX_PTKP_DEPENDENTS);
+        
+        *PtkpStatus::ALL
+            .iter()
+            .find(|status| status.parts() == (prefix, dependents))
+            .expect("every prefix has 0 to MAX_PTKP_DEPENDENTS dependents")
+    }
+    
+    fn parts(&self) -> (&'static str, u32) {
+        match self {
+            PtkpStatus::Tk0 => ("TK", 0),
+            PtkpStatus::Tk1 => ("TK", 1),
+            PtkpStatus::Tk2 => ("TK", 2),
+            PtkpStatus::Tk3 => ("TK", 3),
+            PtkpStatus::K0 => ("K", 0),
+            PtkpStatus::K1 => ("K", 1),
+            PtkpStatus::K2 => ("K", 2),
+            PtkpStatus::K3 => ("K", 3),
+            PtkpStatus::Ki0 => ("K/I", 0),
+            PtkpStatus::Ki1 => ("K/I", 1),
+            PtkpStatus::Ki2 => ("K/I", 2),
+            PtkpStatus::Ki3 => ("K/I", 3),
+        }
+    }
+    
+    pub fn dependents(&self) -> u32 {
+        self.parts().1
+    }
+    
+    pub fn is_married(&self) -> bool {
+        self.parts().0 != "TK"
+    }
+    
+    // K/I: the wife's income is combined with the husband's
+    pub fn spouse_income_combined(&self) -> bool {
+        self.parts().0 == "K/I"
+    }
+    
+    // Built-in annual PTKP (PMK 101/PMK.010/2016): Rp 54,000,000 for the
+    // taxpayer plus Rp 4,500,000 for a spouse and per dependent; K/I adds
+    // another Rp 54,000,000 for the wife's combined income
+    pub fn ptkp_value(&self) -> f64 {
+        let (prefix, dependents) = self.parts();
+        let base = match prefix {
+            "K/I" => 112_500_000.0,
+            "K" => 58_500_000.0,
+            _ => 54_000_000.0,
+        };
+        
+        base + 4_500_000.0 * dependents as f64
+    }
+}
+
+impl fmt::Display for PtkpStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (prefix, dependents) = self.parts();
+        f.pad(&format!("{}/{}", prefix, dependents))
+    }
+}
+
+impl FromStr for PtkpStatus {
+    type Err = Message;
+    
+    // Parse a status written as in the tables, such as "TK/0" or "K/I/2"
+    fn from_str(input: &str) -> Result<PtkpStatus, Message> {
+        PtkpStatus::ALL
+            .iter()
+            .find(|status| status.to_string().eq_ignore_ascii_case(input.trim()))
+            .copied()
+            .ok_or_else(|| Message::new("error.unknown_ptkp_status", &[&input.trim()]))
+    }
+}
+
+impl TryFrom<String> for PtkpStatus {
+    type Error = Message;
+    
+    fn try_from(input: String) -> Result<PtkpStatus, Message> {
+        input.parse()
+    }
+}
+
+// Maximum number of dependents counted for PTKP
+pub const MAX_PTKP_DEPENDENTS: u32 = 3;
+
+// Sanity limit on entered dependents (separate from the PTKP cap of 3),
+// overridable with RUSTACEAN_MAX_DEPENDENTS
+pub const DEFAULT_MAX_DEPENDENTS_INPUT: u32 = 20;
+
+pub fn max_dependents_input() -> u32 {
+    std::env::var("RUSTACEAN_MAX_DEPENDENTS")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_MAX_DEPENDENTS_INPUT)
+}
+
+// Sanity limit on a monthly amount (Rp 1 trillion), overridable with
+// RUSTACEAN_MAX_MONTHLY_INCOME. Anything larger is a typo, and far enough
+// beyond it the annual figures overflow Decimal or f64
+pub const DEFAULT_MAX_MONTHLY_INCOME: f64 = 1_000_000_000_000.0;
+
+pub fn max_monthly_income() -> f64 {
+    std::env::var("RUSTACEAN_MAX_MONTHLY_INCOME")
+        .ok()
+        .and_then(|value| value.trim().parse::<f64>().ok())
+        .filter(|max| max.is_finite() && *max > 0.0)
+        .unwrap_or(DEFAULT_MAX_MONTHLY_INCOME)
+}
+
+// Reject a monthly `amount` above `max`, naming the field by the message key `label`
+pub fn check_monthly_bound(label: &'static str, amount: f64, max: f64) -> Result<(), CalcError> {
+    if amount > max {
+        return Err(CalcError::OutOfRange(Message::with_args(
+            "error.above_bound",
+            vec![MessageArg::Message(Message::new(label, &[])), MessageArg::Text(format_rupiah(max))],
+        )));
+    }
+    
+    Ok(())
+}
+
+// Dependents (tanggungan) as entered: a plain count or a list of ages
+#[derive(Debug, PartialEq)]
+pub enum Dependents {
+    Count(u32),
+    Ages(Vec<u8>),
+}
+
+impl Dependents {
+    // Parse "2" as a count, or comma-separated ages like "8,12,15" as a list;
+    // empty input means no dependents
+    pub fn parse(input: &str) -> Result<Dependents, CalcError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(Dependents::Count(0));
+        }
+        
+        let invalid = || CalcError::Parse(Message::new("error.invalid_dependents", &[&input]));
+        if input.contains(',') {
+            input
+                .split(',')
+                .map(str::trim)
+                .filter(|age| !age.is_empty())
+                .map(|age| age.parse::<u8>().map_err(|_| invalid()))
+                .collect::<Result<Vec<u8>, CalcError>>()
+                .map(Dependents::Ages)
+        } else {
+            input.parse().map(Dependents::Count).map_err(|_| invalid())
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        match self {
+            Dependents::Count(count) => *count,
+            Dependents::Ages(ages) => ages.len() as u32,
+        }
+    }
+
+    // Reject entries above the sanity limit, which are almost certainly typos
+    pub fn validate(self, max_dependents: u32) -> Result<Dependents, CalcError> {
+        if self.count() > max_dependents {
+            return Err(CalcError::OutOfRange(Message::new("error.too_many_dependents", &[&self.count(), &max_dependents])));
+        }
+        
+        Ok(self)
+    }
+    
+    // Number of dependents that count towards PTKP (max 3)
+    pub fn ptkp_count(&self) -> u32 {
+        self.count().min(MAX_PTKP_DEPENDENTS)
+    }
+    
+    // Notice for an entry above the PTKP maximum, so the cap is never silent
+    pub fn ptkp_notice(&self, lang: Lang) -> Option<String> {
+        (self.count() > MAX_PTKP_DEPENDENTS).then(|| t_with(
+            "note.ptkp_dependents_capped",
+            lang,
+            &[&MAX_PTKP_DEPENDENTS, &self.count(), &self.ptkp_count()],
+        ))
+    }
+}
+
+// Latest tax year covered by the built-in PTKP values and brackets
+pub const TAX_TABLES_YEAR: i32 = 2024;
+
+// Tax years with built-in tables, oldest first
+pub const SUPPORTED_TAX_YEARS: [u16; 3] = [2022, 2023, 2024];
+
+fn check_tax_year(year: u16) -> Result<(), Message> {
+    if SUPPORTED_TAX_YEARS.contains(&year) {
+        return Ok(());
+    }
+    let years: Vec<String> = SUPPORTED_TAX_YEARS.iter().map(|year| year.to_string()).collect();
+    
+    Err(Message::new("error.unsupported_year", &[&year, &years.join(", ")]))
+}
+
+// Source of the current date, so date-dependent behaviour can be tested
+pub trait Clock {
+    fn today(&self) -> NaiveDate;
+}
+
+// Clock backed by the local system time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> NaiveDate {
+        Local::now().date_naive()
+    }
+}
+
+// Warn (without blocking) when today is past the year the built-in tables cover
+pub fn outdated_tables_warning(clock: &dyn Clock, lang: Lang) -> Option<String> {
+    let today = clock.today();
+    if today.year() > TAX_TABLES_YEAR {
+        Some(t_with("note.outdated_tables", lang, &[&TAX_TABLES_YEAR, &today.year()]))
+    } else {
+        None
+    }
+}
+
+// Annual PTKP amount per status
+pub type PtkpTable = BTreeMap<PtkpStatus, f64>;
+
+// Built once on first use so per-row lookups in batch runs don't reallocate
+static BUILTIN_PTKP: LazyLock<PtkpTable> =
+    LazyLock::new(|| PtkpStatus::ALL.iter().map(|status| (*status, status.ptkp_value())).collect());
+
+// The built-in PTKP amount for every status, shared rather than copied
+pub fn builtin_ptkp() -> &'static PtkpTable {
+    &BUILTIN_PTKP
+}
+
+// The built-in PTKP amount for every status, as an owned table a config can change
+pub fn ptkp_table() -> PtkpTable {
+    builtin_ptkp().clone()
+}
+
+// PTKP values for a supported tax year; PMK 101/2016 amounts apply
+// unchanged to every year in SUPPORTED_TAX_YEARS
+pub fn ptkp_values_for_year(year: u16) -> Result<PtkpTable, Message> {
+    check_tax_year(year)?;
+    Ok(ptkp_table())
+}
+
+// Annual PTKP from the built-in tables for a tax year in SUPPORTED_TAX_YEARS
+pub fn compute_ptkp(married: bool, dependents: u8, spouse_income_combined: bool, year: u16) -> Result<f64, Message> {
+    check_tax_year(year)?;
+    
+    Ok(builtin_ptkp()[&ptkp_status_for(married, dependents, spouse_income_combined)?])
+}
+
+// PTKP status for a marital/dependents spec, rejecting specs that have no
+// PTKP status
+fn ptkp_status_for(married: bool, dependents: u8, spouse_income_combined: bool) -> Result<PtkpStatus, Message> {
+    if u32::from(dependents) > MAX_PTKP_DEPENDENTS {
+        return Err(Message::new("error.ptkp_dependents_limit", &[&MAX_PTKP_DEPENDENTS]));
+    }
+    if spouse_income_combined && !married {
+        return Err(Message::new("error.ptkp_combined_unmarried", &[]));
+    }
+    
+    Ok(PtkpStatus::new(married, spouse_income_combined, u32::from(dependents)))
+}
+
+// PTKP amounts and Pasal 17 brackets, either built in or loaded from a TOML file;
+// `rounding` may be omitted from the file and defaults to half-up,
+// `vat_rate` is the default PPN percentage (see settings::resolve_vat_rate)
+// and `flat_rate` the percentage of gross pay for the flat method;
+// the BPJS salary ceilings default to the latest year's, and `presets`
+// replaces the built-in presets when the file lists any, as `final_taxes`
+// (category name to percentage) does the built-in final-tax rates
+#[derive(Debug, Deserialize)]
+pub struct TaxConfig {
+    pub ptkp: PtkpTable,
+    pub brackets: Vec<TaxBracket>,
+    #[serde(default)]
+    pub rounding: RoundingMode,
+    #[serde(default)]
+    pub vat_rate: Option<f64>,
+    #[serde(default = "default_flat_rate")]
+    pub flat_rate: f64,
+    #[serde(default = "default_bpjs_kesehatan_salary_cap")]
+    pub bpjs_kesehatan_salary_cap: f64,
+    #[serde(default = "default_bpjs_jp_salary_cap")]
+    pub bpjs_jp_salary_cap: f64,
+    #[serde(default = "builtin_presets")]
+    pub presets: Vec<Preset>,
+    #[serde(default = "builtin_final_taxes")]
+    pub final_taxes: BTreeMap<String, f64>,
+}
+
+// Percentage of gross pay withheld under the legacy flat PPh 21 method
+pub const FLAT_PPH21_RATE: f64 = 0.75;
+
+fn default_flat_rate() -> f64 {
+    FLAT_PPH21_RATE
+}
+
+fn default_bpjs_kesehatan_salary_cap() -> f64 {
+    BPJS_KESEHATAN_SALARY_CAP
+}
+
+fn default_bpjs_jp_salary_cap() -> f64 {
+    bpjs_jp_salary_cap_for_year(TAX_TABLES_YEAR as u16).expect("the latest tax year is supported")
+}
+
+impl Default for TaxConfig {
+    // The built-in tables for the latest tax year
+    fn default() -> Self {
+        TaxConfig {
+            ptkp: ptkp_table(),
+            brackets: get_tax_brackets(),
+            rounding: RoundingMode::default(),
+            vat_rate: None,
+            flat_rate: FLAT_PPH21_RATE,
+            bpjs_kesehatan_salary_cap: BPJS_KESEHATAN_SALARY_CAP,
+            bpjs_jp_salary_cap: default_bpjs_jp_salary_cap(),
+            presets: builtin_presets(),
+            final_taxes: builtin_final_taxes(),
+        }
+    }
+}
+
+impl TaxConfig {
+    // The built-in tables for a supported tax year
+    pub fn for_year(year: u16) -> Result<TaxConfig, Message> {
+        Ok(TaxConfig {
+            ptkp: ptkp_values_for_year(year)?,
+            brackets: tax_brackets_for_year(year)?,
+            rounding: RoundingMode::default(),
+            vat_rate: None,
+            flat_rate: FLAT_PPH21_RATE,
+            bpjs_kesehatan_salary_cap: BPJS_KESEHATAN_SALARY_CAP,
+            bpjs_jp_salary_cap: bpjs_jp_salary_cap_for_year(year)?,
+            presets: builtin_presets(),
+            final_taxes: builtin_final_taxes(),
+        })
+    }
+    
+    pub fn from_toml(contents: &str) -> Result<TaxConfig, Message> {
+        let config: TaxConfig = toml::from_str(contents).map_err(|err| Message::new("error.config_invalid", &[&err]))?;
+        config.validate()?;
+        Ok(config)
+    }
+    
+    // Reject tables the calculations cannot use: a missing TK/0 or K/0-K/3
+    // PTKP amount (the other statuses are derived from these), or brackets
+    // that do not start at 0 and join up
+    fn validate(&self) -> Result<(), Message> {
+        let missing: Vec<String> = [PtkpStatus::Tk0, PtkpStatus::K0, PtkpStatus::K1, PtkpStatus::K2, PtkpStatus::K3]
+            .iter()
+            .filter(|status| !self.ptkp.contains_key(status))
+            .map(PtkpStatus::to_string)
+            .collect();
+        if !missing.is_empty() {
+            return Err(Message::new("error.config_missing_ptkp", &[&missing.join(", ")]));
+        }
+        if !check_bracket_contiguity(&self.brackets) {
+            return Err(Message::new("error.config_brackets", &[]));
+        }
+        for preset in &self.presets {
+            preset.params().map_err(|err| {
+                Message::with_args("error.config_preset", vec![MessageArg::Text(preset.name.clone()), MessageArg::Message(err.message())])
+            })?;
+        }
+        if let Some((category, rate)) = self.final_taxes.iter().find(|(_, rate)| !(0.0..=100.0).contains(*rate)) {
+            return Err(Message::new("error.config_final_tax", &[category, rate]));
+        }
+        
+        Ok(())
+    }
+    
+    pub fn find_preset(&self, name: &str) -> Option<&Preset> {
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+    
+    // fingerprint_tables of this configuration
+    pub fn fingerprint(&self) -> String {
+        fingerprint_tables(self)
+    }
+    
+    pub fn load(path: &std::path::Path) -> Result<TaxConfig, Message> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|err| Message::new("error.config_read", &[&path.display(), &err]))?;
+        TaxConfig::from_toml(&contents)
+    }
+    
+    // Annual PTKP for a marital/dependents spec, rejecting specs that have
+    // no PTKP status
+    pub fn ptkp_for(&self, married: bool, dependents: u8, spouse_income_combined: bool) -> Result<f64, Message> {
+        Ok(self.ptkp(ptkp_status_for(married, dependents, spouse_income_combined)?))
+    }
+    
+    // PTKP added per dependent: the step from K/0 to K/1
+    pub fn dependent_ptkp(&self) -> f64 {
+        self.ptkp(PtkpStatus::K1) - self.ptkp(PtkpStatus::K0)
+    }
+    
+    // Annual PTKP for a status. A config that leaves a status out derives it
+    // from the others: K/I as the K amount plus TK/0, and TK with dependents
+    // as TK/0 plus the married per-dependent step
+    pub fn ptkp(&self, status: PtkpStatus) -> f64 {
+        if let Some(ptkp) = self.ptkp.get(&status) {
+            return *ptkp;
+        }
+        
+        let dependents = status.dependents();
+        match status.parts().0 {
+            "K/I" => self.ptkp(PtkpStatus::new(true, false, dependents)) + self.ptkp(PtkpStatus::Tk0),
+            "TK" if dependents > 0 => {
+                self.ptkp(PtkpStatus::Tk0) + self.ptkp(PtkpStatus::new(true, false, dependents)) - self.ptkp(PtkpStatus::K0)
+            },
+            _ => 0.0,
+        }
+    }
+}
+
+// Money math runs on Decimal so rupiah amounts don't drift; the public API
+// keeps f64 and converts at the boundary. Non-finite or out-of-range values
+// (beyond ~7.9e28) become zero.
+pub fn to_decimal(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap_or(Decimal::ZERO)
+}
+
+fn from_decimal(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(0.0)
+}
+
+// How amounts are rounded to whole rupiah. Indonesian practice is half-up;
+// half-even (banker's rounding) and truncation are available for matching
+// other payroll systems
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RoundingMode {
+    // x.5 goes to x + 1
+    #[default]
+    HalfUp,
+    // x.5 goes to the even neighbour
+    HalfEven,
+    // Fractions are dropped
+    Down,
+}
+
+impl RoundingMode {
+    // MidpointAwayFromZero and ToZero match half-up and down for the
+    // non-negative amounts used here
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::Down => RoundingStrategy::ToZero,
+        }
+    }
+}
+
+// Round to whole rupiah under `mode`; every calculation rounds through here
+pub fn round_rupiah(value: f64, mode: RoundingMode) -> f64 {
+    from_decimal(round_decimal(to_decimal(value), mode))
+}
+
+// Round a final amount to the nearest multiple of `multiple` rupiah, halves
+// up, for payroll systems that pay in round figures; 0 leaves it unchanged.
+// Only for display: calculations keep the exact amounts
+pub fn round_to_multiple(value: f64, multiple: u32) -> f64 {
+    if multiple == 0 {
+        return value;
+    }
+    let multiple = Decimal::from(multiple);
+    from_decimal((to_decimal(value) / multiple).round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero) * multiple)
+}
+
+fn round_decimal(amount: Decimal, mode: RoundingMode) -> Decimal {
+    amount.round_dp_with_strategy(0, mode.strategy())
+}
+
+// Rupiah amount in Indonesian notation for display: dots between thousands
+// and two decimals after a comma, e.g. Rp 45.340,50
+pub fn format_rupiah(value: f64) -> String {
+    // An overflowed figure must not pass for Rp 0,00, which to_decimal would give
+    let Some(amount) = Decimal::from_f64(value) else {
+        return "Rp (tidak valid)".to_string();
+    };
+    let amount = amount.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero);
+    let sign = if amount < Decimal::ZERO { "-" } else { "" };
+    let amount = amount.abs();
+    let whole = amount.trunc().to_u128().unwrap_or(0);
+    let cents = ((amount - amount.trunc()) * Decimal::from(100)).to_u8().unwrap_or(0);
+    
+    format!("{}Rp {},{:02}", sign, whole.separate_with_dots(), cents)
+}
+
+// PKP is rounded down to the nearest Rp 1,000 before brackets are applied
+pub fn round_down_pkp(pkp: f64) -> f64 {
+    from_decimal(floor_to_thousand(to_decimal(pkp)))
+}
+
+fn floor_to_thousand(amount: Decimal) -> Decimal {
+    let thousand = Decimal::from(1000);
+    (amount / thousand).floor() * thousand
+}
+
+// Biaya jabatan: 5% of annual gross, capped at Rp 6,000,000 per year (Rp 500,000 per month)
+pub const BIAYA_JABATAN_RATE: f64 = 0.05;
+pub const BIAYA_JABATAN_ANNUAL_CAP: f64 = 6_000_000.0;
+
+// Rounded to whole rupiah, since a solved gross-up salary is rarely a round number
+pub fn calculate_biaya_jabatan(annual_gross: f64) -> f64 {
+    from_decimal(biaya_jabatan(to_decimal(annual_gross), RoundingMode::default()))
+}
+
+fn biaya_jabatan(annual_gross: Decimal, mode: RoundingMode) -> Decimal {
+    round_decimal((annual_gross * to_decimal(BIAYA_JABATAN_RATE)).min(to_decimal(BIAYA_JABATAN_ANNUAL_CAP)), mode)
+}
+
+// Biaya jabatan for a partial year: the cap is Rp 500,000 per month worked
+fn biaya_jabatan_for_months(annual_gross: Decimal, months_worked: u8, mode: RoundingMode) -> Decimal {
+    biaya_jabatan(annual_gross, mode).min(to_decimal(BIAYA_JABATAN_ANNUAL_CAP) * Decimal::from(months_worked) / Decimal::from(12))
+}
+
+// Surcharge on PPh 21 for employees without an NPWP
+pub const NO_NPWP_SURCHARGE: f64 = 1.2;
+
+// Round a computed PPh 21 amount to the rupiah and apply the no-NPWP surcharge
+pub fn apply_npwp_surcharge(tax: f64, has_npwp: bool) -> f64 {
+    surcharge_rounded(tax, has_npwp, RoundingMode::default())
+}
+
+// Round `tax` under `mode`, then apply the surcharge
+fn surcharge_rounded(tax: f64, has_npwp: bool, mode: RoundingMode) -> f64 {
+    from_decimal(npwp_surcharge(round_decimal(to_decimal(tax), mode), has_npwp, mode))
+}
+
+fn npwp_surcharge(tax: Decimal, has_npwp: bool, mode: RoundingMode) -> Decimal {
+    if has_npwp {
+        tax
+    } else {
+        round_decimal(tax * to_decimal(NO_NPWP_SURCHARGE), mode)
+    }
+}
+
+// Calculate PPh 21 for monthly employee;
+// returns (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan)
+pub fn calculate_pph21(params: &PPh21Params, config: &TaxConfig) -> (f64, f64, f64, f64, f64) {
+    let monthly_gross = to_decimal(params.monthly_gross());
+    let annual_gross = monthly_gross * Decimal::from(params.salary_payments());
+    
+    // Get PTKP based on marital status and number of dependents, prorated
+    // over the months worked for a mid-year start
+    let months_worked = Decimal::from(params.months_worked);
+    let ptkp = round_decimal(to_decimal(params.annual_ptkp(config)) * months_worked / Decimal::from(12), config.rounding);
+    
+    // Deduct biaya jabatan (occupational cost) before PTKP; the cap is
+    // Rp 500,000 per month worked
+    let biaya_jabatan = biaya_jabatan_for_months(annual_gross, params.months_worked, config.rounding);
+    
+    // Calculate PKP (Penghasilan Kena Pajak) after pension contributions and
+    // zakat, rounded down to the nearest thousand
+    let deductions = biaya_jabatan + to_decimal(params.annual_pension_contribution()) + to_decimal(params.zakat);
+    let pkp = floor_to_thousand((annual_gross - deductions - ptkp).max(Decimal::ZERO));
+    
+    // Calculate flat PPh 21 (config.flat_rate, 0.75% by default) on gross
+    // income. The monthly tax is rounded once, surcharge included, and the
+    // annual tax is that amount for each salary payment, so monthly x
+    // payments always equals annual
+    let pph_21_rate = to_decimal(config.flat_rate) / Decimal::ONE_HUNDRED;
+    let monthly_tax = npwp_surcharge(round_decimal(monthly_gross * pph_21_rate, config.rounding), params.has_npwp, config.rounding);
+    let annual_tax = monthly_tax * Decimal::from(params.salary_payments());
+    
+    (from_decimal(annual_tax), from_decimal(monthly_tax), from_decimal(ptkp), from_decimal(pkp), from_decimal(biaya_jabatan))
+}
+
+// PPh 21 calculation method
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pph21Method {
+    // Pasal 17 progressive brackets applied to annual PKP
+    Progressive,
+    // Legacy flat rate of gross income, TaxConfig::flat_rate
+    Flat,
+}
+
+// Calculate PPh 21 by applying the progressive brackets to the annual PKP
+pub fn calculate_pph21_progressive(params: &PPh21Params, config: &TaxConfig) -> (f64, f64, f64, f64, f64) {
+    // Biaya jabatan, PTKP and PKP are the same as for the flat method
+    let (_, _, ptkp, pkp, biaya_jabatan) = calculate_pph21(params, config);
+    
+    let annual_tax = surcharge_rounded(calculate_income_tax(pkp, &config.brackets), params.has_npwp, config.rounding);
+    // Withheld over the months worked
+    let monthly_tax = round_rupiah(annual_tax / f64::from(params.months_worked), config.rounding);
+    
+    (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan)
+}
+
+// Extra annual PPh 21 caused by an irregular payment (THR/bonus): the tax on
+// regular salary plus bonus minus the tax on regular salary alone
+pub fn calculate_pph21_bonus(monthly_gross: f64, bonus: f64, params: &PPh21Params, config: &TaxConfig) -> f64 {
+    let regular = PPh21Params { gross_income: monthly_gross, ..*params };
+    let (regular_tax, _, ptkp, _, _) = calculate_pph21_progressive(&regular, config);
+    
+    // Biaya jabatan is recomputed on the larger total, still capped per month worked
+    let annual_gross = regular.annual_gross() + bonus;
+    let biaya_jabatan = from_decimal(biaya_jabatan_for_months(to_decimal(annual_gross), params.months_worked, config.rounding));
+    let pkp = round_down_pkp((annual_gross - biaya_jabatan - params.annual_pension_contribution() - params.zakat - ptkp).max(0.0));
+    let total_tax = surcharge_rounded(calculate_income_tax(pkp, &config.brackets), params.has_npwp, config.rounding);
+    
+    total_tax - regular_tax
+}
+
+// PPh 21 withheld in each month worked when `bonus` is paid with the
+// `bonus_month`-th salary (1 = the first month worked): the regular monthly
+// tax every month, plus calculate_pph21_bonus in the bonus month. Both are
+// annualized over `params.months_worked`, not twelve months
+pub fn bonus_withholding_schedule(monthly_gross: f64, bonus: f64, bonus_month: u8, params: &PPh21Params, config: &TaxConfig) -> Result<Vec<f64>, CalcError> {
+    if !(1..=params.months_worked).contains(&bonus_month) {
+        return Err(CalcError::OutOfRange(Message::new("error.bonus_month", &[&params.months_worked, &bonus_month])));
+    }
+    
+    let (_, monthly_tax, _, _, _) = calculate_pph21_progressive(&PPh21Params { gross_income: monthly_gross, ..*params }, config);
+    let bonus_tax = calculate_pph21_bonus(monthly_gross, bonus, params, config);
+    
+    Ok((1..=params.months_worked)
+        .map(|month| if month == bonus_month { from_decimal(to_decimal(monthly_tax) + to_decimal(bonus_tax)) } else { monthly_tax })
+        .collect())
+}
+
+// Calculate PPh 21 with the chosen method
+pub fn calculate_pph21_with_method(params: &PPh21Params, method: Pph21Method, config: &TaxConfig) -> (f64, f64, f64, f64, f64) {
+    match method {
+        Pph21Method::Progressive => calculate_pph21_progressive(params, config),
+        Pph21Method::Flat => calculate_pph21(params, config),
+    }
+}
+
+// Named PPh 21 figures for serialization
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct Pph21Result {
+    pub gross_monthly: f64,
+    pub biaya_jabatan: f64,
+    pub ptkp: f64,
+    pub pkp: f64,
+    pub annual_tax: f64,
+    pub monthly_tax: f64,
+    // fingerprint_tables of the PTKP table and brackets the result was computed with
+    #[serde(default)]
+    pub tables_fingerprint: String,
+}
+
+impl Pph21Result {
+    // Builder starting from an all-zero result, for building one outside
+    // calculate_pph21_result now that new fields can be added
+    pub fn builder() -> Pph21ResultBuilder {
+        Pph21ResultBuilder::default()
+    }
+    
+    // The same result with the annual and monthly tax rounded by round_to_multiple
+    pub fn rounded_to(&self, multiple: u32) -> Pph21Result {
+        Pph21Result {
+            annual_tax: round_to_multiple(self.annual_tax, multiple),
+            monthly_tax: round_to_multiple(self.monthly_tax, multiple),
+            tables_fingerprint: self.tables_fingerprint.clone(),
+            ..*self
+        }
+    }
+}
+
+// Builder for Pph21Result; fields left unset stay zero
+#[derive(Debug, Clone, Default)]
+pub struct Pph21ResultBuilder {
+    gross_monthly: f64,
+    biaya_jabatan: f64,
+    ptkp: f64,
+    pkp: f64,
+    annual_tax: f64,
+    monthly_tax: f64,
+    tables_fingerprint: String,
+}
+
+impl Pph21ResultBuilder {
+    pub fn gross_monthly(mut self, gross_monthly: f64) -> Self {
+        self.gross_monthly = gross_monthly;
+        self
+    }
+    
+    pub fn biaya_jabatan(mut self, biaya_jabatan: f64) -> Self {
+        self.biaya_jabatan = biaya_jabatan;
+        self
+    }
+    
+    pub fn ptkp(mut self, ptkp: f64) -> Self {
+        self.ptkp = ptkp;
+        self
+    }
+    
+    pub fn pkp(mut self, pkp: f64) -> Self {
+        self.pkp = pkp;
+        self
+    }
+    
+    pub fn annual_tax(mut self, annual_tax: f64) -> Self {
+        self.annual_tax = annual_tax;
+        self
+    }
+    
+    pub fn monthly_tax(mut self, monthly_tax: f64) -> Self {
+        self.monthly_tax = monthly_tax;
+        self
+    }
+    
+    pub fn tables_fingerprint(mut self, tables_fingerprint: &str) -> Self {
+        self.tables_fingerprint = tables_fingerprint.to_string();
+        self
+    }
+    
+    pub fn build(self) -> Pph21Result {
+        Pph21Result {
+            gross_monthly: self.gross_monthly,
+            biaya_jabatan: self.biaya_jabatan,
+            ptkp: self.ptkp,
+            pkp: self.pkp,
+            annual_tax: self.annual_tax,
+            monthly_tax: self.monthly_tax,
+            tables_fingerprint: self.tables_fingerprint,
+        }
+    }
+}
+
+pub fn calculate_pph21_result(params: &PPh21Params, method: Pph21Method, config: &TaxConfig) -> Pph21Result {
+    let (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) = calculate_pph21_with_method(params, method, config);
+    
+    Pph21Result {
+        gross_monthly: params.monthly_gross(),
+        biaya_jabatan,
+        ptkp,
+        pkp,
+        annual_tax,
+        monthly_tax,
+        tables_fingerprint: config.fingerprint(),
+    }
+}
+
+// PPh 21 under one tax year's built-in tables, or why that year is unavailable
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct YearResult {
+    pub year: u16,
+    pub result: Result<Pph21Result, Message>,
+}
+
+// Progressive PPh 21 for the same params under each year's rules, in the
+// order the years are given
+pub fn compare_years(params: &PPh21Params, years: &[u16]) -> Vec<YearResult> {
+    years
+        .iter()
+        .map(|&year| YearResult {
+            year,
+            result: TaxConfig::for_year(year).map(|config| calculate_pph21_result(params, Pph21Method::Progressive, &config)),
+        })
+        .collect()
+}
+
+// Side-by-side table of a year comparison; unavailable years show dashes
+pub fn render_year_comparison(results: &[YearResult], lang: Lang) -> String {
+    let rows: Vec<Vec<TableCell>> = results
+        .iter()
+        .map(|year_result| {
+            let year = TableCell::Text(year_result.year.to_string());
+            match &year_result.result {
+                Ok(result) => vec![
+                    year,
+                    TableCell::Rupiah(result.ptkp),
+                    TableCell::Rupiah(result.pkp),
+                    TableCell::Rupiah(result.annual_tax),
+                    TableCell::Rupiah(result.monthly_tax),
+                ],
+                Err(_) => [year].into_iter().chain((0..4).map(|_| TableCell::Text("-".to_string()))).collect(),
+            }
+        })
+        .collect();
+    
+    render_table(&[t("table.year", lang), "PTKP", "PKP", t("label.annual_tax", lang), t("label.monthly_tax", lang)], &rows)
+}
+
+// Year-end figures for the annual SPT, built from the monthly pay of one tax year
+#[derive(Debug, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct AnnualSummary {
+    pub months: usize,
+    pub total_gross: f64,
+    pub biaya_jabatan: f64,
+    pub pension_contribution: f64,
+    pub zakat: f64,
+    pub ptkp: f64,
+    pub pkp: f64,
+    // Pasal 17 progressive tax on the year's PKP
+    pub annual_tax: f64,
+    // Sum of the PPh 21 withheld each month
+    pub total_withheld: f64,
+}
+
+impl AnnualSummary {
+    // Tax still owed at year end (kurang bayar) when positive, overpaid
+    // (lebih bayar) when negative
+    pub fn reconciliation(&self) -> f64 {
+        self.annual_tax - self.total_withheld
+    }
+}
+
+// Annual SPT summary for 1 to 12 months of pay, each withheld with `method`.
+// The PTKP status, NPWP and annual zakat are taken from the first month, since
+// the status at the start of the year applies for the whole year
+pub fn annual_summary(months: &[PPh21Params], method: Pph21Method, config: &TaxConfig) -> Result<AnnualSummary, CalcError> {
+    let first = match months {
+        [first, ..] if months.len() <= 12 => first,
+        _ => return Err(CalcError::OutOfRange(Message::new("error.summary_months", &[&months.len()]))),
+    };
+    
+    let months_worked = months.len() as f64;
+    let total_gross: f64 = months.iter().map(PPh21Params::monthly_gross).sum();
+    let pension_contribution: f64 = months.iter().map(|month| month.pension_contribution).sum();
+    let total_withheld: f64 = months.iter().map(|month| calculate_pph21_with_method(month, method, config).1).sum();
+    
+    // Biaya jabatan cap and PTKP are prorated over the months paid, as for a mid-year start
+    let biaya_jabatan = from_decimal(biaya_jabatan(to_decimal(total_gross), config.rounding))
+        .min(BIAYA_JABATAN_ANNUAL_CAP * months_worked / 12.0);
+    let ptkp = round_rupiah(first.annual_ptkp(config) * months_worked / 12.0, config.rounding);
+    let pkp = round_down_pkp((total_gross - biaya_jabatan - pension_contribution - first.zakat - ptkp).max(0.0));
+    let annual_tax = surcharge_rounded(calculate_income_tax(pkp, &config.brackets), first.has_npwp, config.rounding);
+    
+    Ok(AnnualSummary {
+        months: months.len(),
+        total_gross,
+        biaya_jabatan,
+        pension_contribution,
+        zakat: first.zakat,
+        ptkp,
+        pkp,
+        annual_tax,
+        total_withheld,
+    })
+}
+
+// Annual SPT summary when the same pay recurs in every month worked
+pub fn recurring_annual_summary(params: &PPh21Params, method: Pph21Method, config: &TaxConfig) -> AnnualSummary {
+    let months = vec![params.clone(); usize::from(params.months_worked)];
+    annual_summary(&months, method, config).expect("months_worked is always 1-12")
+}
+
+// Annual summary of a year that ended with severance pay: the pesangon is
+// taxed final on its own schedule and stays out of the salary's PKP
+#[derive(Debug, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct SeveranceAnnualSummary {
+    pub salary: AnnualSummary,
+    pub severance: f64,
+    pub severance_tax: f64,
+}
+
+impl SeveranceAnnualSummary {
+    // PPh 21 withheld in the year: the monthly salary withholding plus the
+    // final tax on the severance
+    pub fn total_withheld(&self) -> f64 {
+        from_decimal(to_decimal(self.salary.total_withheld) + to_decimal(self.severance_tax))
+    }
+    
+    // PPh 21 due for the year, progressive on the salary and final on the severance
+    pub fn total_tax(&self) -> f64 {
+        from_decimal(to_decimal(self.salary.annual_tax) + to_decimal(self.severance_tax))
+    }
+}
+
+// annual_summary of the monthly pay with `severance` taxed apart by
+// calculate_pesangon_tax
+pub fn annual_summary_with_severance(months: &[PPh21Params], severance: f64, method: Pph21Method, config: &TaxConfig) -> Result<SeveranceAnnualSummary, CalcError> {
+    Ok(SeveranceAnnualSummary {
+        salary: annual_summary(months, method, config)?,
+        severance,
+        severance_tax: round_rupiah(calculate_pesangon_tax(severance), config.rounding),
+    })
+}
+
+// Label and magnitude for a year-end settlement of `amount`: tax still owed
+// is kurang bayar, over-withholding a lebih bayar refund, never a negative sum
+pub fn settlement_row(amount: f64, lang: Lang) -> (&'static str, f64) {
+    if amount < 0.0 {
+        (t("label.overpaid", lang), -amount)
+    } else {
+        (t("label.underpaid", lang), amount)
+    }
+}
+
+// Format the annual summary, ending with the year-end kurang/lebih bayar
+pub fn render_annual_summary(summary: &AnnualSummary, lang: Lang) -> String {
+    let mut output = format!("{}\n", t_with("summary.title", lang, &[&summary.months]));
+    let mut line = |label: &str, amount: f64| output.push_str(&format!("{:<27}: {:>20}\n", label, format_rupiah(amount)));
+    
+    line(t("summary.annual_gross", lang), summary.total_gross);
+    line(t("label.biaya_jabatan", lang), summary.biaya_jabatan);
+    if summary.pension_contribution > 0.0 {
+        line(t("label.pension", lang), summary.pension_contribution);
+    }
+    if summary.zakat > 0.0 {
+        line("Zakat", summary.zakat);
+    }
+    line("PTKP", summary.ptkp);
+    line("PKP", summary.pkp);
+    line(t("summary.annual_tax_due", lang), summary.annual_tax);
+    line(t("summary.withheld", lang), summary.total_withheld);
+    
+    let reconciliation = summary.reconciliation();
+    if reconciliation == 0.0 {
+        output.push_str(&format!("{}\n", t("summary.nil", lang)));
+    } else {
+        let (label, amount) = settlement_row(reconciliation, lang);
+        line(label, amount);
+    }
+    
+    output
+}
+
+// December PPh 21: the full-year tax on `params` under the progressive
+// brackets, less `ytd_withheld` over January to November. A negative result
+// is over-withholding the employer returns to the employee with December pay
+pub fn calculate_pph21_december(ytd_withheld: f64, params: &PPh21Params, config: &TaxConfig) -> f64 {
+    let (annual_tax, _, _, _, _) = calculate_pph21_progressive(params, config);
+    from_decimal(to_decimal(annual_tax) - to_decimal(ytd_withheld))
+}
+
+// Tax withheld or paid in advance during the year, credited on the annual return
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TaxCredits {
+    pub pph21: f64,
+    pub pph22: f64,
+    pub pph23: f64,
+    // Monthly installments paid by the taxpayer
+    pub pph25: f64,
+}
+
+impl TaxCredits {
+    // Credits for the year from every withholding slip received, each added to
+    // the credit for its kind of tax
+    pub fn from_withholdings(withholdings: &[Withholding]) -> TaxCredits {
+        let mut credits = TaxCredits::default();
+        for withholding in withholdings {
+            let credit = match withholding.kind {
+                WithholdingKind::Pph21 => &mut credits.pph21,
+                WithholdingKind::Pph22 => &mut credits.pph22,
+                WithholdingKind::Pph23 => &mut credits.pph23,
+            };
+            *credit = from_decimal(to_decimal(*credit) + to_decimal(withholding.withheld));
+        }
+        credits
+    }
+    
+    pub fn total(&self) -> f64 {
+        from_decimal(to_decimal(self.pph21) + to_decimal(self.pph22) + to_decimal(self.pph23) + to_decimal(self.pph25))
+    }
+}
+
+// Withholding tax the recipient can credit on the annual return
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WithholdingKind {
+    Pph21,
+    // Withheld on purchases and imports; there is no PPh 22 calculator, so
+    // these come from the slip as given
+    Pph22,
+    Pph23,
+}
+
+// One withholding slip: the gross it was withheld from and the tax withheld
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Withholding {
+    pub kind: WithholdingKind,
+    pub gross: f64,
+    pub withheld: f64,
+}
+
+// The year's progressive PPh 21 withheld by an employer on `params`
+pub fn pph21_withholding(params: &PPh21Params, config: &TaxConfig) -> Withholding {
+    let (annual_tax, _, _, _, _) = calculate_pph21_progressive(params, config);
+    Withholding { kind: WithholdingKind::Pph21, gross: params.annual_gross(), withheld: annual_tax }
+}
+
+// calculate_pph23 as a creditable withholding
+pub fn pph23_withholding(amount: f64, object: Pph23Object, has_npwp: bool, config: &TaxConfig) -> Withholding {
+    Withholding { kind: WithholdingKind::Pph23, gross: amount, withheld: calculate_pph23(amount, object, has_npwp, config) }
+}
+
+// Gross income taxed at a final Pasal 4 ayat (2) rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FinalIncome {
+    pub object: PphFinal42Object,
+    pub gross: f64,
+}
+
+// Everything an individual annual return (SPT Tahunan) is built from
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnualReturnInputs {
+    // Employment pay, which also sets the PTKP status; a gross of 0 for a
+    // taxpayer without a salary
+    pub salary: PPh21Params,
+    // Net business or other income for the year that is not taxed as final
+    pub other_net_income: f64,
+    pub final_income: Vec<FinalIncome>,
+    pub credits: TaxCredits,
+}
+
+// Figures of an individual annual return
+#[derive(Debug, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct AnnualReturn {
+    // Salary less biaya jabatan, pension and zakat, plus the other net income
+    pub net_income: f64,
+    pub ptkp: f64,
+    pub pkp: f64,
+    // Pasal 17 progressive tax on the PKP
+    pub progressive_tax: f64,
+    pub credits: f64,
+    // Final 4(2) tax on the final income, reported apart from the PKP
+    pub final_tax: f64,
+}
+
+impl AnnualReturn {
+    // Progressive tax less the credits: still owed (kurang bayar) when
+    // positive, a refund (lebih bayar) when negative. The final tax is
+    // settled at source, so it does not change this
+    pub fn tax_due(&self) -> f64 {
+        from_decimal(to_decimal(self.progressive_tax) - to_decimal(self.credits))
+    }
+    
+    // Income tax for the year, progressive and final together
+    pub fn total_tax(&self) -> f64 {
+        from_decimal(to_decimal(self.progressive_tax) + to_decimal(self.final_tax))
+    }
+}
+
+// Annual return: the salary as in recurring_annual_summary plus the other net
+// income, less PTKP, taxed progressively without the NPWP surcharge that only
+// applies to withholding; final income is taxed on its own at its 4(2) rate
+pub fn annual_return(inputs: &AnnualReturnInputs, config: &TaxConfig) -> AnnualReturn {
+    let salary = recurring_annual_summary(&inputs.salary, Pph21Method::Progressive, config);
+    let net_income = from_decimal(
+        to_decimal(salary.total_gross) - to_decimal(salary.biaya_jabatan) - to_decimal(salary.pension_contribution) - to_decimal(salary.zakat)
+            + to_decimal(inputs.other_net_income),
+    );
+    let pkp = round_down_pkp((net_income - salary.ptkp).max(0.0));
+    let final_tax = inputs
+        .final_income
+        .iter()
+        .map(|income| to_decimal(calculate_pph_final_42(income.gross, income.object, config)))
+        .sum();
+    
+    AnnualReturn {
+        net_income,
+        ptkp: salary.ptkp,
+        pkp,
+        progressive_tax: round_rupiah(calculate_income_tax(pkp, &config.brackets), config.rounding),
+        credits: inputs.credits.total(),
+        final_tax: from_decimal(final_tax),
+    }
+}
+
+// Largest sequence number that fits the 7-digit bukti potong counter
+pub const MAX_BUKTI_POTONG_SEQUENCE: u32 = 9_999_999;
+
+// Withholding slip reference for an employee's PPh 21
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BuktiPotong {
+    pub number: String,
+    pub npwp: String,
+}
+
+// Strip the usual dots and dashes from an NPWP and check it has 15 digits
+// (old format) or 16 digits (NIK-based)
+pub fn normalize_npwp(npwp: &str) -> Result<String, CalcError> {
+    let digits: String = npwp.trim().chars().filter(|c| !matches!(c, '.' | '-' | ' ')).collect();
+    
+    if !digits.chars().all(|c| c.is_ascii_digit()) || !(digits.len() == 15 || digits.len() == 16) {
+        return Err(CalcError::Parse(Message::new("error.invalid_npwp", &[&npwp.trim()])));
+    }
+    
+    Ok(digits)
+}
+
+// Parse a tax period written as MM/YYYY
+pub fn parse_period(input: &str) -> Result<(u32, i32), CalcError> {
+    let invalid = || CalcError::Parse(Message::new("error.invalid_period", &[&input.trim()]));
+    let (month, year) = input.trim().split_once('/').ok_or_else(invalid)?;
+    let month = parse_in_range(month, 1, 12)?;
+    let year = year.trim().parse::<i32>().ok().filter(|year| (1000..=9999).contains(year)).ok_or_else(invalid)?;
+    
+    Ok((month, year))
+}
+
+// Bukti potong 1721-A1 number "1.1-MM.YY-NNNNNNN" for the given period and
+// sequence, paired with the employee's validated NPWP
+pub fn bukti_potong(npwp: &str, month: u32, year: i32, sequence: u32) -> Result<BuktiPotong, CalcError> {
+    let npwp = normalize_npwp(npwp)?;
+    if !(1..=12).contains(&month) {
+        return Err(CalcError::OutOfRange(Message::new("error.month_range", &[&month])));
+    }
+    if !(1..=MAX_BUKTI_POTONG_SEQUENCE).contains(&sequence) {
+        return Err(CalcError::OutOfRange(Message::new("error.sequence_range", &[&sequence, &MAX_BUKTI_POTONG_SEQUENCE])));
+    }
+    
+    Ok(BuktiPotong {
+        number: format!("1.1-{:02}.{:02}-{:07}", month, year.rem_euclid(100), sequence),
+        npwp,
+    })
+}
+
+// PPh 21 result together with its bukti potong, for JSON output
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Pph21WithBuktiPotong {
+    #[serde(flatten)]
+    pub result: Pph21Result,
+    pub bukti_potong: BuktiPotong,
+}
+
+// One operation on the way from gross pay to PPh 21: what was done, the
+// amount it used and the running value after it
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CalcStep {
+    pub label: &'static str,
+    pub detail: String,
+    pub amount: f64,
+    pub running: f64,
+}
+
+// Progressive PPh 21 result together with the steps that produced it, for JSON output
+#[derive(Debug, Serialize)]
+pub struct Pph21WithTrace {
+    #[serde(flatten)]
+    pub result: Pph21Result,
+    pub steps: Vec<CalcStep>,
+}
+
+// Progressive PPh 21 with a step-by-step trace: annualize, subtract biaya
+// jabatan and other deductions, subtract PTKP, floor to the thousand, apply
+// each bracket, then the NPWP surcharge and the monthly split. The running
+// values end at the same figures as calculate_pph21_progressive. Labels and
+// details are in `lang`
+pub fn trace_pph21(params: &PPh21Params, config: &TaxConfig, lang: Lang) -> Pph21WithTrace {
+    let result = calculate_pph21_result(params, Pph21Method::Progressive, config);
+    let mut steps = Vec::new();
+    let mut step = |label, detail: String, amount, running| steps.push(CalcStep { label, detail, amount, running });
+    
+    let monthly_gross = params.monthly_gross();
+    step(t("trace.monthly_gross", lang), String::new(), monthly_gross, monthly_gross);
+    let annual_gross = params.annual_gross();
+    step(t("trace.annualized", lang), t_with("trace.annualized_detail", lang, &[&params.salary_payments()]), annual_gross, annual_gross);
+    
+    let mut net = annual_gross - result.biaya_jabatan;
+    step(t("trace.biaya_jabatan", lang), t_with("trace.biaya_jabatan_detail", lang, &[&(BIAYA_JABATAN_RATE * 100.0), &format_rupiah(BIAYA_JABATAN_ANNUAL_CAP)]), result.biaya_jabatan, net);
+    let pension = params.annual_pension_contribution();
+    if pension > 0.0 {
+        net -= pension;
+        step(t("trace.pension", lang), String::new(), pension, net);
+    }
+    if params.zakat > 0.0 {
+        net -= params.zakat;
+        step(t("trace.zakat", lang), String::new(), params.zakat, net);
+    }
+    
+    let before_rounding = (net - result.ptkp).max(0.0);
+    step(t("trace.ptkp", lang), params.ptkp_label(), result.ptkp, before_rounding);
+    step(t("trace.rounded", lang), "PKP".to_string(), before_rounding - result.pkp, result.pkp);
+    
+    let mut tax = 0.0;
+    for contribution in income_tax_breakdown(result.pkp, &config.brackets) {
+        tax += contribution.tax;
+        step(t("trace.bracket", lang), format!("{}% x {}", contribution.rate * 100.0, format_rupiah(contribution.taxable)), contribution.tax, tax);
+    }
+    if !params.has_npwp {
+        step(t("trace.no_npwp", lang), format!("{}%", (NO_NPWP_SURCHARGE - 1.0) * 100.0), result.annual_tax - tax, result.annual_tax);
+    }
+    step(t("trace.monthly", lang), t_with("trace.monthly_detail", lang, &[&params.months_worked]), result.annual_tax, result.monthly_tax);
+    
+    Pph21WithTrace { result, steps }
+}
+
+// Format a trace as a table, one row per step, with headers in `lang`
+pub fn render_trace(steps: &[CalcStep], lang: Lang) -> String {
+    let rows: Vec<Vec<TableCell>> = steps
+        .iter()
+        .map(|step| {
+            vec![
+                TableCell::Text(step.label.to_string()),
+                TableCell::Text(step.detail.clone()),
+                TableCell::Rupiah(step.amount),
+                TableCell::Rupiah(step.running),
+            ]
+        })
+        .collect();
+    
+    render_table(&[t("table.step", lang), t("table.detail", lang), t("table.amount", lang), t("table.running", lang)], &rows)
+}
+
+// Iteration limit for the gross-up solver
+pub const GROSS_UP_MAX_ITERATIONS: u32 = 100;
+
+// Default gross-up tolerance: the solver stops once an iteration moves the
+// gross by less than this many rupiah
+pub const GROSS_UP_TOLERANCE: f64 = 0.01;
+
+// Smallest gross-up step that counts as stalled when it fails to shrink
+pub const GROSS_UP_STALL_STEP: f64 = 1_000.0;
+
+// Gross salary whose progressive PPh 21 leaves exactly `net_salary` after tax.
+// The net is the take-home pay with `params.overtime` on top of the salary, so
+// the solved salary plus overtime less the tax equals `net_salary`; every other
+// field of `params` except gross_income is used as given
+pub fn gross_up(net_salary: f64, params: &PPh21Params, config: &TaxConfig) -> Result<f64, CalcError> {
+    gross_up_with_tolerance(net_salary, params, config, GROSS_UP_TOLERANCE)
+}
+
+// gross_up stopping once an iteration moves the gross by less than `tolerance` rupiah
+pub fn gross_up_with_tolerance(net_salary: f64, params: &PPh21Params, config: &TaxConfig, tolerance: f64) -> Result<f64, CalcError> {
+    gross_up_with_limit(net_salary, params, config, tolerance, GROSS_UP_MAX_ITERATIONS)
+}
+
+// gross_up_with_tolerance giving up with CalcError::DidNotConverge after
+// `max_iterations`, or with CalcError::Unreachable once a step stops shrinking
+pub fn gross_up_with_limit(net_salary: f64, params: &PPh21Params, config: &TaxConfig, tolerance: f64, max_iterations: u32) -> Result<f64, CalcError> {
+    if !tolerance.is_finite() || tolerance <= 0.0 {
+        return Err(CalcError::OutOfRange(Message::new("error.tolerance", &[&tolerance])));
+    }
+    
+    let monthly_tax = |gross_income: f64| {
+        calculate_pph21_with_method(&PPh21Params { gross_income, ..*params }, Pph21Method::Progressive, config).1
+    };
+    
+    // Fixed-point iteration gross = net - overtime + tax(gross): the tax grows
+    // slower than the gross, so the sequence rises monotonically to the solution
+    // and each step is shorter than the last. A step that is not shorter means
+    // the gross has entered a bracket taking every extra rupiah (100% or more
+    // with the no-NPWP surcharge), where the net no longer rises, so the target
+    // is out of reach. Steps under GROSS_UP_STALL_STEP are left to the
+    // iteration limit, as rounding the tax to rupiah can keep those from shrinking
+    let salary_net = net_salary - params.overtime;
+    let mut gross = salary_net.max(0.0);
+    let mut residual = f64::INFINITY;
+    for iteration in 1..=max_iterations {
+        let next = (salary_net + monthly_tax(gross)).max(0.0);
+        log::debug!("Gross-up iterasi {}: bruto {} untuk neto {}", iteration, next, net_salary);
+        let step = (next - gross).abs();
+        if step < tolerance {
+            return Ok(next);
+        }
+        if step >= residual && step >= GROSS_UP_STALL_STEP {
+            return Err(CalcError::Unreachable(Message::new("error.gross_up_unreachable", &[&format_rupiah(net_salary)])));
+        }
+        residual = step;
+        gross = next;
+    }
+    
+    log::warn!("Gross-up neto {} belum konvergen setelah {} iterasi", net_salary, max_iterations);
+    Err(CalcError::DidNotConverge { iterations: max_iterations, residual })
+}
+
+// gross_up under the built-in PTKP and Pasal 17 tables of a year in
+// SUPPORTED_TAX_YEARS; gross_up itself solves against whatever brackets
+// `config` carries, such as a table loaded with --brackets
+pub fn gross_up_for_year(net_salary: f64, params: &PPh21Params, year: u16) -> Result<f64, CalcError> {
+    let config = TaxConfig::for_year(year).map_err(CalcError::OutOfRange)?;
+    gross_up(net_salary, params, &config)
+}
+
+// Progressive PPh 21 for the year as a percentage of the annual gross; 0 for no gross
+pub fn effective_tax_rate(params: &PPh21Params, config: &TaxConfig) -> f64 {
+    let annual_gross = params.annual_gross();
+    if annual_gross <= 0.0 {
+        return 0.0;
+    }
+    let (annual_tax, _, _, _, _) = calculate_pph21_progressive(params, config);
+    
+    annual_tax / annual_gross * 100.0
+}
+
+// Monthly gross salary at which effective_tax_rate reaches `target_rate`
+// percent, bisected to within a rupiah; every other field of `params` is used as given.
+// The effective rate only approaches the top marginal rate (with the no-NPWP
+// surcharge), so targets at or above it, and targets of 0% or less, are rejected
+pub fn gross_for_effective_rate(target_rate: f64, params: &PPh21Params, config: &TaxConfig) -> Result<f64, CalcError> {
+    let top_rate = config.brackets.last().map(|bracket| bracket.rate * 100.0).unwrap_or(0.0);
+    let top_rate = if params.has_npwp { top_rate } else { top_rate * NO_NPWP_SURCHARGE };
+    if !target_rate.is_finite() || target_rate <= 0.0 {
+        return Err(CalcError::OutOfRange(Message::new("error.effective_rate_unreachable", &[&target_rate, &top_rate])));
+    }
+    if target_rate >= top_rate {
+        return Err(CalcError::Unreachable(Message::new("error.effective_rate_unreachable", &[&target_rate, &top_rate])));
+    }
+    
+    let rate_at = |gross_income: f64| effective_tax_rate(&PPh21Params { gross_income, ..*params }, config);
+    let mut low = 0.0;
+    let mut high = 1_000_000.0;
+    while rate_at(high) < target_rate {
+        low = high;
+        high *= 2.0;
+    }
+    // Bisect: the rate is below the target at `low` and reaches it at `high`
+    while high - low > 1.0 {
+        let middle = (low + high) / 2.0;
+        if rate_at(middle) < target_rate {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    
+    Ok(high)
+}
+
+// Validate a requested net salary before solving the gross-up
+pub fn validate_requested_net(input: &str) -> Result<f64, CalcError> {
+    let net_salary = parse_rupiah(input)?;
+    
+    if !net_salary.is_finite() || net_salary <= 0.0 {
+        return Err(CalcError::OutOfRange(Message::new("error.requested_net", &[&input.trim()])));
+    }
+    check_monthly_bound("error.label_requested_net", net_salary, max_monthly_income())?;
+    
+    Ok(net_salary)
+}
+
+// Parse a Rupiah amount as typed or copied from the output: an optional "Rp"
+// prefix, spaces, and either "." (Indonesian) or "," (English) grouping.
+// When both separators appear, the last one is the decimal separator; a
+// lone separator followed by exactly three digits is grouping, so
+// "Rp6.000" and "6,000" are both six thousand while "6,5" is six and a half
+pub fn parse_rupiah(input: &str) -> Result<f64, CalcError> {
+    let invalid = || CalcError::Parse(Message::new("error.invalid_amount", &[&input.trim()]));
+    
+    let text: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text.as_str()),
+    };
+    let text = match text.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("rp") => text[2..].strip_prefix('.').unwrap_or(&text[2..]),
+        _ => text,
+    };
+    
+    let normalized = normalize_separators(text).ok_or_else(invalid)?;
+    format!("{}{}", sign, normalized).parse::<f64>().map_err(|_| invalid())
+}
+
+// Rewrite grouped digits to a plain "1234.5" form, or None if the grouping
+// is not in threes
+fn normalize_separators(text: &str) -> Option<String> {
+    let dots = text.matches('.').count();
+    let commas = text.matches(',').count();
+    let (grouping, decimal) = match (dots, commas) {
+        (0, 0) => return Some(text.to_string()),
+        (_, 0) | (0, _) => {
+            let separator = if dots > 0 { '.' } else { ',' };
+            let lone_decimal = dots + commas == 1 && text.rsplit(separator).next().is_some_and(|tail| tail.len() != 3);
+            if lone_decimal { (None, Some(separator)) } else { (Some(separator), None) }
+        },
+        _ if text.rfind('.') > text.rfind(',') => (Some(','), Some('.')),
+        _ => (Some('.'), Some(',')),
+    };
+    
+    let (whole, fraction) = match decimal {
+        Some(separator) => text.rsplit_once(separator)?,
+        None => (text, ""),
+    };
+    if decimal.is_some_and(|separator| whole.contains(separator)) {
+        return None;
+    }
+    let whole = match grouping {
+        Some(separator) => {
+            let groups: Vec<&str> = whole.split(separator).collect();
+            let well_grouped = (1..=3).contains(&groups[0].len()) && groups[1..].iter().all(|group| group.len() == 3);
+            if !well_grouped {
+                return None;
+            }
+            groups.concat()
+        },
+        None => whole.to_string(),
+    };
+    
+    Some(if fraction.is_empty() { whole } else { format!("{}.{}", whole, fraction) })
+}
+
+// Parse a non-negative Rupiah amount
+pub fn parse_amount(input: &str) -> Result<f64, CalcError> {
+    let amount = parse_rupiah(input)?;
+    
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(CalcError::OutOfRange(Message::new("error.amount_negative", &[&input.trim()])));
+    }
+    
+    Ok(amount)
+}
+
+// Parse a whole number between `min` and `max` inclusive, such as a day or month
+pub fn parse_in_range(input: &str, min: u32, max: u32) -> Result<u32, CalcError> {
+    let value = input
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| CalcError::Parse(Message::new("error.not_a_whole_number", &[&input.trim(), &min, &max])))?;
+    
+    if !(min..=max).contains(&value) {
+        return Err(CalcError::OutOfRange(Message::new("error.outside_range", &[&value, &min, &max])));
+    }
+    
+    Ok(value)
+}
+
+// Share of a month worked when starting on `start_day` (1 = full month)
+pub fn proration_factor(start_day: u32, days_in_month: u32) -> f64 {
+    if days_in_month == 0 {
+        return 1.0;
+    }
+    let start_day = start_day.clamp(1, days_in_month);
+    
+    (days_in_month - start_day + 1) as f64 / days_in_month as f64
+}
+
+// Number of calendar days in the month of `date`
+pub fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 { (date.year() + 1, 1) } else { (date.year(), date.month() + 1) };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first_of_next| first_of_next.pred_opt())
+        .map(|last_day| last_day.day())
+        .unwrap_or(30)
+}
+
+// PPh 21 for a partial month: the full-month tax is prorated, while PTKP/PKP
+// stay annualized on the full monthly salary
+pub fn calculate_pph21_partial_month(params: &PPh21Params, proration_factor: f64, method: Pph21Method, config: &TaxConfig) -> f64 {
+    let (_, monthly_tax, _, _, _) = calculate_pph21_with_method(params, method, config);
+    
+    round_rupiah(monthly_tax * proration_factor, config.rounding)
+}
+
+// TER (Tarif Efektif Rata-rata) category for the monthly method, PP 58/2023
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TerCategory {
+    A, // TK/0, TK/1, K/0
+    B, // TK/2, TK/3, K/1, K/2
+    C, // K/3
+}
+
+impl TerCategory {
+    // Category for a PTKP status; dependents beyond the PTKP maximum are ignored
+    pub fn from_status(is_married: bool, num_dependents: u32) -> TerCategory {
+        match (is_married, num_dependents.min(MAX_PTKP_DEPENDENTS)) {
+            (false, 0..=1) | (true, 0) => TerCategory::A,
+            (false, _) | (true, 1..=2) => TerCategory::B,
+            (true, _) => TerCategory::C,
+        }
+    }
+    
+    pub fn label(&self) -> &'static str {
+        match self {
+            TerCategory::A => "A",
+            TerCategory::B => "B",
+            TerCategory::C => "C",
+        }
+    }
+    
+    // Monthly TER brackets for this category
+    pub fn table(&self) -> &'static [(f64, f64)] {
+        match self {
+            TerCategory::A => TER_TABLE_A,
+            TerCategory::B => TER_TABLE_B,
+            TerCategory::C => TER_TABLE_C,
+        }
+    }
+}
+
+// Monthly TER brackets as (upper bound of gross income per month, rate in %)
+pub const TER_TABLE_A: &[(f64, f64)] = &[
+    (5_400_000.0, 0.0),
+    (5_650_000.0, 0.25),
+    (5_950_000.0, 0.5),
+    (6_300_000.0, 0.75),
+    (6_750_000.0, 1.0),
+    (7_500_000.0, 1.25),
+    (8_550_000.0, 1.5),
+    (9_650_000.0, 1.75),
+    (10_050_000.0, 2.0),
+    (10_350_000.0, 2.25),
+    (10_700_000.0, 2.5),
+    (11_050_000.0, 3.0),
+    (11_600_000.0, 3.5),
+    (12_500_000.0, 4.0),
+    (13_750_000.0, 5.0),
+    (15_100_000.0, 6.0),
+    (16_950_000.0, 7.0),
+    (19_750_000.0, 8.0),
+    (24_150_000.0, 9.0),
+    (26_450_000.0, 10.0),
+    (28_000_000.0, 11.0),
+    (30_050_000.0, 12.0),
+    (32_400_000.0, 13.0),
+    (35_400_000.0, 14.0),
+    (39_100_000.0, 15.0),
+    (43_850_000.0, 16.0),
+    (47_800_000.0, 17.0),
+    (51_400_000.0, 18.0),
+    (56_300_000.0, 19.0),
+    (62_200_000.0, 20.0),
+    (68_600_000.0, 21.0),
+    (77_500_000.0, 22.0),
+    (89_000_000.0, 23.0),
+    (103_000_000.0, 24.0),
+    (125_000_000.0, 25.0),
+    (157_000_000.0, 26.0),
+    (206_000_000.0, 27.0),
+    (337_000_000.0, 28.0),
+    (454_000_000.0, 29.0),
+    (550_000_000.0, 30.0),
+    (695_000_000.0, 31.0),
+    (910_000_000.0, 32.0),
+    (1_400_000_000.0, 33.0),
+    (f64::MAX, 34.0),
+];
+
+pub const TER_TABLE_B: &[(f64, f64)] = &[
+    (6_200_000.0, 0.0),
+    (6_500_000.0, 0.25),
+    (6_850_000.0, 0.5),
+    (7_300_000.0, 0.75),
+    (9_200_000.0, 1.0),
+    (10_750_000.0, 1.5),
+    (11_250_000.0, 2.0),
+    (11_600_000.0, 2.5),
+    (12_600_000.0, 3.0),
+    (13_600_000.0, 4.0),
+    (14_950_000.0, 5.0),
+    (16_400_000.0, 6.0),
+    (18_450_000.0, 7.0),
+    (21_850_000.0, 8.0),
+    (26_000_000.0, 9.0),
+    (27_700_000.0, 10.0),
+    (29_350_000.0, 11.0),
+    (31_450_000.0, 12.0),
+    (33_950_000.0, 13.0),
+    (37_100_000.0, 14.0),
+    (41_100_000.0, 15.0),
+    (45_800_000.0, 16.0),
+    (49_500_000.0, 17.0),
+    (53_800_000.0, 18.0),
+    (58_500_000.0, 19.0),
+    (64_000_000.0, 20.0),
+    (71_000_000.0, 21.0),
+    (80_000_000.0, 22.0),
+    (93_000_000.0, 23.0),
+    (109_000_000.0, 24.0),
+    (129_000_000.0, 25.0),
+    (163_000_000.0, 26.0),
+    (211_000_000.0, 27.0),
+    (374_000_000.0, 28.0),
+    (459_000_000.0, 29.0),
+    (555_000_000.0, 30.0),
+    (704_000_000.0, 31.0),
+    (957_000_000.0, 32.0),
+    (1_405_000_000.0, 33.0),
+    (f64::MAX, 34.0),
+];
+
+pub const TER_TABLE_C: &[(f64, f64)] = &[
+    (6_600_000.0, 0.0),
+    (6_950_000.0, 0.25),
+    (7_350_000.0, 0.5),
+    (7_800_000.0, 0.75),
+    (8_850_000.0, 1.0),
+    (9_800_000.0, 1.25),
+    (10_950_000.0, 1.5),
+    (11_200_000.0, 1.75),
+    (12_050_000.0, 2.0),
+    (12_950_000.0, 3.0),
+    (14_150_000.0, 4.0),
+    (15_550_000.0, 5.0),
+    (17_050_000.0, 6.0),
+    (19_500_000.0, 7.0),
+    (22_700_000.0, 8.0),
+    (26_600_000.0, 9.0),
+    (28_100_000.0, 10.0),
+    (30_100_000.0, 11.0),
+    (32_600_000.0, 12.0),
+    (35_400_000.0, 13.0),
+    (38_900_000.0, 14.0),
+    (43_000_000.0, 15.0),
+    (47_400_000.0, 16.0),
+    (51_200_000.0, 17.0),
+    (55_800_000.0, 18.0),
+    (60_400_000.0, 19.0),
+    (66_700_000.0, 20.0),
+    (74_500_000.0, 21.0),
+    (83_200_000.0, 22.0),
+    (95_600_000.0, 23.0),
+    (110_000_000.0, 24.0),
+    (134_000_000.0, 25.0),
+    (169_000_000.0, 26.0),
+    (221_000_000.0, 27.0),
+    (390_000_000.0, 28.0),
+    (463_000_000.0, 29.0),
+    (561_000_000.0, 30.0),
+    (709_000_000.0, 31.0),
+    (965_000_000.0, 32.0),
+    (1_419_000_000.0, 33.0),
+    (f64::MAX, 34.0),
+];
+
+pub fn get_ter_tables() -> HashMap<&'static str, &'static [(f64, f64)]> {
+    let mut ter_tables = HashMap::new();
+    ter_tables.insert("A", TER_TABLE_A);
+    ter_tables.insert("B", TER_TABLE_B);
+    ter_tables.insert("C", TER_TABLE_C);
+    ter_tables
+}
+
+// Effective monthly rate (in %) for a gross monthly income under a TER category
+pub fn ter_rate(ter_category: TerCategory, monthly_gross: f64) -> f64 {
+    ter_category
+        .table()
+        .iter()
+        .find(|(upper_bound, _)| monthly_gross <= *upper_bound)
+        .map(|(_, rate)| *rate)
+        .unwrap_or(0.0)
+}
+
+// Monthly PPh 21 under the 2024 TER method: TER rate x monthly gross income
+pub fn calculate_pph21_ter(params: &PPh21Params, ter_category: TerCategory, config: &TaxConfig) -> f64 {
+    surcharge_rounded(params.monthly_gross() * ter_rate(ter_category, params.monthly_gross()) / 100.0, params.has_npwp, config.rounding)
+}
+
+// December PPh 21 under TER for a salary of `monthly_gross` (with
+// `params.overtime` on top) paid in every month worked: the year's
+// progressive tax less the TER withheld in the other months. Negative when
+// TER over-withheld, to be returned with December pay
+pub fn ter_year_end_adjustment(monthly_gross: f64, params: &PPh21Params, config: &TaxConfig) -> f64 {
+    let params = PPh21Params { gross_income: monthly_gross, ..*params };
+    let ter = calculate_pph21_ter(&params, TerCategory::from_status(params.is_married, params.num_dependents), config);
+    let ytd_withheld = from_decimal(to_decimal(ter) * Decimal::from(params.months_worked - 1));
+    
+    calculate_pph21_december(ytd_withheld, &params, config)
+}
+
+// PPh 21 an employer deposits for `month` (1-12) of the tax year, for a
+// roster paid the same every month: each employee's TER withholding from
+// January to November, and in December the year's progressive tax less the
+// TER withheld over the employee's other months worked
+pub fn pph21_period_total(employees: &[PPh21Params], month: u32, config: &TaxConfig) -> Result<f64, CalcError> {
+    if !(1..=12).contains(&month) {
+        return Err(CalcError::OutOfRange(Message::new("error.month_range", &[&month])));
+    }
+    
+    let total: Decimal = employees
+        .iter()
+        .map(|params| {
+            if month < 12 {
+                to_decimal(calculate_pph21_ter(params, TerCategory::from_status(params.is_married, params.num_dependents), config))
+            } else {
+                to_decimal(ter_year_end_adjustment(params.gross_income, params, config))
+            }
+        })
+        .sum();
+    
+    Ok(from_decimal(total))
+}
+
+// Daily-wage earners (pegawai tidak tetap) owe nothing up to these wages
+pub const DAILY_WAGE_THRESHOLD: f64 = 450_000.0;
+pub const MONTHLY_WAGE_THRESHOLD: f64 = 4_500_000.0;
+
+// Once the month's wages pass the monthly threshold, the daily PTKP
+// (Rp 54,000,000 / 360) is deducted instead of the daily threshold
+pub const DAILY_PTKP: f64 = 150_000.0;
+pub const DAILY_WAGE_RATE: f64 = 0.05;
+
+// PPh 21 withheld on one day's wage; `cumulative_month` is the total wage
+// earned so far this month, including this day
+pub fn calculate_pph21_daily(daily_wage: f64, cumulative_month: f64, config: &TaxConfig) -> f64 {
+    let exempt = if cumulative_month > MONTHLY_WAGE_THRESHOLD {
+        DAILY_PTKP
+    } else {
+        DAILY_WAGE_THRESHOLD
+    };
+    
+    from_decimal(round_decimal((to_decimal(daily_wage) - to_decimal(exempt)).max(Decimal::ZERO) * to_decimal(DAILY_WAGE_RATE), config.rounding))
+}
+
+// PPh 21 withheld on one honorarium payment to a commissioner or director who
+// is not an employee: the Pasal 17 brackets apply to the year's cumulative
+// gross with no biaya jabatan or PTKP, so each payment is taxed at the
+// marginal rate its cumulative total has reached. `cumulative_gross` is the
+// total paid so far this year, including this payment
+pub fn calculate_pph21_non_employee(payment: f64, cumulative_gross: f64, config: &TaxConfig) -> f64 {
+    let cumulative = to_decimal(cumulative_gross);
+    let prior = (cumulative - to_decimal(payment)).max(Decimal::ZERO);
+    let tax = decimal_income_tax(cumulative, &config.brackets) - decimal_income_tax(prior, &config.brackets);
+    
+    from_decimal(round_decimal(tax, config.rounding))
+}
+
+// PPh 21 for a director who is also an employee: the salary is withheld as
+// regular employment pay and the director's fees as non-employee honoraria,
+// each fee in the order received
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct DirectorWithholding {
+    // Progressive PPh 21 on the salary for the year
+    pub salary_tax: f64,
+    pub fee_taxes: Vec<f64>,
+}
+
+impl DirectorWithholding {
+    pub fn fee_tax(&self) -> f64 {
+        from_decimal(self.fee_taxes.iter().map(|tax| to_decimal(*tax)).sum())
+    }
+    
+    // PPh 21 withheld from the director for the year, salary and fees together
+    pub fn total(&self) -> f64 {
+        from_decimal(to_decimal(self.salary_tax) + to_decimal(self.fee_tax()))
+    }
+}
+
+// Withholding for a director paid `params` as salary and `fees` as director's
+// fees during the year; the fees never share the salary's biaya jabatan or PTKP
+pub fn director_withholding(params: &PPh21Params, fees: &[f64], config: &TaxConfig) -> DirectorWithholding {
+    let (salary_tax, _, _, _, _) = calculate_pph21_progressive(params, config);
+    let mut cumulative = Decimal::ZERO;
+    let fee_taxes = fees
+        .iter()
+        .map(|&fee| {
+            cumulative += to_decimal(fee);
+            calculate_pph21_non_employee(fee, from_decimal(cumulative), config)
+        })
+        .collect();
+    
+    DirectorWithholding { salary_tax, fee_taxes }
+}
+
+// Employee BPJS contribution rates
+pub const BPJS_KESEHATAN_RATE: f64 = 0.01;
+pub const BPJS_JHT_RATE: f64 = 0.02;
+pub const BPJS_JP_RATE: f64 = 0.01;
+
+// Monthly salary ceiling for BPJS Kesehatan contributions, unchanged since
+// Perpres 64/2020
+pub const BPJS_KESEHATAN_SALARY_CAP: f64 = 12_000_000.0;
+
+// Monthly salary ceiling for Jaminan Pensiun contributions in a supported tax
+// year; BPJS Ketenagakerjaan raises it every March
+pub fn bpjs_jp_salary_cap_for_year(year: u16) -> Result<f64, Message> {
+    check_tax_year(year)?;
+    
+    match year {
+        2022 => Ok(9_077_600.0),
+        2023 => Ok(9_559_600.0),
+        2024 => Ok(10_042_300.0),
+        _ => unreachable!("check_tax_year accepts only SUPPORTED_TAX_YEARS"),
+    }
+}
+
+// Employee share of the monthly BPJS contributions
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct BpjsBreakdown {
+    pub kesehatan: f64,
+    pub jht: f64,
+    pub jp: f64,
+    pub total: f64,
+}
+
+pub fn calculate_bpjs(gross: f64, config: &TaxConfig) -> BpjsBreakdown {
+    let kesehatan = round_rupiah(gross.min(config.bpjs_kesehatan_salary_cap) * BPJS_KESEHATAN_RATE, config.rounding);
+    let jht = round_rupiah(gross * BPJS_JHT_RATE, config.rounding);
+    let jp = round_rupiah(gross.min(config.bpjs_jp_salary_cap) * BPJS_JP_RATE, config.rounding);
+    
+    BpjsBreakdown {
+        kesehatan,
+        jht,
+        jp,
+        total: kesehatan + jht + jp,
+    }
+}
+
+// Employer BPJS contribution rates, paid on top of gross pay
+pub const BPJS_JKM_RATE: f64 = 0.003;
+pub const BPJS_JHT_EMPLOYER_RATE: f64 = 0.037;
+pub const BPJS_JP_EMPLOYER_RATE: f64 = 0.02;
+pub const BPJS_KESEHATAN_EMPLOYER_RATE: f64 = 0.04;
+
+// Workplace risk class for the Jaminan Kecelakaan Kerja (JKK) rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum JkkClass {
+    // Offices and most services
+    #[default]
+    VeryLow,
+    Low,
+    Medium,
+    High,
+    // Mining, offshore and similar work
+    VeryHigh,
+}
+
+impl JkkClass {
+    // Parse a risk class number from 1 (sangat rendah) to 5 (sangat tinggi)
+    pub fn parse(input: &str) -> Result<JkkClass, String> {
+        match input.trim() {
+            "1" => Ok(JkkClass::VeryLow),
+            "2" => Ok(JkkClass::Low),
+            "3" => Ok(JkkClass::Medium),
+            "4" => Ok(JkkClass::High),
+            "5" => Ok(JkkClass::VeryHigh),
+            other => Err(format!("Kelas risiko JKK '{}' tidak valid (gunakan 1 sampai 5)", other)),
+        }
+    }
+    
+    // Share of monthly gross pay
+    pub fn rate(self) -> f64 {
+        match self {
+            JkkClass::VeryLow => 0.0024,
+            JkkClass::Low => 0.0054,
+            JkkClass::Medium => 0.0089,
+            JkkClass::High => 0.0127,
+            JkkClass::VeryHigh => 0.0174,
+        }
+    }
+}
+
+// Employer share of the monthly BPJS contributions, on top of gross pay
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct EmployerCost {
+    pub risk_class: JkkClass,
+    pub jkk: f64,
+    pub jkm: f64,
+    pub jht: f64,
+    pub jp: f64,
+    pub kesehatan: f64,
+    pub total: f64,
+}
+
+// Employer BPJS contributions on a monthly gross, with the same Kesehatan
+// and JP salary ceilings as the employee share
+pub fn employer_contributions(gross: f64, risk_class: JkkClass, config: &TaxConfig) -> EmployerCost {
+    let contribution = |base: f64, rate: f64| round_rupiah(base * rate, config.rounding);
+    let jkk = contribution(gross, risk_class.rate());
+    let jkm = contribution(gross, BPJS_JKM_RATE);
+    let jht = contribution(gross, BPJS_JHT_EMPLOYER_RATE);
+    let jp = contribution(gross.min(config.bpjs_jp_salary_cap), BPJS_JP_EMPLOYER_RATE);
+    let kesehatan = contribution(gross.min(config.bpjs_kesehatan_salary_cap), BPJS_KESEHATAN_EMPLOYER_RATE);
+    
+    EmployerCost {
+        risk_class,
+        jkk,
+        jkm,
+        jht,
+        jp,
+        kesehatan,
+        total: jkk + jkm + jht + jp + kesehatan,
+    }
+}
+
+// Every line of the monthly pay waterfall; biaya jabatan, PTKP and PKP are
+// annual figures, as in the PPh 21 worksheet. `company_cost` is gross pay
+// plus the employer's BPJS share
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct PayrollReport {
+    pub gross_monthly: f64,
+    pub gross_annual: f64,
+    pub biaya_jabatan: f64,
+    pub bpjs: BpjsBreakdown,
+    pub ptkp: f64,
+    pub pkp: f64,
+    pub annual_tax: f64,
+    pub monthly_tax: f64,
+    // Named amounts taken from pay after tax, such as a salary advance or a
+    // loan repayment; net_monthly is what is left after them
+    #[serde(default)]
+    pub post_tax_deductions: Vec<(String, f64)>,
+    pub net_monthly: f64,
+    pub employer: EmployerCost,
+    pub company_cost: f64,
+}
+
+impl PayrollReport {
+    // The same report with the tax and take-home pay rounded by round_to_multiple
+    pub fn rounded_to(&self, multiple: u32) -> PayrollReport {
+        PayrollReport {
+            annual_tax: round_to_multiple(self.annual_tax, multiple),
+            monthly_tax: round_to_multiple(self.monthly_tax, multiple),
+            net_monthly: round_to_multiple(self.net_monthly, multiple),
+            post_tax_deductions: self.post_tax_deductions.clone(),
+            ..*self
+        }
+    }
+    
+    // The same report with `deductions` taken from the take-home pay after
+    // tax; net_monthly stops at Rp 0 when they are more than the pay
+    pub fn with_post_tax_deductions(self, deductions: Vec<(String, f64)>) -> PayrollReport {
+        let total: Decimal = deductions.iter().map(|(_, amount)| to_decimal(*amount)).sum();
+        PayrollReport {
+            net_monthly: from_decimal((to_decimal(self.net_before_deductions()) - total).max(Decimal::ZERO)),
+            post_tax_deductions: deductions,
+            ..self
+        }
+    }
+    
+    // Take-home pay before the post-tax deductions
+    pub fn net_before_deductions(&self) -> f64 {
+        self.gross_monthly - self.bpjs.total - self.monthly_tax
+    }
+    
+    pub fn post_tax_deductions_total(&self) -> f64 {
+        from_decimal(self.post_tax_deductions.iter().map(|(_, amount)| to_decimal(*amount)).sum())
+    }
+    
+    // True when the post-tax deductions are more than the pay they come out of
+    pub fn deductions_exceed_net(&self) -> bool {
+        self.post_tax_deductions_total() > self.net_before_deductions()
+    }
+}
+
+// Parse a post-tax deduction written as NAME=AMOUNT, e.g. Kasbon=500000
+pub fn parse_post_tax_deduction(input: &str) -> Result<(String, f64), CalcError> {
+    let invalid = || CalcError::Parse(Message::new("error.invalid_deduction", &[&input]));
+    let (name, amount) = input.split_once('=').ok_or_else(invalid)?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(invalid());
+    }
+    
+    Ok((name.to_string(), parse_amount(amount)?))
+}
+
+// Gross to take-home pay: PPh 21 under the progressive method, with the
+// employee's BPJS share and the monthly tax withheld from gross pay, and the
+// employer's BPJS share at the lowest JKK risk class
+pub fn full_payroll(params: &PPh21Params, config: &TaxConfig) -> PayrollReport {
+    full_payroll_with_risk_class(params, JkkClass::default(), config)
+}
+
+// full_payroll with the employer's JKK at `risk_class`
+pub fn full_payroll_with_risk_class(params: &PPh21Params, risk_class: JkkClass, config: &TaxConfig) -> PayrollReport {
+    let (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) = calculate_pph21_progressive(params, config);
+    let bpjs = calculate_bpjs(params.gross_income, config);
+    let net_monthly = params.monthly_gross() - bpjs.total - monthly_tax;
+    let employer = employer_contributions(params.gross_income, risk_class, config);
+    
+    PayrollReport {
+        gross_monthly: params.monthly_gross(),
+        gross_annual: params.annual_gross(),
+        biaya_jabatan,
+        bpjs,
+        ptkp,
+        pkp,
+        annual_tax,
+        monthly_tax,
+        post_tax_deductions: Vec::new(),
+        net_monthly,
+        employer,
+        company_cost: params.monthly_gross() + employer.total,
+    }
+}
+
+// full_payroll_with_risk_class for a gross that the employee's BPJS share has
+// already come out of: no BPJS is deducted again, so take-home pay is the
+// gross less PPh 21
+pub fn full_payroll_net_of_bpjs(params: &PPh21Params, risk_class: JkkClass, config: &TaxConfig) -> PayrollReport {
+    let report = full_payroll_with_risk_class(params, risk_class, config);
+    
+    PayrollReport {
+        bpjs: BpjsBreakdown { kesehatan: 0.0, jht: 0.0, jp: 0.0, total: 0.0 },
+        net_monthly: report.gross_monthly - report.monthly_tax,
+        ..report
+    }
+}
+
+// Largest difference (in rupiah) still treated as a match for a self-computed figure
+pub const SELF_CHECK_TOLERANCE: f64 = 1.0;
+
+// Comparison of a taxpayer's own monthly PPh 21 figure against the calculator
+#[derive(Debug)]
+pub struct SelfComputedCheck {
+    pub claimed: f64,
+    pub expected: f64,
+    pub difference: f64,
+    pub matches: bool,
+}
+
+pub fn check_self_computed(params: &PPh21Params, claimed_monthly_tax: f64, tolerance: f64, config: &TaxConfig) -> SelfComputedCheck {
+    let (_, expected, _, _, _) = calculate_pph21_with_method(params, Pph21Method::Progressive, config);
+    let difference = claimed_monthly_tax - expected;
+    
+    SelfComputedCheck {
+        claimed: claimed_monthly_tax,
+        expected,
+        difference,
+        matches: difference.abs() <= tolerance,
+    }
+}
+
+// Tax bracket structure; in a config file the top bracket may omit `upper_bound`
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct TaxBracket {
+    pub lower_bound: f64,
+    #[serde(default = "unbounded")]
+    pub upper_bound: f64,
+    pub rate: f64,
+}
+
+fn unbounded() -> f64 {
+    f64::MAX
+}
+
+// Columns of a bracket CSV, in order
+pub const BRACKETS_CSV_HEADER: [&str; 3] = ["lower_bound", "upper_bound", "rate"];
+
+// Brackets from a spreadsheet export with `lower_bound,upper_bound,rate`
+// columns; the rate is a fraction as in the config file, and the top
+// bracket's upper_bound may be `max` or blank. The brackets must start at
+// zero and follow on from each other
+pub fn load_brackets_csv(path: &std::path::Path) -> Result<Vec<TaxBracket>, CalcError> {
+    let read_error = |err: csv::Error| CalcError::File(Message::new("error.brackets_read", &[&path.display(), &err]));
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_path(path).map_err(read_error)?;
+    
+    let headers = reader.headers().map_err(read_error)?;
+    if headers.iter().ne(BRACKETS_CSV_HEADER) {
+        return Err(CalcError::Parse(Message::new(
+            "error.brackets_header",
+            &[&path.display(), &BRACKETS_CSV_HEADER.join(","), &headers.iter().collect::<Vec<_>>().join(",")],
+        )));
+    }
+    
+    let mut brackets = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(read_error)?;
+        let line = record.position().map(|position| position.line()).unwrap_or(0);
+        let invalid = |column: &str, value: &str| CalcError::Parse(Message::new("error.brackets_value", &[&line, &column, &value]));
+        
+        let lower_bound = parse_rupiah(&record[0]).map_err(|_| invalid("lower_bound", &record[0]))?;
+        let upper_bound = match &record[1] {
+            "" => f64::MAX,
+            max if max.eq_ignore_ascii_case("max") => f64::MAX,
+            upper => parse_rupiah(upper).map_err(|_| invalid("upper_bound", upper))?,
+        };
+        let rate = record[2]
+            .replace(',', ".")
+            .parse::<f64>()
+            .ok()
+            .filter(|rate| (0.0..=1.0).contains(rate))
+            .ok_or_else(|| invalid("rate", &record[2]))?;
+        brackets.push(TaxBracket { lower_bound, upper_bound, rate });
+    }
+    
+    if !check_bracket_contiguity(&brackets) {
+        return Err(CalcError::OutOfRange(Message::new("error.brackets_contiguity", &[&path.display()])));
+    }
+    
+    Ok(brackets)
+}
+
+// PPh 21 Tax brackets for the latest tax year
+pub fn get_tax_brackets() -> Vec<TaxBracket> {
+    tax_brackets_for_year(TAX_TABLES_YEAR as u16).expect("the latest tax year is supported")
+}
+
+// Pasal 17 brackets for a supported tax year. UU HPP (7/2021) applies from
+// 2022: a wider 5% bracket up to Rp 60,000,000 and a 35% rate above Rp 5 billion
+pub fn tax_brackets_for_year(year: u16) -> Result<Vec<TaxBracket>, Message> {
+    check_tax_year(year)?;
+    
+    match year {
+        2022..=2024 => Ok(vec![
+            TaxBracket { lower_bound: 0.0, upper_bound: 60_000_000.0, rate: 0.05 },
+            TaxBracket { lower_bound: 60_000_000.0, upper_bound: 250_000_000.0, rate: 0.15 },
+            TaxBracket { lower_bound: 250_000_000.0, upper_bound: 500_000_000.0, rate: 0.25 },
+            TaxBracket { lower_bound: 500_000_000.0, upper_bound: 5_000_000_000.0, rate: 0.30 },
+            TaxBracket { lower_bound: 5_000_000_000.0, upper_bound: f64::MAX, rate: 0.35 },
+        ]),
+        _ => unreachable!("check_tax_year accepts only SUPPORTED_TAX_YEARS"),
+    }
+}
+
+impl TaxBracket {
+    // Whether income falls in this bracket, i.e. its next rupiah is taxed at this rate
+    pub fn contains(&self, income: f64) -> bool {
+        income >= self.lower_bound && income < self.upper_bound
+    }
+    
+    // Size of the bracket; the open-ended top bracket is infinitely wide
+    pub fn width(&self) -> f64 {
+        if self.upper_bound == f64::MAX {
+            f64::INFINITY
+        } else {
+            self.upper_bound - self.lower_bound
+        }
+    }
+    
+    // Whether any part of income is taxed in this bracket; brackets are
+    // ordered, so the ones that apply are a prefix of the list
+    pub fn applies_to(&self, income: f64) -> bool {
+        income > self.lower_bound
+    }
+    
+    // Portion of income falling inside this bracket
+    pub fn taxable_in_bracket(&self, income: f64) -> f64 {
+        from_decimal(self.decimal_taxable(to_decimal(income)))
+    }
+    
+    // Tax on the portion of income falling inside this bracket
+    pub fn tax_in_bracket(&self, income: f64) -> f64 {
+        from_decimal(self.decimal_tax(to_decimal(income)))
+    }
+    
+    fn decimal_taxable(&self, income: Decimal) -> Decimal {
+        // f64::MAX does not fit in a Decimal; an open-ended bracket has no ceiling
+        let taxed_up_to = if self.upper_bound == f64::MAX { income } else { income.min(to_decimal(self.upper_bound)) };
+        (taxed_up_to - to_decimal(self.lower_bound)).max(Decimal::ZERO)
+    }
+    
+    fn decimal_tax(&self, income: Decimal) -> Decimal {
+        self.decimal_taxable(income) * to_decimal(self.rate)
+    }
+}
+
+// Function to calculate income tax based on tax brackets
+pub fn calculate_income_tax(income: f64, tax_brackets: &[TaxBracket]) -> f64 {
+    from_decimal(decimal_income_tax(to_decimal(income), tax_brackets))
+}
+
+fn decimal_income_tax(income: Decimal, tax_brackets: &[TaxBracket]) -> Decimal {
+    tax_brackets
+        .iter()
+        .take_while(|bracket| to_decimal(bracket.lower_bound) < income)
+        .map(|bracket| bracket.decimal_tax(income))
+        .sum()
+}
+
+// The part of an income taxed in one bracket and the tax on it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BracketContribution {
+    pub rate: f64,
+    pub taxable: f64,
+    pub tax: f64,
+}
+
+// Per-bracket split of calculate_income_tax, for the brackets the income reaches;
+// the contributions' tax adds up to the total
+pub fn income_tax_breakdown(income: f64, tax_brackets: &[TaxBracket]) -> Vec<BracketContribution> {
+    tax_brackets
+        .iter()
+        .take_while(|bracket| bracket.applies_to(income))
+        .map(|bracket| BracketContribution {
+            rate: bracket.rate,
+            taxable: bracket.taxable_in_bracket(income),
+            tax: bracket.tax_in_bracket(income),
+        })
+        .collect()
+}
+
+// One "rate x taxable = tax" line per contribution
+pub fn render_income_tax_breakdown(contributions: &[BracketContribution]) -> String {
+    let mut output = String::new();
+    for contribution in contributions {
+        writeln!(output, "{:>3}% x {:>20} = {:>20}", 
+            contribution.rate * 100.0,
+            format_rupiah(contribution.taxable),
+            format_rupiah(contribution.tax)).unwrap();
+    }
+    
+    output
+}
+
+// PKP that leaves `net` after income tax, the inverse of calculate_income_tax.
+// Net income rises by (1 - rate) per rupiah inside each bracket, so find the
+// bracket whose net range holds `net` and solve linearly within it; the
+// result is rounded to the rupiah under `mode`
+pub fn income_tax_from_net(net: f64, tax_brackets: &[TaxBracket], mode: RoundingMode) -> f64 {
+    let net = to_decimal(net).max(Decimal::ZERO);
+    let net_at = |income: Decimal| income - decimal_income_tax(income, tax_brackets);
+    
+    for bracket in tax_brackets {
+        let reaches_net = bracket.upper_bound == f64::MAX || net <= net_at(to_decimal(bracket.upper_bound));
+        if reaches_net {
+            let lower = to_decimal(bracket.lower_bound);
+            let kept = Decimal::ONE - to_decimal(bracket.rate);
+            let above_lower = (net - net_at(lower)).checked_div(kept).unwrap_or(Decimal::ZERO);
+            return from_decimal(round_decimal(lower + above_lower, mode));
+        }
+    }
+    
+    // Past every bracket the income is untaxed
+    let last_upper = tax_brackets.last().map(|bracket| to_decimal(bracket.upper_bound)).unwrap_or(Decimal::ZERO);
+    from_decimal(round_decimal(net + decimal_income_tax(last_upper, tax_brackets), mode))
+}
+
+// Income tax with optional PKP floor-to-thousand rounding, matching the PPh 21 path
+pub fn calculate_income_tax_with_rounding(income: f64, tax_brackets: &[TaxBracket], round_pkp: bool) -> f64 {
+    let pkp = if round_pkp { round_down_pkp(income) } else { income };
+    calculate_income_tax(pkp, tax_brackets)
+}
+
+// Function to calculate VAT
+pub fn calculate_vat(amount: f64, vat_rate: f64) -> f64 {
+    from_decimal(to_decimal(amount) * to_decimal(vat_rate) / Decimal::from(100))
+}
+
+// Parse a percentage accepting both "11.5" and the Indonesian "11,5" (and an
+// optional trailing "%"); only empty input falls back to the default
+pub fn parse_percent(input: &str, default: f64) -> Result<f64, CalcError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(default);
+    }
+    
+    input
+        .trim_end_matches('%')
+        .trim()
+        .replace(',', ".")
+        .parse::<f64>()
+        .map_err(|_| CalcError::Parse(Message::new("error.invalid_percent", &[&input])))
+}
+
+// Highest PPN percentage accepted as input
+pub const MAX_VAT_RATE: f64 = 100.0;
+
+// Parse a PPN percentage and check it lies within 0-100%; empty input uses `default`
+pub fn parse_vat_rate(input: &str, default: f64) -> Result<f64, CalcError> {
+    let rate = parse_percent(input, default)?;
+    if !(0.0..=MAX_VAT_RATE).contains(&rate) {
+        return Err(CalcError::OutOfRange(Message::new("error.vat_rate_range", &[&rate, &MAX_VAT_RATE])));
+    }
+    
+    Ok(rate)
+}
+
+// A rate change taking effect at the start of a month (1 = January)
+#[derive(Debug)]
+pub struct RateChange {
+    pub effective_month: u32,
+    pub new_rate: f64,
+}
+
+// VAT on an annual amount spread evenly over the months, blending the old
+// rate before the change and the new rate from the effective month onwards
+pub fn calculate_vat_with_rate_change(annual_amount: f64, old_rate: f64, change: &RateChange) -> f64 {
+    let months_before = change.effective_month.clamp(1, 13) - 1;
+    let amount_before = annual_amount * months_before as f64 / 12.0;
+    
+    calculate_vat(amount_before, old_rate) + calculate_vat(annual_amount - amount_before, change.new_rate)
+}
+
+// Income tax on an annual income when the brackets change at the start of
+// `effective_month` (1 = January). Pasal 17 rates apply to the whole year's
+// income, so the tax under each table is weighted by the months it applies,
+// the same split as calculate_vat_with_rate_change
+pub fn calculate_income_tax_with_bracket_change(
+    annual_income: f64,
+    old_brackets: &[TaxBracket],
+    new_brackets: &[TaxBracket],
+    effective_month: u32,
+) -> f64 {
+    let income = to_decimal(annual_income);
+    let months_before = Decimal::from(effective_month.clamp(1, 13) - 1);
+    let months_after = Decimal::from(12) - months_before;
+    
+    from_decimal(
+        (decimal_income_tax(income, old_brackets) * months_before + decimal_income_tax(income, new_brackets) * months_after)
+            / Decimal::from(12),
+    )
+}
+
+// Split an estimated annual tax into equal whole-rupiah installments; the
+// remainder goes into the final period so the installments sum exactly
+pub fn installments(annual_estimate: f64, periods: u32) -> Vec<f64> {
+    if periods == 0 {
+        return Vec::new();
+    }
+    
+    let regular = (annual_estimate / periods as f64).floor();
+    let mut result = vec![regular; periods as usize];
+    result[periods as usize - 1] = annual_estimate - regular * (periods - 1) as f64;
+    
+    result
+}
+
+// A single table cell; rupiah amounts are formatted and right-aligned
+#[derive(Debug, Clone)]
+pub enum TableCell {
+    Text(String),
+    Rupiah(f64),
+}
+
+impl TableCell {
+    pub fn render(&self) -> String {
+        match self {
+            TableCell::Text(text) => text.clone(),
+            TableCell::Rupiah(amount) => format_rupiah(*amount),
+        }
+    }
+}
+
+// Render rows as a text table with columns aligned to the widest cell
+pub fn render_table(headers: &[&str], rows: &[Vec<TableCell>]) -> String {
+    let rendered: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(TableCell::render).collect())
+        .collect();
+    
+    let widths: Vec<usize> = (0..headers.len())
+        .map(|column| {
+            rendered
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .chain(std::iter::once(headers[column].chars().count()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    
+    let header_line: Vec<String> = headers
+        .iter()
+        .zip(&widths)
+        .map(|(header, width)| format!("{:<width$}", header, width = width))
+        .collect();
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    
+    let mut output = format!("{}\n{}\n", header_line.join(" | "), separator.join("-+-"));
+    for (row, cells) in rows.iter().zip(&rendered) {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(row)
+            .zip(&widths)
+            .map(|((text, cell), width)| match cell {
+                TableCell::Rupiah(_) => format!("{:>width$}", text, width = width),
+                TableCell::Text(_) => format!("{:<width$}", text, width = width),
+            })
+            .collect();
+        output.push_str(&line.join(" | "));
+        output.push('\n');
+    }
+    
+    output
+}
+
+// VAT treatment of a supply; zero-rated and exempt both carry no VAT, but
+// only zero-rated supplies keep input VAT creditable
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum VatStatus {
+    Standard(f64),
+    ZeroRated,
+    Exempt,
+}
+
+impl VatStatus {
+    pub fn label(&self, lang: Lang) -> String {
+        match self {
+            VatStatus::Standard(rate) => format!("{}%", rate),
+            VatStatus::ZeroRated => t("vat.zero_rated", lang).to_string(),
+            VatStatus::Exempt => t("vat.exempt", lang).to_string(),
+        }
+    }
+}
+
+// VAT calculation result, keeping the status it was computed under
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub struct VatResult {
+    pub status: VatStatus,
+    pub base: f64,
+    pub vat: f64,
+}
+
+impl VatResult {
+    // The same result with the PPN rounded by round_to_multiple
+    pub fn rounded_to(&self, multiple: u32) -> VatResult {
+        VatResult { vat: round_to_multiple(self.vat, multiple), ..*self }
+    }
+}
+
+// Function to calculate VAT for a given status
+pub fn calculate_vat_with_status(amount: f64, status: VatStatus) -> VatResult {
+    let vat = match status {
+        VatStatus::Standard(rate) => calculate_vat(amount, rate),
+        VatStatus::ZeroRated | VatStatus::Exempt => 0.0,
+    };
+    
+    VatResult { status, base: amount, vat }
+}
+
+// Format a VAT result; with a mid-year rate change the blended annual VAT
+// follows the plain result
+pub fn render_vat(result: &VatResult, rate_change: Option<&RateChange>, lang: Lang) -> String {
+    let mut output = String::new();
+    writeln!(output, "{}", t_with("result.vat", lang, &[&result.status.label(lang)])).unwrap();
+    writeln!(output, "{}: {:>20}", t("label.price_before_vat", lang), format_rupiah(result.base)).unwrap();
+    writeln!(output, "{} {:>20}", t("label.vat", lang), format_rupiah(result.vat)).unwrap();
+    writeln!(output, "{}: {:>20}", t("label.total_due", lang), format_rupiah(result.base + result.vat)).unwrap();
+    
+    if let (Some(change), VatStatus::Standard(rate)) = (rate_change, result.status) {
+        let blended_vat = calculate_vat_with_rate_change(result.base, rate, change);
+        writeln!(output, "\n{}", t_with("vat.rate_change", lang, &[&rate, &change.new_rate, &change.effective_month])).unwrap();
+        writeln!(output, "{}: {:>20}", t("label.annual_vat", lang), format_rupiah(blended_vat)).unwrap();
+        writeln!(output, "{}: {:>20}", t("label.total_due", lang), format_rupiah(result.base + blended_vat)).unwrap();
+    }
+    
+    output
+}
+
+impl fmt::Display for VatResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&render_vat(self, None, Lang::Id))
+    }
+}
+
+// One invoice line item
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceLine {
+    pub description: String,
+    pub quantity: u32,
+    pub unit_price: f64,
+}
+
+impl InvoiceLine {
+    pub fn total(&self) -> f64 {
+        self.quantity as f64 * self.unit_price
+    }
+}
+
+// How an invoice's VAT was worked out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceVatMethod {
+    // Once on the subtotal
+    Subtotal,
+    // Rounded to whole rupiah on every line, with the difference to the
+    // rounded VAT on the subtotal put on the largest line
+    PerLineReconciled,
+}
+
+// Invoice totals; `line_vat` holds the VAT of each line, in line order, when
+// it was rounded per line and `adjustment` what reconciliation added to the
+// largest line
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct InvoiceSummary {
+    pub subtotal: f64,
+    pub vat_rate: f64,
+    pub vat: f64,
+    pub total: f64,
+    pub method: InvoiceVatMethod,
+    pub line_vat: Vec<f64>,
+    pub adjustment: f64,
+}
+
+pub fn calculate_invoice(lines: &[InvoiceLine], vat_rate: f64) -> InvoiceSummary {
+    let subtotal: f64 = lines.iter().map(InvoiceLine::total).sum();
+    let vat = calculate_vat(subtotal, vat_rate);
+    
+    InvoiceSummary {
+        subtotal,
+        vat_rate,
+        vat,
+        total: subtotal + vat,
+        method: InvoiceVatMethod::Subtotal,
+        line_vat: Vec::new(),
+        adjustment: 0.0,
+    }
+}
+
+// Invoice VAT rounded line by line, as on a tax invoice (faktur pajak) that
+// shows PPN per item; the rounding difference goes on the largest line so the
+// lines still add up to the VAT on the subtotal, rounded the same way
+pub fn calculate_invoice_per_line(lines: &[InvoiceLine], vat_rate: f64, rounding: RoundingMode) -> InvoiceSummary {
+    let subtotal: f64 = lines.iter().map(InvoiceLine::total).sum();
+    let vat = round_rupiah(calculate_vat(subtotal, vat_rate), rounding);
+    let mut line_vat: Vec<f64> = lines.iter().map(|line| round_rupiah(calculate_vat(line.total(), vat_rate), rounding)).collect();
+    let line_sum: Decimal = line_vat.iter().map(|amount| to_decimal(*amount)).sum();
+    let adjustment = from_decimal(to_decimal(vat) - line_sum);
+    
+    // First line with the largest total; ties keep the earlier line
+    let largest = lines.iter().enumerate().fold(None, |largest: Option<(usize, f64)>, (index, line)| match largest {
+        Some((_, total)) if total >= line.total() => largest,
+        _ => Some((index, line.total())),
+    });
+    if let Some((index, _)) = largest {
+        line_vat[index] = from_decimal(to_decimal(line_vat[index]) + to_decimal(adjustment));
+    }
+    
+    InvoiceSummary {
+        subtotal,
+        vat_rate,
+        vat,
+        total: subtotal + vat,
+        method: InvoiceVatMethod::PerLineReconciled,
+        line_vat,
+        adjustment,
+    }
+}
+
+// Format an invoice with one row per line item followed by the totals
+pub fn render_invoice(lines: &[InvoiceLine], summary: &InvoiceSummary, lang: Lang) -> String {
+    let mut rows: Vec<Vec<TableCell>> = lines
+        .iter()
+        .map(|line| vec![
+            TableCell::Text(line.description.clone()),
+            TableCell::Text(line.quantity.to_string()),
+            TableCell::Rupiah(line.unit_price),
+            TableCell::Rupiah(line.total()),
+        ])
+        .collect();
+    let blank = || TableCell::Text(String::new());
+    rows.push(vec![TableCell::Text(t("table.subtotal", lang).to_string()), blank(), blank(), TableCell::Rupiah(summary.subtotal)]);
+    let vat_label = match summary.method {
+        InvoiceVatMethod::Subtotal => format!("PPN {}%", summary.vat_rate),
+        InvoiceVatMethod::PerLineReconciled => t_with("table.vat_per_line", lang, &[&summary.vat_rate]),
+    };
+    rows.push(vec![TableCell::Text(vat_label), blank(), blank(), TableCell::Rupiah(summary.vat)]);
+    rows.push(vec![TableCell::Text(t("table.total", lang).to_string()), blank(), blank(), TableCell::Rupiah(summary.total)]);
+    
+    render_table(&[t("table.item", lang), t("table.quantity", lang), t("table.unit_price", lang), t("table.total", lang)], &rows)
+}
+
+// Highest PPnBM rate allowed by the VAT law, in percent
+pub const MAX_PPNBM_RATE: f64 = 200.0;
+
+// PPnBM (luxury goods sales tax) on the same base as VAT
+pub fn calculate_ppnbm(amount: f64, ppnbm_rate: f64) -> f64 {
+    amount * ppnbm_rate / 100.0
+}
+
+// Parse a PPnBM percentage and check it lies within 0-200%
+pub fn parse_ppnbm_rate(input: &str) -> Result<f64, CalcError> {
+    if input.trim().is_empty() {
+        return Err(CalcError::Parse(Message::new("error.ppnbm_required", &[])));
+    }
+    let rate = parse_percent(input, 0.0)?;
+    if !(0.0..=MAX_PPNBM_RATE).contains(&rate) {
+        return Err(CalcError::OutOfRange(Message::new("error.ppnbm_rate_range", &[&rate, &MAX_PPNBM_RATE])));
+    }
+    
+    Ok(rate)
+}
+
+// Whether an entered price excludes VAT (the base) or already includes it (the total)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VatMode {
+    Exclusive,
+    Inclusive,
+}
+
+// VAT embedded in a VAT-inclusive total, rounded to whole rupiah
+pub fn extract_vat(total: f64, vat_rate: f64) -> f64 {
+    round_rupiah(total - total / (1.0 + vat_rate / 100.0), RoundingMode::default())
+}
+
+// Function to calculate VAT for a given status, where `amount` is read per `mode`
+pub fn calculate_vat_with_mode(amount: f64, status: VatStatus, mode: VatMode) -> VatResult {
+    match (mode, status) {
+        (VatMode::Inclusive, VatStatus::Standard(rate)) => {
+            let vat = extract_vat(amount, rate);
+            VatResult { status, base: amount - vat, vat }
+        },
+        _ => calculate_vat_with_status(amount, status),
+    }
+}
+
+// Income subject to PPh 23 withholding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pph23Object {
+    Services,
+    Dividends,
+    Royalties,
+    Interest,
+}
+
+impl Pph23Object {
+    // PPh 23 rate for a counterparty with an NPWP
+    pub fn rate(&self) -> f64 {
+        match self {
+            Pph23Object::Services => 0.02,
+            Pph23Object::Dividends | Pph23Object::Royalties | Pph23Object::Interest => 0.15,
+        }
+    }
+    
+    pub fn label(&self, lang: Lang) -> &'static str {
+        let key = match self {
+            Pph23Object::Services => "object.services",
+            Pph23Object::Dividends => "object.dividends",
+            Pph23Object::Royalties => "object.royalties",
+            Pph23Object::Interest => "object.interest",
+        };
+        t(key, lang)
+    }
+}
+
+// PPh 23 withheld from a gross amount; the rate is 100% higher (doubled) when
+// the counterparty has no NPWP
+pub fn calculate_pph23(amount: f64, object: Pph23Object, has_npwp: bool, config: &TaxConfig) -> f64 {
+    let rate = if has_npwp { object.rate() } else { object.rate() * 2.0 };
+    
+    round_rupiah(amount * rate, config.rounding)
+}
+
+// PPh 26 on payments to non-resident individuals and entities, as a
+// percentage; a tax treaty (P3B) can lower it
+pub const PPH26_RATE: f64 = 20.0;
+
+// PPh 26 withheld from a gross amount at `rate` percent
+pub fn calculate_pph26(amount: f64, rate: f64, config: &TaxConfig) -> f64 {
+    round_rupiah(amount * rate / 100.0, config.rounding)
+}
+
+// Parse a PPh 26 percentage within 0-20%; empty input uses the 20% default
+pub fn parse_pph26_rate(input: &str) -> Result<f64, CalcError> {
+    let rate = parse_percent(input, PPH26_RATE)?;
+    if !(0.0..=PPH26_RATE).contains(&rate) {
+        return Err(CalcError::OutOfRange(Message::new("error.pph26_rate_range", &[&rate, &PPH26_RATE])));
+    }
+    
+    Ok(rate)
+}
+
+// Contractor qualification for construction work (PP 9/2022)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConstructionQualification {
+    // Kualifikasi kecil or individual with a certificate
+    Small,
+    // Kualifikasi menengah/besar
+    MediumLarge,
+    // No business entity or expertise certificate
+    Unqualified,
+}
+
+impl ConstructionQualification {
+    pub fn rate(&self) -> f64 {
+        match self {
+            ConstructionQualification::Small => 0.0175,
+            ConstructionQualification::MediumLarge => 0.0265,
+            ConstructionQualification::Unqualified => 0.04,
+        }
+    }
+}
+
+// Income subject to PPh Final Pasal 4 ayat (2)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PphFinal42Object {
+    // Land and/or building rental
+    Rental,
+    Construction(ConstructionQualification),
+    // Transfer of land and/or building rights
+    LandSale,
+}
+
+impl PphFinal42Object {
+    pub fn rate(&self) -> f64 {
+        match self {
+            PphFinal42Object::Rental => 0.10,
+            PphFinal42Object::Construction(qualification) => qualification.rate(),
+            PphFinal42Object::LandSale => 0.025,
+        }
+    }
+    
+    pub fn label(&self, lang: Lang) -> &'static str {
+        let key = match self {
+            PphFinal42Object::Rental => "object.rental",
+            PphFinal42Object::Construction(ConstructionQualification::Small) => "object.construction_small",
+            PphFinal42Object::Construction(ConstructionQualification::MediumLarge) => "object.construction_medium_large",
+            PphFinal42Object::Construction(ConstructionQualification::Unqualified) => "object.construction_unqualified",
+            PphFinal42Object::LandSale => "object.land_sale",
+        };
+        t(key, lang)
+    }
+}
+
+// PPh Final 4(2) on a gross value
+pub fn calculate_pph_final_42(gross: f64, object: PphFinal42Object, config: &TaxConfig) -> f64 {
+    round_rupiah(gross * object.rate(), config.rounding)
+}
+
+// Built-in final-tax rates in percent by category, used when the config
+// file has no `final_taxes` section
+pub fn builtin_final_taxes() -> BTreeMap<String, f64> {
+    [("deposit_interest", 20.0), ("dividend", 10.0), ("land_sale", 2.5), ("rental", 10.0)]
+        .into_iter()
+        .map(|(category, rate)| (category.to_string(), rate))
+        .collect()
+}
+
+// Final tax on `amount` at the rate `config.final_taxes` lists for `category`
+pub fn calculate_final_tax(amount: f64, category: &str, config: &TaxConfig) -> Result<f64, CalcError> {
+    let rate = config.final_taxes.get(category).ok_or_else(|| {
+        let categories: Vec<&str> = config.final_taxes.keys().map(String::as_str).collect();
+        CalcError::Parse(Message::new("error.unknown_final_tax", &[&category, &categories.join(", ")]))
+    })?;
+    
+    Ok(from_decimal(round_decimal(to_decimal(amount) * to_decimal(*rate) / Decimal::ONE_HUNDRED, config.rounding)))
+}
+
+// Final tax brackets for severance pay (pesangon), applied to the gross amount
+pub fn get_pesangon_brackets() -> Vec<TaxBracket> {
+    vec![
+        TaxBracket { lower_bound: 0.0, upper_bound: 50_000_000.0, rate: 0.0 },
+        TaxBracket { lower_bound: 50_000_000.0, upper_bound: 100_000_000.0, rate: 0.05 },
+        TaxBracket { lower_bound: 100_000_000.0, upper_bound: 500_000_000.0, rate: 0.15 },
+        TaxBracket { lower_bound: 500_000_000.0, upper_bound: f64::MAX, rate: 0.25 },
+    ]
+}
+
+// Final PPh 21 on severance pay
+pub fn calculate_pesangon_tax(amount: f64) -> f64 {
+    calculate_income_tax(amount, &get_pesangon_brackets())
+}
+
+// Named PPh 21 scenario for exploring the calculator without typing inputs;
+// a config file lists them as [[presets]] tables
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub gross_income: f64,
+    #[serde(default)]
+    pub is_married: bool,
+    #[serde(default)]
+    pub num_dependents: u32,
+}
+
+// Built-in presets for common salaries (2023 figures)
+pub fn builtin_presets() -> Vec<Preset> {
+    let preset = |name: &str, description: &str, gross_income: f64, is_married: bool, num_dependents: u32| Preset {
+        name: name.to_string(),
+        description: description.to_string(),
+        gross_income,
+        is_married,
+        num_dependents,
+    };
+    
+    vec![
+        preset("umr-jakarta", "UMP DKI Jakarta 2023, belum kawin", 4_901_798.0, false, 0),
+        preset("mid-manager", "Manajer menengah, kawin, 1 tanggungan", 15_000_000.0, true, 1),
+        preset("executive", "Eksekutif, kawin, 3 tanggungan", 50_000_000.0, true, 3),
+    ]
+}
+
+impl Preset {
+    pub fn params(&self) -> Result<PPh21Params, CalcError> {
+        PPh21Params::builder()
+            .gross_income(self.gross_income)
+            .married(self.is_married)
+            .dependents(if self.is_married { self.num_dependents } else { 0 })
+            .build()
+    }
+}
+
+// Compute and format a condensed PPh 21 result under a heading, in `lang`
+pub fn render_pph21_summary(heading: &str, params: &PPh21Params, config: &TaxConfig, lang: Lang) -> String {
+    let (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) = calculate_pph21_with_method(params, Pph21Method::Progressive, config);
+    let ptkp_key = params.ptkp_label();
+    
+    let mut output = format!("=== {} ===\n", heading);
+    output.push_str(&format!("{:<27}: {:>20}\n", t("label.gross_monthly", lang), format_rupiah(params.gross_income)));
+    if params.overtime > 0.0 {
+        output.push_str(&format!("{:<27}: {:>20}\n", t("label.overtime_monthly", lang), format_rupiah(params.overtime)));
+    }
+    output.push_str(&format!("{:<27}: {:>20}\n", t("label.biaya_jabatan", lang), format_rupiah(biaya_jabatan)));
+    output.push_str(&format!("{:<27}: {:>20}\n", format!("PTKP {}", ptkp_key), format_rupiah(ptkp)));
+    output.push_str(&format!("{:<27}: {:>20}\n", "PKP", format_rupiah(pkp)));
+    output.push_str(&format!("{:<27}: {:>20}\n", t("label.annual_tax", lang), format_rupiah(annual_tax)));
+    output.push_str(&format!("{:<27}: {:>20}\n", t("label.monthly_tax", lang), format_rupiah(monthly_tax)));
+    output
+}
+
+// Compute and format the PPh 21 result for a preset, in `lang`
+pub fn render_preset(preset: &Preset, config: &TaxConfig, lang: Lang) -> Result<String, CalcError> {
+    Ok(render_pph21_summary(&format!("Preset {} ({})", preset.name, preset.description), &preset.params()?, config, lang))
+}
+
+// Version tag for shareable request codes. v2 carries every PPh21Params
+// field; v1 codes (gross, status, dependents and NPWP only) still decode
+pub const REQUEST_CODE_VERSION: &str = "v2";
+
+// Encode PPh 21 inputs as a compact URL-safe string that reproduces the calculation
+pub fn encode_request(params: &PPh21Params) -> String {
+    let payload = format!(
+        "{}:{}:{}:{}:{}:{}:{}:{}:{}:{}:{}",
+        REQUEST_CODE_VERSION,
+        params.gross_income,
+        match (params.is_married, params.spouse_income_combined) {
+            (true, true) => 2,
+            (true, false) => 1,
+            (false, _) => 0,
+        },
+        params.num_dependents,
+        u8::from(params.has_npwp),
+        params.zakat,
+        params.overtime,
+        params.pension_contribution,
+        u8::from(params.disability_adjustment),
+        params.months_per_year,
+        params.months_worked
+    );
+    URL_SAFE_NO_PAD.encode(payload)
+}
+
+// Restore PPh 21 inputs from a code produced by `encode_request`; the
+// restored inputs pass the same checks as PPh21Params::builder
+pub fn decode_request(code: &str) -> Result<PPh21Params, CalcError> {
+    let invalid = || CalcError::Parse(Message::new("error.invalid_request_code", &[&code.trim()]));
+    let bytes = URL_SAFE_NO_PAD.decode(code.trim()).map_err(|_| invalid())?;
+    let payload = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let flag = |value: &str| match value {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(invalid()),
+    };
+    let status = |married: &str| match married {
+        "0" => Ok((false, false)),
+        "1" => Ok((true, false)),
+        "2" => Ok((true, true)),
+        _ => Err(invalid()),
+    };
+    
+    match payload.split(':').collect::<Vec<&str>>().as_slice() {
+        [REQUEST_CODE_VERSION, gross, married, dependents, has_npwp, zakat, overtime, pension, disability, months_per_year, months_worked] => {
+            let (is_married, spouse_income_combined) = status(married)?;
+            PPh21Params::builder()
+                .gross_income(gross.parse().map_err(|_| invalid())?)
+                .married(is_married)
+                .dependents(dependents.parse().map_err(|_| invalid())?)
+                .spouse_income_combined(spouse_income_combined)
+                .has_npwp(flag(has_npwp)?)
+                .zakat(zakat.parse().map_err(|_| invalid())?)
+                .overtime(overtime.parse().map_err(|_| invalid())?)
+                .pension_contribution(pension.parse().map_err(|_| invalid())?)
+                .disability_adjustment(flag(disability)?)
+                .months_per_year(months_per_year.parse().map_err(|_| invalid())?)
+                .months_worked(months_worked.parse().map_err(|_| invalid())?)
+                .build()
+        },
+        // v1: a trailing ":0" marks an employee without an NPWP
+        ["v1", gross, married, dependents, no_npwp @ ..] if no_npwp.is_empty() || no_npwp == ["0"] => {
+            let (is_married, spouse_income_combined) = status(married)?;
+            PPh21Params::builder()
+                .gross_income(gross.parse().map_err(|_| invalid())?)
+                .married(is_married)
+                .dependents(dependents.parse().map_err(|_| invalid())?)
+                .spouse_income_combined(spouse_income_combined)
+                .has_npwp(no_npwp.is_empty())
+                .build()
+        },
+        _ => Err(invalid()),
+    }
+}
+
+// SHA-256 over a canonical rendering of everything in `config` that feeds a
+// calculation (PTKP, brackets, rounding, rates and BPJS caps) together with
+// the fixed biaya jabatan, BPJS and TER tables, so two runs can be confirmed
+// to have used identical tables
+pub fn fingerprint_tables(config: &TaxConfig) -> String {
+    let mut canonical = String::new();
+    for (status, amount) in &config.ptkp {
+        canonical.push_str(&format!("ptkp:{}={:?};", status, amount));
+    }
+    for bracket in &config.brackets {
+        canonical.push_str(&format!("bracket:{:?}-{:?}@{:?};", bracket.lower_bound, bracket.upper_bound, bracket.rate));
+    }
+    canonical.push_str(&format!("max_dependents:{};", MAX_PTKP_DEPENDENTS));
+    canonical.push_str(&format!("rounding:{:?};vat_rate:{:?};flat_rate:{:?};", config.rounding, config.vat_rate, config.flat_rate));
+    canonical.push_str(&format!("biaya_jabatan:{:?}@{:?};no_npwp:{:?};", BIAYA_JABATAN_RATE, BIAYA_JABATAN_ANNUAL_CAP, NO_NPWP_SURCHARGE));
+    canonical.push_str(&format!(
+        "bpjs:kesehatan={:?}@{:?},jht={:?},jp={:?}@{:?};",
+        BPJS_KESEHATAN_RATE, config.bpjs_kesehatan_salary_cap, BPJS_JHT_RATE, BPJS_JP_RATE, config.bpjs_jp_salary_cap
+    ));
+    for (name, table) in [("A", TER_TABLE_A), ("B", TER_TABLE_B), ("C", TER_TABLE_C)] {
+        for (upper_bound, rate) in table {
+            canonical.push_str(&format!("ter:{}:{:?}@{:?};", name, upper_bound, rate));
+        }
+    }
+    for preset in &config.presets {
+        canonical.push_str(&format!("preset:{:?};", preset));
+    }
+    for (category, rate) in &config.final_taxes {
+        canonical.push_str(&format!("final_tax:{}={:?};", category, rate));
+    }
+    
+    Sha256::digest(canonical.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// Fingerprint of the built-in tables for a tax year, if that year is available
+pub fn tables_fingerprint(year: u16) -> Option<String> {
+    TaxConfig::for_year(year).ok().map(|config| config.fingerprint())
+}
+
+// Result of a single self-check on the built-in tables
+#[derive(Debug)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub passed: bool,
+}
+
+// PTKP must grow with each status: TK/0 < K/0 < K/1 < K/2 < K/3
+pub fn check_ptkp_ordering(ptkp: &PtkpTable) -> bool {
+    let order = [PtkpStatus::Tk0, PtkpStatus::K0, PtkpStatus::K1, PtkpStatus::K2, PtkpStatus::K3];
+    order.windows(2).all(|pair| match (ptkp.get(&pair[0]), ptkp.get(&pair[1])) {
+        (Some(lower), Some(higher)) => lower < higher,
+        _ => false,
+    })
+}
+
+// Brackets must start at zero and each one must begin where the previous ended
+pub fn check_bracket_contiguity(brackets: &[TaxBracket]) -> bool {
+    brackets.first().is_some_and(|first| first.lower_bound == 0.0)
+        && brackets.iter().all(|bracket| bracket.width() > 0.0)
+        && brackets.windows(2).all(|pair| pair[0].upper_bound == pair[1].lower_bound)
+}
+
+// Rates must increase with each higher bracket
+pub fn check_rate_monotonicity(brackets: &[TaxBracket]) -> bool {
+    brackets.windows(2).all(|pair| pair[0].rate < pair[1].rate)
+}
+
+// Run all consistency checks against the given tables, naming them in `lang`
+pub fn run_selfcheck(ptkp: &PtkpTable, brackets: &[TaxBracket], lang: Lang) -> Vec<CheckResult> {
+    vec![
+        CheckResult { name: t("selfcheck.ptkp_order", lang), passed: check_ptkp_ordering(ptkp) },
+        CheckResult { name: t("selfcheck.bracket_contiguity", lang), passed: check_bracket_contiguity(brackets) },
+        CheckResult { name: t("selfcheck.rate_monotonicity", lang), passed: check_rate_monotonicity(brackets) },
+    ]
+}
+
+// Per-bracket Pasal 17 lines for a PKP, followed by the annual and monthly tax
+fn write_progressive_breakdown(output: &mut String, pkp: f64, annual_tax: f64, monthly_tax: f64, months_worked: u8, config: &TaxConfig, lang: Lang) {
+    output.push_str(&render_income_tax_breakdown(&income_tax_breakdown(pkp, &config.brackets)));
+    writeln!(output, "{} {:>20}", t("worksheet.per_year", lang), format_rupiah(annual_tax)).unwrap();
+    writeln!(output, "{} {:>20} / {} = {:>20}", 
+        t("worksheet.per_month", lang),
+        format_rupiah(annual_tax),
+        months_worked,
+        format_rupiah(monthly_tax)).unwrap();
+}
+
+fn marital_status_label(params: &PPh21Params, lang: Lang) -> &'static str {
+    let key = match (params.is_married, params.spouse_income_combined) {
+        (true, true) => "marital.combined",
+        (true, false) => "marital.married",
+        (false, _) => "marital.single",
+    };
+    t(key, lang)
+}
+
+// Full PPh 21 worksheet for the gross scheme (employee bears the tax)
+pub fn render_gross_worksheet(params: &PPh21Params, method: Pph21Method, config: &TaxConfig, lang: Lang) -> String {
+    let (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) = calculate_pph21_with_method(params, method, config);
+    let ptkp_key = params.ptkp_label();
+    
+    let mut output = String::new();
+    writeln!(output, "\n{}", t("worksheet.gross", lang)).unwrap();
+    writeln!(output, "{}: {:>20}", t("label.gross_monthly", lang), format_rupiah(params.gross_income)).unwrap();
+    if params.overtime > 0.0 {
+        writeln!(output, "{:<28}{:>20}", t("worksheet.overtime_monthly", lang), format_rupiah(params.overtime)).unwrap();
+    }
+    writeln!(output, "{:<28}{:>20}", t("worksheet.gross_annual", lang), format_rupiah(params.annual_gross())).unwrap();
+    if params.months_per_year != 12 {
+        writeln!(output, "{}", t_with("worksheet.thirteenth", lang, &[&params.months_per_year])).unwrap();
+    }
+    if params.months_worked < 12 {
+        writeln!(output, "{}", t_with("worksheet.mid_year", lang, &[&params.months_worked])).unwrap();
+    }
+    writeln!(output, "\nStatus: {}", marital_status_label(params, lang)).unwrap();
+    if params.is_married {
+        writeln!(output, "{}: {}", t("label.dependents", lang), params.num_dependents).unwrap();
+    }
+    
+    // Display PTKP and PKP details
+    writeln!(output, "\n{}", t("worksheet.ptkp", lang)).unwrap();
+    writeln!(output, "Status {:<5}: {:>20} {}", ptkp_key, format_rupiah(ptkp), t("label.per_year", lang)).unwrap();
+    
+    writeln!(output, "\n{}", t("worksheet.biaya_jabatan", lang)).unwrap();
+    writeln!(output, "{}: {:>20}", t("worksheet.biaya_jabatan_rule", lang), format_rupiah(biaya_jabatan)).unwrap();
+    
+    writeln!(output, "\n{}", t("worksheet.pkp", lang)).unwrap();
+    let mut terms = vec![(t("label.annual_salary", lang), params.annual_gross()), (t("label.biaya_jabatan", lang), biaya_jabatan)];
+    if params.pension_contribution > 0.0 {
+        terms.push((t("label.pension", lang), params.annual_pension_contribution()));
+    }
+    if params.zakat > 0.0 {
+        terms.push(("Zakat", params.zakat));
+    }
+    terms.push(("PTKP", ptkp));
+    let labels: Vec<&str> = terms.iter().map(|(label, _)| *label).collect();
+    let amounts: Vec<String> = terms.iter().map(|(_, amount)| format!("{:>20}", format_rupiah(*amount))).collect();
+    writeln!(output, "{}: {} = {:>20}", labels.join(" - "), amounts.join(" - "), format_rupiah(pkp)).unwrap();
+    
+    // Display PPh 21 calculation details
+    match method {
+        Pph21Method::Progressive => {
+            writeln!(output, "\n{}", t("worksheet.progressive", lang)).unwrap();
+            write_progressive_breakdown(&mut output, pkp, annual_tax, monthly_tax, params.months_worked, config, lang);
+        },
+        Pph21Method::Flat => {
+            writeln!(output, "\n{}", t_with("worksheet.flat", lang, &[&config.flat_rate])).unwrap();
+            writeln!(output, "{} {}% x {:>20} = {:>20}", t("worksheet.per_month", lang), config.flat_rate,
+                format_rupiah(params.monthly_gross()),
+                format_rupiah(monthly_tax)).unwrap();
+            writeln!(output, "{} {}% x {:>20} = {:>20}", t("worksheet.per_year", lang), config.flat_rate,
+                format_rupiah(params.annual_gross()),
+                format_rupiah(annual_tax)).unwrap();
+        },
+    }
+    if !params.has_npwp {
+        writeln!(output, "{}", t("worksheet.no_npwp", lang)).unwrap();
+    }
+    
+    // Summary
+    writeln!(output, "\n{}", t("worksheet.summary", lang)).unwrap();
+    if params.overtime > 0.0 {
+        let payments = f64::from(params.salary_payments());
+        writeln!(output, "{:<20}: {:>20}", t("label.annual_salary", lang), format_rupiah(params.gross_income * payments)).unwrap();
+        writeln!(output, "{:<20}: {:>20} (+)", t("label.annual_overtime", lang), format_rupiah(params.overtime * payments)).unwrap();
+    }
+    writeln!(output, "{:<20}: {:>20}", t("label.annual_gross_pay", lang), format_rupiah(params.annual_gross())).unwrap();
+    writeln!(output, "{:<20}: {:>20} (-)", t("label.biaya_jabatan", lang), format_rupiah(biaya_jabatan)).unwrap();
+    if params.pension_contribution > 0.0 {
+        writeln!(output, "{:<20}: {:>20} (-)", t("label.pension", lang), format_rupiah(params.annual_pension_contribution())).unwrap();
+    }
+    if params.zakat > 0.0 {
+        writeln!(output, "Zakat               : {:>20} (-)", format_rupiah(params.zakat)).unwrap();
+    }
+    writeln!(output, "PTKP                : {:>20} (-)", format_rupiah(ptkp)).unwrap();
+    writeln!(output, "PKP                 : {:>20}", format_rupiah(pkp)).unwrap();
+    writeln!(output, "{:<20}: {:>20}", t("label.annual_tax", lang), format_rupiah(annual_tax)).unwrap();
+    writeln!(output, "{:<20}: {:>20}", t("label.monthly_tax", lang), format_rupiah(monthly_tax)).unwrap();
+    
+    // Monthly take-home after BPJS and PPh 21
+    let bpjs = calculate_bpjs(params.gross_income, config);
+    writeln!(output, "\n{:<20}: {:>20}", t("label.monthly_gross_pay", lang), format_rupiah(params.gross_income)).unwrap();
+    if params.overtime > 0.0 {
+        writeln!(output, "{:<20}: {:>20} (+)", t("label.overtime", lang), format_rupiah(params.overtime)).unwrap();
+    }
+    writeln!(output, "BPJS Kesehatan (1%) : {:>20} (-)", format_rupiah(bpjs.kesehatan)).unwrap();
+    writeln!(output, "BPJS JHT (2%)       : {:>20} (-)", format_rupiah(bpjs.jht)).unwrap();
+    writeln!(output, "BPJS JP (1%)        : {:>20} (-)", format_rupiah(bpjs.jp)).unwrap();
+    writeln!(output, "{:<20}: {:>20} (-)", t("label.monthly_tax", lang), format_rupiah(monthly_tax)).unwrap();
+    writeln!(output, "{:<20}: {:>20}", t("label.take_home", lang), format_rupiah(params.monthly_gross() - bpjs.total - monthly_tax)).unwrap();
+    
+    writeln!(output, "\n{:<20}: {}", t("label.fingerprint", lang), config.fingerprint()).unwrap();
+    
+    output
+}
+
+// Payroll report lines from gross pay down to take-home pay, with each
+// post-tax deduction after the PPh 21
+pub fn payroll_rows(report: &PayrollReport, lang: Lang) -> Vec<(&str, f64)> {
+    let mut rows = vec![
+        (t("payroll.annual_gross", lang), report.gross_annual),
+        (t("payroll.biaya_jabatan", lang), report.biaya_jabatan),
+        (t("payroll.ptkp", lang), report.ptkp),
+        ("PKP", report.pkp),
+        (t("label.annual_tax", lang), report.annual_tax),
+        (t("label.monthly_gross_pay", lang), report.gross_monthly),
+        (t("payroll.bpjs_kesehatan", lang), report.bpjs.kesehatan),
+        (t("payroll.bpjs_jht", lang), report.bpjs.jht),
+        (t("payroll.bpjs_jp", lang), report.bpjs.jp),
+        (t("payroll.monthly_tax", lang), report.monthly_tax),
+    ];
+    rows.extend(report.post_tax_deductions.iter().map(|(name, amount)| (name.as_str(), *amount)));
+    rows.extend([
+        (t("label.take_home", lang), report.net_monthly),
+        (t("payroll.employer_jkk", lang), report.employer.jkk),
+        (t("payroll.employer_jkm", lang), report.employer.jkm),
+        (t("payroll.employer_jht", lang), report.employer.jht),
+        (t("payroll.employer_jp", lang), report.employer.jp),
+        (t("payroll.employer_kesehatan", lang), report.employer.kesehatan),
+        (t("payroll.company_cost", lang), report.company_cost),
+    ]);
+    
+    rows
+}
+
+// PPh 21 result lines in worksheet order
+pub fn pph21_result_rows(result: &Pph21Result, lang: Lang) -> Vec<(&'static str, f64)> {
+    vec![
+        (t("label.monthly_gross_pay", lang), result.gross_monthly),
+        (t("label.annual_biaya_jabatan", lang), result.biaya_jabatan),
+        ("PTKP", result.ptkp),
+        ("PKP", result.pkp),
+        (t("label.annual_tax", lang), result.annual_tax),
+        (t("label.monthly_tax", lang), result.monthly_tax),
+    ]
+}
+
+// Format a payroll report as a table, from gross pay down to take-home pay
+pub fn render_payroll(report: &PayrollReport, lang: Lang) -> String {
+    let rows: Vec<Vec<TableCell>> = payroll_rows(report, lang)
+        .into_iter()
+        .map(|(label, amount)| vec![TableCell::Text(label.to_string()), TableCell::Rupiah(amount)])
+        .collect();
+    
+    render_table(&[t("table.component", lang), t("table.amount", lang)], &rows)
+}
+
+impl fmt::Display for PayrollReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&render_payroll(self, Lang::Id))
+    }
+}
+
+// Format a PPh 21 result in the same table layout as the payroll report
+pub fn render_pph21_result(result: &Pph21Result, lang: Lang) -> String {
+    let rows: Vec<Vec<TableCell>> = pph21_result_rows(result, lang)
+        .into_iter()
+        .map(|(label, amount)| vec![TableCell::Text(label.to_string()), TableCell::Rupiah(amount)])
+        .collect();
+    
+    render_table(&[t("table.component", lang), t("table.amount", lang)], &rows)
+}
+
+impl fmt::Display for Pph21Result {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&render_pph21_result(self, Lang::Id))
+    }
+}
+
+// A second currency results are also shown in; amounts are converted for
+// display only, so all tax math stays in rupiah
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayCurrency {
+    pub code: String,
+    // Rupiah per one unit of the currency
+    pub rate: f64,
+}
+
+impl DisplayCurrency {
+    // Validate a three-letter currency code and a positive exchange rate
+    pub fn new(code: &str, rate: f64) -> Result<DisplayCurrency, CalcError> {
+        let code = code.trim().to_ascii_uppercase();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(CalcError::Parse(Message::new("error.invalid_currency", &[&code])));
+        }
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(CalcError::OutOfRange(Message::new("error.exchange_rate", &[&code, &rate])));
+        }
+        
+        Ok(DisplayCurrency { code, rate })
+    }
+    
+    pub fn convert(&self, rupiah: f64) -> f64 {
+        from_decimal(to_decimal(rupiah) / to_decimal(self.rate))
+    }
+    
+    // Amount in this currency in English notation, e.g. USD 1,234.56
+    pub fn format(&self, amount: f64) -> String {
+        let amount = to_decimal(amount).round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero);
+        let sign = if amount < Decimal::ZERO { "-" } else { "" };
+        let amount = amount.abs();
+        let whole = amount.trunc().to_u128().unwrap_or(0);
+        let cents = ((amount - amount.trunc()) * Decimal::from(100)).to_u8().unwrap_or(0);
+        
+        format!("{}{} {}.{:02}", sign, self.code, whole.separate_with_commas(), cents)
+    }
+}
+
+// The key PPh 21 amounts in rupiah beside their value in `currency`, in `lang`
+pub fn render_converted_result(result: &Pph21Result, currency: &DisplayCurrency, lang: Lang) -> String {
+    let rows: Vec<Vec<TableCell>> = pph21_result_rows(result, lang)
+        .into_iter()
+        .map(|(label, amount)| {
+            vec![
+                TableCell::Text(label.to_string()),
+                TableCell::Rupiah(amount),
+                TableCell::Text(currency.format(currency.convert(amount))),
+            ]
+        })
+        .collect();
+    
+    format!(
+        "{}\n{}",
+        t_with("result.exchange_rate", lang, &[&format_rupiah(currency.rate), &currency.code]),
+        render_table(&[t("table.component", lang), "Rupiah", &currency.code], &rows)
+    )
+}
+
+// Bukti potong number and NPWP on one line, in `lang`
+pub fn render_bukti_potong(slip: &BuktiPotong, lang: Lang) -> String {
+    format!("{}: {} (NPWP {})", t("label.bukti_potong", lang), slip.number, slip.npwp)
+}
+
+impl fmt::Display for BuktiPotong {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&render_bukti_potong(self, Lang::Id))
+    }
+}
+
+impl fmt::Display for Pph21WithBuktiPotong {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.result)?;
+        writeln!(f, "{}", self.bukti_potong)
+    }
+}
+
+// Format (item, amount) lines as a GitHub-flavored Markdown table with headers in `lang`
+pub fn render_markdown_table(rows: &[(&str, f64)], lang: Lang) -> String {
+    let mut output = format!("| {} | {} |\n|---|---:|\n", t("table.component", lang), t("table.amount", lang));
+    for (label, amount) in rows {
+        writeln!(output, "| {} | {} |", label, format_rupiah(*amount)).unwrap();
+    }
+    
+    output
+}
+
+// Condensed --summary output, one "label: amount" line per figure
+pub fn render_brief(rows: &[(&str, f64)]) -> String {
+    rows.iter().map(|(label, amount)| format!("{}: {}\n", label, format_rupiah(*amount))).collect()
+}
+
+// --summary figures of a PPh 21 result: the monthly tax and the monthly
+// gross left after it, before BPJS
+pub fn pph21_brief_rows(result: &Pph21Result, lang: Lang) -> Vec<(&'static str, f64)> {
+    vec![
+        (t("label.monthly_tax", lang), result.monthly_tax),
+        (t("label.net_monthly", lang), from_decimal(to_decimal(result.gross_monthly) - to_decimal(result.monthly_tax))),
+    ]
+}
+
+// --summary figures of a payroll report: the monthly tax, the employee's
+// BPJS and the take-home pay
+pub fn payroll_brief_rows(report: &PayrollReport, lang: Lang) -> Vec<(&'static str, f64)> {
+    vec![
+        (t("label.monthly_tax", lang), report.monthly_tax),
+        ("BPJS", report.bpjs.total),
+        (t("label.take_home", lang), report.net_monthly),
+    ]
+}
+
+// Extra monthly outlay for the company under gross-up versus paying the same
+// amount as plain gross salary; equals the PPh 21 the company absorbs
+pub fn company_cost_delta(net_salary: f64, gross_up_salary: f64) -> f64 {
+    gross_up_salary - net_salary
+}
+
+// Full PPh 21 worksheet for the gross-up scheme (company bears the tax)
+// `gross_salary` is the solved gross from `gross_up`, before `status.overtime`
+pub fn render_gross_up_worksheet(net_salary: f64, gross_salary: f64, status: &PPh21Params, config: &TaxConfig, lang: Lang) -> String {
+    let is_married = status.is_married;
+    let num_dependents = status.num_dependents;
+    let gross_params = PPh21Params { gross_income: gross_salary, ..*status };
+    let (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) = calculate_pph21_with_method(&gross_params, Pph21Method::Progressive, config);
+    
+    let ptkp_key = gross_params.ptkp_label();
+    let per_month = t("label.per_month", lang);
+    
+    let mut output = String::new();
+    writeln!(output, "\n{}", t("worksheet.gross_up", lang)).unwrap();
+    
+    // Employee Receives Section
+    writeln!(output, "\n{}", t("worksheet.employee_receives", lang)).unwrap();
+    writeln!(output, "{:<27}: {:>20} {}", t("label.net_pay", lang), format_rupiah(net_salary), per_month).unwrap();
+    writeln!(output, "{:<26}: {:>20}", t("label.annual_net_pay", lang), format_rupiah(net_salary * f64::from(status.salary_payments()))).unwrap();
+    
+    // Company Pays Section
+    writeln!(output, "\n{}", t("worksheet.company_pays", lang)).unwrap();
+    writeln!(output, "{:<22}: {:>20} {}", t("label.gross_up_pay", lang), format_rupiah(gross_params.monthly_gross()), per_month).unwrap();
+    writeln!(output, "{:<22}: {:>20}", t("label.annual_gross_up_pay", lang), format_rupiah(gross_params.annual_gross())).unwrap();
+    
+    // Company vs employee portion: take-home is unchanged, the company absorbs the tax
+    writeln!(output, "\n{}", t("worksheet.shares", lang)).unwrap();
+    writeln!(output, "{:<43}: {:>20} {}", t("label.employee_share", lang), format_rupiah(net_salary), per_month).unwrap();
+    writeln!(output, "{:<43}: {:>20} {}", t("label.company_extra_cost", lang), format_rupiah(company_cost_delta(net_salary, gross_params.monthly_gross())), per_month).unwrap();
+    
+    // Tax Calculation Section
+    writeln!(output, "\n{}", t("worksheet.tax", lang)).unwrap();
+    writeln!(output, "Status              : {}", marital_status_label(status, lang)).unwrap();
+    if is_married {
+        writeln!(output, "{:<20}: {}", t("label.dependents", lang), num_dependents).unwrap();
+    }
+    writeln!(output, "PTKP (Status {})    : {:>20} {}", ptkp_key, format_rupiah(ptkp), t("label.per_year", lang)).unwrap();
+    
+    // PKP Calculation
+    writeln!(output, "\n{}", t("worksheet.pkp_upper", lang)).unwrap();
+    writeln!(output, "{} - {} - PTKP: {:>20} - {:>20} - {:>20} = {:>20}", 
+        t("label.annual_salary", lang),
+        t("label.biaya_jabatan", lang),
+        format_rupiah(gross_params.annual_gross()),
+        format_rupiah(biaya_jabatan),
+        format_rupiah(ptkp),
+        format_rupiah(pkp)).unwrap();
+    
+    // PPh 21 Calculation
+    writeln!(output, "\n{}", t("worksheet.pph21_upper", lang)).unwrap();
+    writeln!(output, "PPh 21                    : {:>20}", format_rupiah(monthly_tax)).unwrap();
+    writeln!(output, "\n{}", t("worksheet.progressive_breakdown", lang)).unwrap();
+    write_progressive_breakdown(&mut output, pkp, annual_tax, monthly_tax, status.months_worked, config, lang);
+    
+    // Annual Summary
+    writeln!(output, "\n{}", t("worksheet.annual_summary_upper", lang)).unwrap();
+    writeln!(output, "{:<20}: {:>20}", t("label.annual_gross_up_pay", lang), format_rupiah(gross_params.annual_gross())).unwrap();
+    writeln!(output, "{:<20}: {:>20} (-)", t("label.biaya_jabatan", lang), format_rupiah(biaya_jabatan)).unwrap();
+    writeln!(output, "PTKP                : {:>20} (-)", format_rupiah(ptkp)).unwrap();
+    writeln!(output, "PKP                 : {:>20}", format_rupiah(pkp)).unwrap();
+    writeln!(output, "{:<20}: {:>20}", t("label.annual_tax", lang), format_rupiah(annual_tax)).unwrap();
+    writeln!(output, "{:<20}: {:>20}", t("label.annual_net_pay", lang), format_rupiah(net_salary * f64::from(status.salary_payments()))).unwrap();
+    
+    writeln!(output, "\n{}", t("worksheet.notes", lang)).unwrap();
+    writeln!(output, "{}", t("note.company_bears_tax", lang)).unwrap();
+    writeln!(output, "{}", t("worksheet.promised_net", lang)).unwrap();
+    
+    output
+}
+
+// Both worksheets for one salary: as gross under the gross scheme and as the
+// promised take-home under the gross-up scheme
+pub fn render_gross_and_gross_up(params: &PPh21Params, config: &TaxConfig, lang: Lang) -> Result<String, CalcError> {
+    let mut output = format!("\n{}\n", t("worksheet.gross_scheme", lang));
+    output.push_str(&render_gross_worksheet(params, Pph21Method::Progressive, config, lang));
+    output.push_str(&format!("\n{}\n", t("worksheet.gross_up_scheme", lang)));
+    let gross_salary = gross_up(params.monthly_gross(), params, config)?;
+    output.push_str(&render_gross_up_worksheet(params.monthly_gross(), gross_salary, params, config, lang));
+    Ok(output)
+}
+
+// Who bears the employee's PPh 21
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PayrollScheme {
+    // The employee bears the tax, withheld from the agreed salary
+    Gross,
+    // The company pays a tax allowance that covers the tax on itself
+    GrossUp,
+    // The company pays the tax on top of the agreed salary, without an allowance
+    Nett,
+}
+
+impl PayrollScheme {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PayrollScheme::Gross => "Gross",
+            PayrollScheme::GrossUp => "Gross Up",
+            PayrollScheme::Nett => "Nett",
+        }
+    }
+}
+
+// Monthly figures for one scheme; take-home is after PPh 21, before BPJS
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub struct SchemeComparison {
+    pub scheme: PayrollScheme,
+    pub company_cost: f64,
+    pub take_home: f64,
+    pub monthly_tax: f64,
+}
+
+// Nett method: the company pays the PPh 21 on `gross` as a benefit that is
+// not added to the tax base, so the employee takes home the full gross and
+// the company pays gross plus tax. `params.overtime` is paid and taxed on top
+// of `gross`; every other field of `params` except gross_income is used as given
+pub fn calculate_pph21_nett(gross: f64, params: &PPh21Params, config: &TaxConfig) -> SchemeComparison {
+    let at_gross = PPh21Params { gross_income: gross, ..*params };
+    let (_, monthly_tax, _, _, _) = calculate_pph21_progressive(&at_gross, config);
+    
+    SchemeComparison {
+        scheme: PayrollScheme::Nett,
+        company_cost: at_gross.monthly_gross() + monthly_tax,
+        take_home: at_gross.monthly_gross(),
+        monthly_tax,
+    }
+}
+
+// Compare the three schemes for the same agreed monthly salary, with
+// `params.overtime` paid on top under each scheme
+pub fn compare_schemes(salary: f64, params: &PPh21Params, config: &TaxConfig) -> Result<Vec<SchemeComparison>, CalcError> {
+    let nett = calculate_pph21_nett(salary, params, config);
+    let salary_tax = nett.monthly_tax;
+    let monthly_pay = nett.take_home;
+    
+    let gross_up_salary = gross_up(monthly_pay, params, config)?;
+    let gross_up_params = PPh21Params { gross_income: gross_up_salary, ..*params };
+    let (_, gross_up_tax, _, _, _) = calculate_pph21_progressive(&gross_up_params, config);
+    
+    Ok(vec![
+        SchemeComparison {
+            scheme: PayrollScheme::Gross,
+            company_cost: monthly_pay,
+            take_home: monthly_pay - salary_tax,
+            monthly_tax: salary_tax,
+        },
+        SchemeComparison {
+            scheme: PayrollScheme::GrossUp,
+            company_cost: gross_up_params.monthly_gross(),
+            take_home: gross_up_params.monthly_gross() - gross_up_tax,
+            monthly_tax: gross_up_tax,
+        },
+        nett,
+    ])
+}
+
+// Extra cost of the gross-up scheme over the gross scheme for the same agreed
+// salary, as a percentage of the gross scheme's cost; 0 for a zero salary
+pub fn grossup_premium_percent(target_net: f64, params: &PPh21Params, config: &TaxConfig) -> Result<f64, CalcError> {
+    let schemes = compare_schemes(target_net, params, config)?;
+    let cost = |scheme: PayrollScheme| {
+        schemes.iter().find(|comparison| comparison.scheme == scheme).map(|comparison| comparison.company_cost).unwrap_or(0.0)
+    };
+    let gross_cost = cost(PayrollScheme::Gross);
+    if gross_cost == 0.0 {
+        return Ok(0.0);
+    }
+    
+    Ok((cost(PayrollScheme::GrossUp) - gross_cost) / gross_cost * 100.0)
+}
+
+// Format a scheme comparison as a table with one row per scheme
+pub fn render_scheme_comparison(comparisons: &[SchemeComparison], lang: Lang) -> String {
+    let rows: Vec<Vec<TableCell>> = comparisons
+        .iter()
+        .map(|comparison| vec![
+            TableCell::Text(comparison.scheme.label().to_string()),
+            TableCell::Rupiah(comparison.company_cost),
+            TableCell::Rupiah(comparison.take_home),
+            TableCell::Rupiah(comparison.monthly_tax),
+        ])
+        .collect();
+    
+    render_table(&[t("table.scheme", lang), t("label.company_cost", lang), t("table.take_home", lang), t("label.monthly_tax", lang)], &rows)
+}
+
+// Compensation that leaves a target monthly net with the company absorbing
+// the salary's PPh 21 (gross-up), plus a bonus taxed as an irregular payment.
+// `employer_cost` is the year's salary payments plus the bonus, before BPJS
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[non_exhaustive]
+pub struct CompensationPlan {
+    pub required_gross: f64,
+    pub monthly_tax: f64,
+    pub annual_tax: f64,
+    pub bonus: f64,
+    pub bonus_tax: f64,
+    pub employer_cost: f64,
+}
+
+impl CompensationPlan {
+    // What the employee keeps in the year after the salary and bonus tax
+    pub fn annual_net(&self) -> f64 {
+        self.employer_cost - self.annual_tax - self.bonus_tax
+    }
+}
+
+// Gross up the salary to `target_net` a month, then price `bonus` on top of
+// it with calculate_pph21_bonus; the bonus tax is withheld from the bonus, not
+// grossed up. Every field of `params` except gross_income is used as given
+pub fn plan_compensation(target_net: f64, bonus: f64, params: &PPh21Params, config: &TaxConfig) -> Result<CompensationPlan, CalcError> {
+    let required_gross = gross_up(target_net, params, config)?;
+    let salary = PPh21Params { gross_income: required_gross, ..*params };
+    let (annual_tax, monthly_tax, _, _, _) = calculate_pph21_progressive(&salary, config);
+    
+    Ok(CompensationPlan {
+        required_gross,
+        monthly_tax,
+        annual_tax,
+        bonus,
+        bonus_tax: calculate_pph21_bonus(required_gross, bonus, params, config),
+        employer_cost: salary.annual_gross() + bonus,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{assert_rupiah_eq, MockClock};
+
+    #[test]
+    fn test_calculate_pph21_single_no_dependents() {
+        let params = PPh21Params::builder()
+            .gross_income(6_000_000.0)
+            .build()
+            .unwrap();
+        
+        let (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) = calculate_pph21(&params, &TaxConfig::default());
+        
+        // PTKP for TK/0 should be 54,000,000
+        assert_eq!(ptkp, 54_000_000.0);
+        
+        // Biaya jabatan = 5% x 72,000,000 = 3,600,000 (below the cap)
+        assert_eq!(biaya_jabatan, 3_600_000.0);
+        
+        // PKP = (6,000,000 * 12) - 3,600,000 - 54,000,000 = 14,400,000
+        assert_eq!(pkp, 14_400_000.0);
+        
+        // PPh 21 = 0.75% of 6,000,000 = 45,000 per month
+        assert_eq!(monthly_tax, 45_000.0);
+        assert_eq!(annual_tax, 540_000.0);
+    }
+
+    #[test]
+    fn test_calculate_pph21_married_with_dependents() {
+        let params = PPh21Params::builder()
+            .gross_income(10_000_000.0)
+            .married(true)
+            .dependents(2)
+            .build()
+            .unwrap();
+        
+        let (annual_tax, monthly_tax, ptkp, _, _) = calculate_pph21(&params, &TaxConfig::default());
+        
+        // PTKP for K/2 should be 67,500,000
+        assert_eq!(ptkp, 67_500_000.0);
+        
+        // PPh 21 = 0.75% of 10,000,000 = 75,000 per month
+        assert_eq!(monthly_tax, 75_000.0);
+        assert_eq!(annual_tax, 900_000.0);
+    }
+
+    #[test]
+    fn test_results_round_trip_through_toml_and_json() {
+        let params = PPh21Params::builder().gross_income(25_000_000.0).married(true).dependents(2).build().unwrap();
+        let config = TaxConfig::default();
+        // Fractions with no short decimal form must come back bit for bit
+        let result = Pph21Result { monthly_tax: 0.1 + 0.2, annual_tax: 28_975_000.0 / 12.0, ..calculate_pph21_result(&params, Pph21Method::Progressive, &config) };
+        
+        let toml_text = toml::to_string(&result).unwrap();
+        assert!(toml_text.contains("pkp = 226500000.0"), "{}", toml_text);
+        assert_eq!(toml::from_str::<Pph21Result>(&toml_text).unwrap(), result);
+        assert_eq!(serde_json::from_str::<Pph21Result>(&serde_json::to_string(&result).unwrap()).unwrap(), result);
+        
+        let report = full_payroll_with_risk_class(&params, JkkClass::High, &config);
+        assert_eq!(toml::from_str::<PayrollReport>(&toml::to_string(&report).unwrap()).unwrap(), report);
+        let vat = calculate_vat_with_status(99_999.95, VatStatus::Standard(11.0));
+        assert_eq!(toml::from_str::<VatResult>(&toml::to_string(&vat).unwrap()).unwrap(), vat);
+    }
+
+    #[test]
+    fn test_custom_flat_rate_scales_the_tax() {
+        let params = PPh21Params::builder().gross_income(10_000_000.0).build().unwrap();
+        let config = TaxConfig { flat_rate: 1.0, ..TaxConfig::default() };
+        
+        let (default_annual, default_monthly, _, default_pkp, _) = calculate_pph21(&params, &TaxConfig::default());
+        let (annual_tax, monthly_tax, _, pkp, _) = calculate_pph21(&params, &config);
+        
+        // 1% instead of 0.75% of 10,000,000: 100,000 instead of 75,000 a month
+        assert_eq!(monthly_tax, 100_000.0);
+        assert_eq!(monthly_tax / default_monthly, 1.0 / 0.75);
+        assert_eq!(annual_tax / default_annual, 1.0 / 0.75);
+        assert_eq!(pkp, default_pkp);
+        
+        let worksheet = render_gross_worksheet(&params, Pph21Method::Flat, &config, Lang::Id);
+        assert!(worksheet.contains("[Perhitungan PPh 21 (1% x Gaji Bruto)]"), "{}", worksheet);
+        assert_eq!(TaxConfig::from_toml(&format!("flat_rate = 1.0\n{}", include_str!("../tests/fixtures/tax_config.toml"))).unwrap().flat_rate, 1.0);
+    }
+
+    #[test]
+    fn test_flat_monthly_tax_adds_up_to_annual_tax() {
+        let config = TaxConfig::default();
+        for gross_income in [4_500_000.0, 6_666_667.0, 25_500_750.0, 33_333_333.0, 123_456_789.0] {
+            for has_npwp in [true, false] {
+                for months_per_year in [12, 13] {
+                    let params = PPh21Params::builder()
+                        .gross_income(gross_income)
+                        .has_npwp(has_npwp)
+                        .months_per_year(months_per_year)
+                        .build()
+                        .unwrap();
+                    let (annual_tax, monthly_tax, _, _, _) = calculate_pph21(&params, &config);
+                    
+                    assert_eq!(monthly_tax * f64::from(months_per_year), annual_tax, "gross {} npwp {}", gross_income, has_npwp);
+                    assert_eq!(monthly_tax, monthly_tax.round());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gross_up_calculation() {
+        let statuses = [(false, 0), (true, 0), (true, 2), (true, 3)];
+        let net_salaries = [3_000_000.0, 6_000_000.0, 15_000_000.0, 40_000_000.0, 150_000_000.0];
+        
+        for &(is_married, num_dependents) in &statuses {
+            for &net_salary in &net_salaries {
+                let params = PPh21Params::builder().gross_income(net_salary).married(is_married).dependents(num_dependents).build().unwrap();
+                let gross = gross_up(net_salary, &params, &TaxConfig::default()).unwrap();
+                
+                // Taxing the solved gross leaves the requested net, to the cent
+                let (_, monthly_tax, _, _, _) = calculate_pph21_with_method(
+                    &PPh21Params::builder().gross_income(gross).married(is_married).dependents(num_dependents).build().unwrap(),
+                    Pph21Method::Progressive,
+                    &TaxConfig::default(),
+                );
+                assert!(
+                    (gross - monthly_tax - net_salary).abs() < 0.01,
+                    "net {} married {} dependents {}: gross {} tax {}",
+                    net_salary, is_married, num_dependents, gross, monthly_tax
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_gross_up_below_ptkp_is_untaxed() {
+        let params = PPh21Params::builder().gross_income(0.0).married(true).dependents(3).build().unwrap();
+        
+        // Rp 5,000,000 x 12 is below PTKP K/3, so nothing is added
+        assert_eq!(gross_up(5_000_000.0, &params, &TaxConfig::default()).unwrap(), 5_000_000.0);
+    }
+
+    #[test]
+    fn test_gross_up_counts_overtime_in_the_net() {
+        let config = TaxConfig::default();
+        let params = PPh21Params::builder()
+            .overtime(2_000_000.0)
+            .build()
+            .unwrap();
+        
+        // The solved salary plus overtime, less the tax on both, is the net
+        let gross = gross_up(10_000_000.0, &params, &config).unwrap();
+        let with_gross = PPh21Params { gross_income: gross, ..params };
+        let (_, monthly_tax, _, _, _) = calculate_pph21_progressive(&with_gross, &config);
+        assert!((gross + 2_000_000.0 - monthly_tax - 10_000_000.0).abs() < 0.01);
+        
+        // Every scheme pays the overtime on top of the agreed salary
+        let schemes = compare_schemes(8_000_000.0, &params, &config).unwrap();
+        assert_eq!(schemes[0].company_cost, 10_000_000.0);
+        assert!((schemes[1].take_home - 10_000_000.0).abs() < 0.01);
+        assert_eq!(schemes[2].take_home, 10_000_000.0);
+    }
+
+    #[test]
+    fn test_gross_up_follows_the_bracket_table() {
+        let params = PPh21Params::builder().build().unwrap();
+        let hpp = TaxConfig::for_year(2023).unwrap();
+        // Pasal 17 before UU HPP, as applied in 2021: 5% only up to Rp 50,000,000
+        let pre_hpp = TaxConfig {
+            brackets: vec![
+                TaxBracket { lower_bound: 0.0, upper_bound: 50_000_000.0, rate: 0.05 },
+                TaxBracket { lower_bound: 50_000_000.0, upper_bound: 250_000_000.0, rate: 0.15 },
+                TaxBracket { lower_bound: 250_000_000.0, upper_bound: 500_000_000.0, rate: 0.25 },
+                TaxBracket { lower_bound: 500_000_000.0, upper_bound: f64::MAX, rate: 0.30 },
+            ],
+            ..TaxConfig::default()
+        };
+        
+        // Net 15,000,000 for TK/0 puts PKP above Rp 60,000,000, so the 15%
+        // slice from Rp 50,000,000 makes the older table's gross higher
+        let gross_2023 = gross_up(15_000_000.0, &params, &hpp).unwrap();
+        let gross_2021 = gross_up(15_000_000.0, &params, &pre_hpp).unwrap();
+        assert!(gross_2021 > gross_2023, "2021 {} vs 2023 {}", gross_2021, gross_2023);
+        for (gross, config) in [(gross_2023, &hpp), (gross_2021, &pre_hpp)] {
+            let (_, monthly_tax, _, _, _) = calculate_pph21_progressive(&PPh21Params { gross_income: gross, ..params }, config);
+            assert!((gross - monthly_tax - 15_000_000.0).abs() < 0.01);
+        }
+        
+        assert_eq!(gross_up_for_year(15_000_000.0, &params, 2023).unwrap(), gross_2023);
+        assert!(matches!(gross_up_for_year(15_000_000.0, &params, 2021), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_gross_for_effective_rate() {
+        let config = TaxConfig::default();
+        let params = PPh21Params::builder().build().unwrap();
+        
+        for target in [1.0, 5.0, 10.0, 20.0, 30.0] {
+            let gross = gross_for_effective_rate(target, &params, &config).unwrap();
+            let rate = effective_tax_rate(&PPh21Params { gross_income: gross, ..params }, &config);
+            assert!(rate >= target && rate - target < 0.01, "target {}: gross {} rate {}", target, gross, rate);
+        }
+        
+        // Above the 35% top rate, or at it, the target is never reached;
+        // without an NPWP the ceiling is 42%
+        for target in [35.0, 50.0] {
+            assert!(matches!(gross_for_effective_rate(target, &params, &config), Err(CalcError::Unreachable(_))), "{}", target);
+        }
+        for target in [0.0, -1.0, f64::NAN] {
+            assert!(matches!(gross_for_effective_rate(target, &params, &config), Err(CalcError::OutOfRange(_))), "{}", target);
+        }
+        let no_npwp = PPh21Params { has_npwp: false, ..params };
+        let gross = gross_for_effective_rate(36.0, &no_npwp, &config).unwrap();
+        assert!(effective_tax_rate(&PPh21Params { gross_income: gross, ..no_npwp }, &config) >= 36.0);
+    }
+
+    #[test]
+    fn test_gross_up_tolerance() {
+        let params = PPh21Params::builder().build().unwrap();
+        let config = TaxConfig::default();
+        let shortfall = |gross: f64| 50_000_000.0 - (gross - calculate_pph21_progressive(&PPh21Params { gross_income: gross, ..params }, &config).1);
+        
+        let tight = gross_up_with_tolerance(50_000_000.0, &params, &config, 0.000_1).unwrap();
+        let loose = gross_up_with_tolerance(50_000_000.0, &params, &config, 100_000.0).unwrap();
+        
+        // A tight tolerance reaches the exact net; a loose one stops early,
+        // short of the net
+        assert_eq!(shortfall(tight), 0.0);
+        assert_eq!(tight, gross_up(50_000_000.0, &params, &config).unwrap());
+        assert!(loose < tight);
+        assert!(shortfall(loose) > 0.0);
+        
+        // A zero or negative tolerance would never be met
+        assert!(matches!(gross_up_with_tolerance(50_000_000.0, &params, &config, 0.0), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(gross_up_with_tolerance(50_000_000.0, &params, &config, -1.0), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_gross_up_reports_non_convergence_at_the_iteration_cap() {
+        let params = PPh21Params::builder().build().unwrap();
+        let config = TaxConfig::default();
+        
+        // Net 50,000,000 for TK/0 takes 13 iterations
+        match gross_up_with_limit(50_000_000.0, &params, &config, GROSS_UP_TOLERANCE, 3) {
+            Err(CalcError::DidNotConverge { iterations, residual }) => {
+                assert_eq!(iterations, 3);
+                assert!(residual > 0.01, "residual {}", residual);
+            },
+            other => panic!("expected DidNotConverge, got {:?}", other),
+        }
+        assert!(gross_up_with_limit(50_000_000.0, &params, &config, GROSS_UP_TOLERANCE, 20).is_ok());
+        
+    }
+
+    #[test]
+    fn test_gross_up_reports_a_net_no_gross_reaches() {
+        let params = PPh21Params::builder().build().unwrap();
+        // A 100% top bracket taxes every extra rupiah fully, so the net stops
+        // rising once the gross reaches it
+        let config = TaxConfig {
+            brackets: vec![
+                TaxBracket { lower_bound: 0.0, upper_bound: 60_000_000.0, rate: 0.05 },
+                TaxBracket { lower_bound: 60_000_000.0, upper_bound: f64::MAX, rate: 1.0 },
+            ],
+            ..TaxConfig::default()
+        };
+        
+        match gross_up(50_000_000.0, &params, &config) {
+            Err(error @ CalcError::Unreachable(_)) => {
+                assert!(error.to_string().contains("Rp 50.000.000,00"), "{}", error);
+                assert_eq!(error.exit_code(), crate::error::EXIT_INVALID_INPUT);
+            },
+            other => panic!("expected Unreachable, got {:?}", other),
+        }
+        // Without an NPWP the 20% surcharge takes an 85% bracket past 100%
+        let config = TaxConfig {
+            brackets: vec![
+                TaxBracket { lower_bound: 0.0, upper_bound: 60_000_000.0, rate: 0.05 },
+                TaxBracket { lower_bound: 60_000_000.0, upper_bound: f64::MAX, rate: 0.85 },
+            ],
+            ..TaxConfig::default()
+        };
+        assert!(gross_up(50_000_000.0, &params, &config).is_ok());
+        let no_npwp = PPh21Params { has_npwp: false, ..params };
+        assert!(matches!(gross_up(50_000_000.0, &no_npwp, &config), Err(CalcError::Unreachable(_))));
+        
+        // A net the lower bracket covers is still solved
+        let gross = gross_up(8_000_000.0, &params, &config).unwrap();
+        assert!(gross > 8_000_000.0);
+    }
+
+    #[test]
+    fn test_ptkp_values() {
+        let expected = [
+            (PtkpStatus::Tk0, 54_000_000.0),
+            (PtkpStatus::Tk1, 58_500_000.0),
+            (PtkpStatus::Tk2, 63_000_000.0),
+            (PtkpStatus::Tk3, 67_500_000.0),
+            (PtkpStatus::K0, 58_500_000.0),
+            (PtkpStatus::K1, 63_000_000.0),
+            (PtkpStatus::K2, 67_500_000.0),
+            (PtkpStatus::K3, 72_000_000.0),
+            (PtkpStatus::Ki0, 112_500_000.0),
+            (PtkpStatus::Ki1, 117_000_000.0),
+            (PtkpStatus::Ki2, 121_500_000.0),
+            (PtkpStatus::Ki3, 126_000_000.0),
+        ];
+        
+        assert_eq!(expected.len(), PtkpStatus::ALL.len());
+        for (status, ptkp) in expected {
+            assert_eq!(status.ptkp_value(), ptkp, "{}", status);
+            assert_eq!(TaxConfig::default().ptkp(status), ptkp, "{}", status);
+        }
+    }
+
+    #[test]
+    fn test_ptkp_status_labels() {
+        assert_eq!(PtkpStatus::new(false, false, 0), PtkpStatus::Tk0);
+        assert_eq!(PtkpStatus::new(true, false, 5), PtkpStatus::K3);
+        assert_eq!(PtkpStatus::new(true, true, 1), PtkpStatus::Ki1);
+        // K/I needs a married taxpayer
+        assert_eq!(PtkpStatus::new(false, true, 2), PtkpStatus::Tk2);
+        
+        for status in PtkpStatus::ALL {
+            assert_eq!(status.to_string().parse::<PtkpStatus>(), Ok(status));
+        }
+        assert_eq!(PtkpStatus::Ki2.to_string(), "K/I/2");
+        assert_eq!(format!("{:<5}|", PtkpStatus::K1), "K/1  |");
+        assert!("K/4".parse::<PtkpStatus>().is_err());
+    }
+
+    #[test]
+    fn test_selfcheck_passes_on_shipped_tables() {
+        let results = run_selfcheck(&ptkp_table(), &get_tax_brackets(), Lang::Id);
+        
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.passed), "{:?}", results);
+    }
+
+    #[test]
+    fn test_selfcheck_detects_broken_tables() {
+        let mut ptkp = ptkp_table();
+        ptkp.insert(PtkpStatus::K1, 50_000_000.0);
+        let brackets = vec![
+            TaxBracket { lower_bound: 0.0, upper_bound: 50_000_000.0, rate: 0.15 },
+            TaxBracket { lower_bound: 60_000_000.0, upper_bound: f64::MAX, rate: 0.05 },
+        ];
+        
+        let results = run_selfcheck(&ptkp, &brackets, Lang::Id);
+        
+        assert!(results.iter().all(|result| !result.passed), "{:?}", results);
+    }
+
+    #[test]
+    fn test_parse_dependent_ages() {
+        let dependents = Dependents::parse("8,12,15").unwrap();
+        
+        assert_eq!(dependents, Dependents::Ages(vec![8, 12, 15]));
+        assert_eq!(dependents.count(), 3);
+        
+        let params = PPh21Params::builder()
+            .gross_income(10_000_000.0)
+            .married(true)
+            .dependents(dependents.ptkp_count())
+            .build()
+            .unwrap();
+        let (_, _, ptkp, _, _) = calculate_pph21(&params, &TaxConfig::default());
+        
+        // Three dependents -> K/3
+        assert_eq!(ptkp, 72_000_000.0);
+    }
+
+    #[test]
+    fn test_parse_dependents_count_and_cap() {
+        assert_eq!(Dependents::parse("2").unwrap(), Dependents::Count(2));
+        // Non-numeric input is an error naming the entry, not zero dependents
+        match Dependents::parse("abc") {
+            Err(CalcError::Parse(message)) => assert!(message.to_string().contains("'abc' tidak valid"), "{}", message),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert!(matches!(Dependents::parse("8,x,15"), Err(CalcError::Parse(_))));
+        
+        // Four ages still cap at 3 for PTKP
+        let dependents = Dependents::parse("3, 5, 9, 14").unwrap();
+        assert_eq!(dependents.count(), 4);
+        assert_eq!(dependents.ptkp_count(), 3);
+    }
+
+    #[test]
+    fn test_outdated_tables_warning_with_mock_clock() {
+        let future = MockClock(NaiveDate::from_ymd_opt(TAX_TABLES_YEAR + 2, 3, 1).unwrap());
+        let warning = outdated_tables_warning(&future, Lang::Id).expect("future date should warn");
+        assert!(warning.contains(&TAX_TABLES_YEAR.to_string()));
+        assert!(warning.contains(&(TAX_TABLES_YEAR + 2).to_string()));
+        
+        let current = MockClock(NaiveDate::from_ymd_opt(TAX_TABLES_YEAR, 12, 31).unwrap());
+        assert_eq!(outdated_tables_warning(&current, Lang::Id), None);
+    }
+
+    #[test]
+    fn test_vat_status_zero_rated_and_exempt() {
+        let standard = calculate_vat_with_status(1_000_000.0, VatStatus::Standard(11.0));
+        let zero_rated = calculate_vat_with_status(1_000_000.0, VatStatus::ZeroRated);
+        let exempt = calculate_vat_with_status(1_000_000.0, VatStatus::Exempt);
+        
+        assert_eq!(standard.vat, 110_000.0);
+        assert_eq!(zero_rated.vat, 0.0);
+        assert_eq!(exempt.vat, 0.0);
+        assert_eq!(zero_rated.status, VatStatus::ZeroRated);
+        assert_eq!(exempt.status, VatStatus::Exempt);
+        assert_ne!(zero_rated.status, exempt.status);
+    }
+
+    #[test]
+    fn test_compute_ptkp() {
+        assert_eq!(compute_ptkp(false, 0, false, 2023).unwrap(), 54_000_000.0);
+        assert_eq!(compute_ptkp(true, 2, false, 2023).unwrap(), 67_500_000.0);
+        // K/I/1 = K/1 + TK/0
+        assert_eq!(compute_ptkp(true, 1, true, 2023).unwrap(), 117_000_000.0);
+    }
+
+    #[test]
+    fn test_ptkp_table_is_built_once() {
+        // Every lookup reads the same static table, and owned copies match it
+        assert!(std::ptr::eq(builtin_ptkp(), builtin_ptkp()));
+        assert_eq!(ptkp_table(), *builtin_ptkp());
+        assert_eq!(ptkp_table(), ptkp_table());
+        for _ in 0..3 {
+            assert_eq!(compute_ptkp(true, 3, false, 2024).unwrap(), 72_000_000.0);
+            assert_eq!(TaxConfig::default().ptkp(PtkpStatus::K3), 72_000_000.0);
+        }
+    }
+
+    #[test]
+    fn test_compute_ptkp_rejects_invalid_spec() {
+        assert!(compute_ptkp(true, 4, false, 2023).is_err());
+        assert!(compute_ptkp(false, 0, true, 2023).is_err());
+        assert!(compute_ptkp(false, 0, false, 2015).is_err());
+        // Years without built-in tables are rejected the same way as ptkp_values_for_year
+        assert!(compute_ptkp(false, 0, false, 2099).unwrap_err().to_string().contains("2022, 2023, 2024"));
+        assert!(ptkp_values_for_year(2099).is_err());
+    }
+
+    #[test]
+    fn test_vat_with_rate_change_in_april() {
+        // 2022: 10% for January-March, 11% from April
+        let change = RateChange { effective_month: 4, new_rate: 11.0 };
+        let vat = calculate_vat_with_rate_change(12_000_000.0, 10.0, &change);
+        
+        // 3,000,000 x 10% + 9,000,000 x 11%
+        assert_eq!(vat, 1_290_000.0);
+        
+        // A change effective in January applies the new rate all year
+        let january = RateChange { effective_month: 1, new_rate: 11.0 };
+        assert_eq!(calculate_vat_with_rate_change(12_000_000.0, 10.0, &january), 1_320_000.0);
+    }
+
+    #[test]
+    fn test_income_tax_with_bracket_change_in_april() {
+        // Pre-HPP brackets, with the 5% bracket ending at 50,000,000
+        let old_brackets = vec![
+            TaxBracket { lower_bound: 0.0, upper_bound: 50_000_000.0, rate: 0.05 },
+            TaxBracket { lower_bound: 50_000_000.0, upper_bound: 250_000_000.0, rate: 0.15 },
+            TaxBracket { lower_bound: 250_000_000.0, upper_bound: 500_000_000.0, rate: 0.25 },
+            TaxBracket { lower_bound: 500_000_000.0, upper_bound: f64::MAX, rate: 0.30 },
+        ];
+        let new_brackets = get_tax_brackets();
+        
+        // 60,000,000 is taxed 4,000,000 under the old table and 3,000,000 under
+        // the new one: 4,000,000 x 3/12 + 3,000,000 x 9/12
+        let tax = calculate_income_tax_with_bracket_change(60_000_000.0, &old_brackets, &new_brackets, 4);
+        assert_eq!(tax, 3_250_000.0);
+        
+        // A change effective in January applies the new brackets all year
+        assert_eq!(calculate_income_tax_with_bracket_change(60_000_000.0, &old_brackets, &new_brackets, 1), 3_000_000.0);
+    }
+
+    #[test]
+    fn test_quarterly_installments_sum_exactly() {
+        let annual = 10_000_001.0;
+        let quarterly = installments(annual, 4);
+        
+        assert_eq!(quarterly.len(), 4);
+        assert_rupiah_eq(quarterly[0], 2_500_000.0);
+        assert_rupiah_eq(quarterly[3], 2_500_001.0);
+        assert_rupiah_eq(quarterly.iter().sum::<f64>(), annual);
+        assert!(installments(annual, 0).is_empty());
+    }
+
+    #[test]
+    fn test_render_table_aligns_columns() {
+        let rows = vec![
+            vec![TableCell::Text("TK/0".to_string()), TableCell::Rupiah(5.0)],
+            vec![TableCell::Text("K/I/3".to_string()), TableCell::Rupiah(1_234_567_890.0)],
+        ];
+        
+        let table = render_table(&["Status", "Pajak"], &rows);
+        let lines: Vec<&str> = table.lines().collect();
+        
+        assert_eq!(lines.len(), 4);
+        assert!(lines.iter().all(|line| line.len() == lines[0].len()), "{}", table);
+        let divider = lines[0].find('|').unwrap();
+        assert!(lines.iter().skip(2).all(|line| line.find('|') == Some(divider)), "{}", table);
+        assert!(lines[2].ends_with("Rp 5,00"));
+        assert!(lines[3].ends_with("Rp 1.234.567.890,00"));
+    }
+
+    #[test]
+    fn test_format_rupiah() {
+        assert_eq!(format_rupiah(0.0), "Rp 0,00");
+        assert_eq!(format_rupiah(5.0), "Rp 5,00");
+        assert_eq!(format_rupiah(6_000_000.0), "Rp 6.000.000,00");
+        assert_eq!(format_rupiah(1_234_567_890.0), "Rp 1.234.567.890,00");
+        
+        // Fractions are kept to the sen instead of dropped
+        assert_eq!(format_rupiah(45_340.5), "Rp 45.340,50");
+        assert_eq!(format_rupiah(10_999.994_5), "Rp 10.999,99");
+        assert_eq!(format_rupiah(0.125), "Rp 0,13");
+        assert_eq!(format_rupiah(-1_500.75), "-Rp 1.500,75");
+        
+        // Overflowed figures are flagged rather than shown as Rp 0,00
+        assert_eq!(format_rupiah(f64::INFINITY), "Rp (tidak valid)");
+        assert_eq!(format_rupiah(f64::NAN), "Rp (tidak valid)");
+        assert_eq!(format_rupiah(1e300), "Rp (tidak valid)");
+        
+        // The output reads back to the same amount
+        assert_eq!(parse_rupiah(&format_rupiah(45_340.5)).unwrap(), 45_340.5);
+    }
+
+    #[test]
+    fn test_parse_rupiah() {
+        assert_eq!(parse_rupiah("Rp6.000.000").unwrap(), 6_000_000.0);
+        assert_eq!(parse_rupiah("6,000,000").unwrap(), 6_000_000.0);
+        assert_eq!(parse_rupiah("6000000").unwrap(), 6_000_000.0);
+        assert_eq!(parse_rupiah(" Rp 6.000.000 ").unwrap(), 6_000_000.0);
+        assert_eq!(parse_rupiah("rp. 6.000.000,50").unwrap(), 6_000_000.5);
+        assert_eq!(parse_rupiah("Rp6,000,000.50").unwrap(), 6_000_000.5);
+        assert_eq!(parse_rupiah("Rp6.000").unwrap(), 6_000.0);
+        assert_eq!(parse_rupiah("6,5").unwrap(), 6.5);
+        assert_eq!(parse_rupiah("-Rp1.000").unwrap(), -1_000.0);
+        
+        for input in ["", "Rp", "6.00.000", "6.000,000.5", "6,000.000.5", "enam juta"] {
+            assert!(matches!(parse_rupiah(input), Err(CalcError::Parse(_))), "{}", input);
+        }
+        assert!(matches!(parse_amount("-Rp1.000"), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_validate_requested_net() {
+        assert_eq!(validate_requested_net(" 6000000 ").unwrap(), 6_000_000.0);
+        assert!(matches!(validate_requested_net("-6000000"), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(validate_requested_net("0"), Err(CalcError::OutOfRange(_))));
+        assert!(validate_requested_net("inf").is_err());
+        assert!(matches!(validate_requested_net("abc"), Err(CalcError::Parse(_))));
+    }
+
+    #[test]
+    fn test_tax_bracket_contains() {
+        let brackets = get_tax_brackets();
+        
+        assert!(brackets[0].contains(0.0));
+        assert!(brackets[0].contains(59_999_999.0));
+        // Income exactly on an edge: the next rupiah falls in the higher bracket
+        assert!(!brackets[0].contains(60_000_000.0));
+        assert!(brackets[1].contains(60_000_000.0));
+        assert!(brackets[4].contains(10_000_000_000.0));
+    }
+
+    #[test]
+    fn test_tax_bracket_width() {
+        let brackets = get_tax_brackets();
+        
+        assert_eq!(brackets[0].width(), 60_000_000.0);
+        assert_eq!(brackets[1].width(), 190_000_000.0);
+        assert_eq!(brackets[3].width(), 4_500_000_000.0);
+        assert_eq!(brackets[4].width(), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_tax_bracket_tax_in_bracket() {
+        let brackets = get_tax_brackets();
+        let income = 300_000_000.0;
+        
+        assert_eq!(brackets[0].tax_in_bracket(income), 3_000_000.0);
+        assert_eq!(brackets[1].tax_in_bracket(income), 28_500_000.0);
+        assert_eq!(brackets[2].tax_in_bracket(income), 12_500_000.0);
+        assert_eq!(brackets[3].tax_in_bracket(income), 0.0);
+        assert_eq!(brackets[3].tax_in_bracket(600_000_000.0), 30_000_000.0);
+        assert_eq!(calculate_income_tax(income, &brackets), 44_000_000.0);
+    }
+
+    #[test]
+    fn test_tax_bracket_methods_for_mid_bracket_income() {
+        let brackets = get_tax_brackets();
+        // 120,000,000 lies inside the 15% bracket (60,000,000 - 250,000,000)
+        let income = 120_000_000.0;
+        
+        assert!(brackets[1].applies_to(income));
+        assert_eq!(brackets[1].taxable_in_bracket(income), 60_000_000.0);
+        assert_eq!(brackets[1].tax_in_bracket(income), 9_000_000.0);
+        
+        // The bracket below is used in full, the ones above not at all
+        assert_eq!(brackets[0].taxable_in_bracket(income), 60_000_000.0);
+        assert!(!brackets[2].applies_to(income));
+        assert_eq!(brackets[2].tax_in_bracket(income), 0.0);
+        assert_eq!(calculate_income_tax(income, &brackets), 12_000_000.0);
+    }
+
+    #[test]
+    fn test_preset_produces_result() {
+        let config = TaxConfig::default();
+        let preset = config.find_preset("mid-manager").expect("preset should exist");
+        let (annual_tax, monthly_tax, _, _, _) = calculate_pph21(&preset.params().unwrap(), &config);
+        
+        assert!(monthly_tax > 0.0);
+        assert!(annual_tax > 0.0);
+        assert!(render_preset(preset, &config, Lang::Id).unwrap().contains("K/1"));
+        assert!(config.find_preset("unknown").is_none());
+    }
+
+    #[test]
+    fn test_presets_load_from_config() {
+        let fixture = include_str!("../tests/fixtures/tax_config.toml");
+        assert_eq!(TaxConfig::from_toml(fixture).unwrap().presets, builtin_presets());
+        
+        let config = TaxConfig::from_toml(&format!(
+            "{}\n[[presets]]\nname = \"junior\"\ngross_income = 8_000_000.0\n",
+            fixture
+        ))
+        .unwrap();
+        assert_eq!(config.presets.len(), 1);
+        assert_eq!(config.find_preset("junior").unwrap().params().unwrap().gross_income, 8_000_000.0);
+        assert!(config.find_preset("mid-manager").is_none());
+        
+        let invalid = TaxConfig::from_toml(&format!("{}\n[[presets]]\nname = \"bad\"\ngross_income = -1.0\n", fixture));
+        assert!(invalid.unwrap_err().to_string().contains("preset 'bad'"));
+    }
+
+    #[test]
+    fn test_final_tax_categories_load_from_config() {
+        let builtin = TaxConfig::default();
+        assert_eq!(calculate_final_tax(50_000_000.0, "rental", &builtin).unwrap(), 5_000_000.0);
+        assert_eq!(calculate_final_tax(10_000_000.0, "deposit_interest", &builtin).unwrap(), 2_000_000.0);
+        
+        let fixture = include_str!("../tests/fixtures/tax_config.toml");
+        let config = TaxConfig::from_toml(&format!("{}
+[final_taxes]
+rental = 10.0
+lottery_prize = 25.0
+", fixture)).unwrap();
+        assert_eq!(calculate_final_tax(4_000_000.0, "lottery_prize", &config).unwrap(), 1_000_000.0);
+        assert_eq!(calculate_final_tax(50_000_000.0, "rental", &config).unwrap(), 5_000_000.0);
+        assert_ne!(config.fingerprint(), TaxConfig::from_toml(fixture).unwrap().fingerprint());
+        
+        // The file's section replaces the built-in categories
+        let err = calculate_final_tax(1_000_000.0, "dividend", &config).unwrap_err();
+        assert!(matches!(err, CalcError::Parse(_)));
+        assert!(err.to_string().contains("lottery_prize, rental"), "{}", err);
+        
+        let invalid = TaxConfig::from_toml(&format!("{}
+[final_taxes]
+rental = 110.0
+", fixture));
+        assert!(invalid.unwrap_err().to_string().contains("tarif final 'rental' 110%"));
+    }
+
+    #[test]
+    fn test_partial_month_starting_on_16th() {
+        let factor = proration_factor(16, 30);
+        assert_eq!(factor, 0.5);
+        assert_eq!(proration_factor(1, 30), 1.0);
+        
+        let params = PPh21Params::builder()
+            .gross_income(6_000_000.0)
+            .build()
+            .unwrap();
+        
+        // Half of the 45,000 full-month PPh 21
+        assert_eq!(calculate_pph21_partial_month(&params, factor, Pph21Method::Flat, &TaxConfig::default()), 22_500.0);
+        // Progressive: half of the 60,000 full-month PPh 21
+        assert_eq!(calculate_pph21_partial_month(&params, factor, Pph21Method::Progressive, &TaxConfig::default()), 30_000.0);
+        
+        // Annualized figures are unaffected by the partial month
+        let (annual_tax, _, _, pkp, _) = calculate_pph21(&params, &TaxConfig::default());
+        assert_eq!(annual_tax, 540_000.0);
+        assert_eq!(pkp, 14_400_000.0);
+    }
+
+    #[test]
+    fn test_partial_first_month_keeps_the_full_biaya_jabatan_cap() {
+        let config = TaxConfig::default();
+        let month = |gross| PPh21Params::builder().gross_income(gross).months_worked(6).build().unwrap();
+        // Hired on 16 July at 20,000,000 a month: July pays half, then five full months
+        let first_month = 20_000_000.0 * proration_factor(16, 30);
+        let mut months = vec![month(first_month)];
+        months.extend(vec![month(20_000_000.0); 5]);
+        let summary = annual_summary(&months, Pph21Method::Progressive, &config).unwrap();
+        
+        // 5% of 110,000,000 is 5,500,000; the cap is 500,000 for each of the
+        // six months, July included in full since its 10,000,000 pay
+        // already warrants 500,000, not 5.5 x 500,000 = 2,750,000
+        assert_eq!(summary.total_gross, 110_000_000.0);
+        assert_eq!(summary.biaya_jabatan, 3_000_000.0);
+        // PTKP 27,000,000 for six months: PKP 80,000,000 -> 3,000,000 + 20,000,000 x 15%
+        assert_eq!(summary.pkp, 80_000_000.0);
+        assert_eq!(summary.annual_tax, 6_000_000.0);
+    }
+
+    #[test]
+    fn test_days_in_month() {
+        assert_eq!(days_in_month(NaiveDate::from_ymd_opt(2024, 2, 10).unwrap()), 29);
+        assert_eq!(days_in_month(NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()), 31);
+    }
+
+    #[test]
+    fn test_tables_fingerprint_changes_with_tables() {
+        let fingerprint = tables_fingerprint(2023).expect("2023 tables are built in");
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(tables_fingerprint(2023), Some(fingerprint.clone()));
+        assert_eq!(tables_fingerprint(1999), None);
+        
+        let changed = |change: fn(&mut TaxConfig)| {
+            let mut config = TaxConfig::for_year(2023).unwrap();
+            change(&mut config);
+            fingerprint_tables(&config)
+        };
+        assert_eq!(changed(|_| {}), fingerprint);
+        assert_ne!(changed(|config| { config.ptkp.insert(PtkpStatus::K2, 67_500_001.0); }), fingerprint);
+        assert_ne!(changed(|config| config.brackets[0].upper_bound = 50_000_000.0), fingerprint);
+        assert_ne!(changed(|config| config.brackets[3].rate = 0.35), fingerprint);
+        assert_ne!(changed(|config| config.bpjs_jp_salary_cap += 1.0), fingerprint);
+        assert_ne!(changed(|config| config.bpjs_kesehatan_salary_cap = 15_000_000.0), fingerprint);
+        assert_ne!(changed(|config| config.flat_rate = 1.0), fingerprint);
+        assert_ne!(changed(|config| config.rounding = RoundingMode::Down), fingerprint);
+        
+        // The years share PTKP and brackets but not the BPJS JP cap
+        assert_ne!(tables_fingerprint(2022), tables_fingerprint(2024));
+    }
+
+    #[test]
+    fn test_parse_percent_locale_decimal() {
+        assert_eq!(parse_percent("11,5", 11.0).unwrap(), 11.5);
+        assert_eq!(parse_percent("11.5", 11.0).unwrap(), 11.5);
+        assert_eq!(parse_percent(" 12% ", 11.0).unwrap(), 12.0);
+        assert_eq!(parse_percent("", 11.0).unwrap(), 11.0);
+        assert!(matches!(parse_percent("sebelas", 11.0), Err(CalcError::Parse(_))));
+    }
+
+    #[test]
+    fn test_parse_vat_rate_range() {
+        // Empty uses the default, garbage is an error rather than the default
+        assert_eq!(parse_vat_rate("", 11.0).unwrap(), 11.0);
+        assert_eq!(parse_vat_rate("  ", 12.0).unwrap(), 12.0);
+        assert!(matches!(parse_vat_rate("abc", 11.0), Err(CalcError::Parse(_))));
+        
+        assert_eq!(parse_vat_rate("12", 11.0).unwrap(), 12.0);
+        assert_eq!(parse_vat_rate("0", 11.0).unwrap(), 0.0);
+        assert_eq!(parse_vat_rate("100%", 11.0).unwrap(), 100.0);
+        
+        assert!(matches!(parse_vat_rate("-1", 11.0), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(parse_vat_rate("5000", 11.0), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(parse_vat_rate("NaN", 11.0), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_pkp_rounded_down_to_thousand() {
+        assert_eq!(round_down_pkp(58_123_456.0), 58_123_000.0);
+        assert_eq!(round_down_pkp(58_123_000.0), 58_123_000.0);
+        
+        let params = PPh21Params::builder()
+            .gross_income(6_000_100.0)
+            .build()
+            .unwrap();
+        let (_, _, _, pkp, _) = calculate_pph21(&params, &TaxConfig::default());
+        
+        // 72,001,200 - 3,600,060 - 54,000,000 = 14,401,140 -> 14,401,000
+        assert_eq!(pkp, 14_401_000.0);
+    }
+
+    #[test]
+    fn test_dependents_above_sanity_limit_rejected() {
+        let typo = Dependents::parse("99999").unwrap();
+        assert!(typo.validate(DEFAULT_MAX_DEPENDENTS_INPUT).is_err());
+        
+        // Above the PTKP cap but within the sanity limit is accepted (and capped)
+        let large_family = Dependents::parse("5").unwrap().validate(DEFAULT_MAX_DEPENDENTS_INPUT).unwrap();
+        assert_eq!(large_family.ptkp_count(), 3);
+        assert!(large_family.ptkp_notice(Lang::Id).unwrap().contains("5 tanggungan dihitung sebagai 3"));
+        assert_eq!(Dependents::Count(3).ptkp_notice(Lang::Id), None);
+        
+        assert!(matches!(Dependents::parse("5").unwrap().validate(4), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_absurd_gross_income_rejected_before_calculation() {
+        let err = PPh21Params::builder().gross_income(1e300).build().unwrap_err();
+        assert!(matches!(err, CalcError::OutOfRange(_)));
+        assert!(err.to_string().contains("Rp 1.000.000.000.000,00"), "{}", err);
+        assert!(PPh21Params::builder().gross_income(6_000_000.0).overtime(2e12).build().is_err());
+        assert!(validate_requested_net("2000000000000").is_err());
+        
+        // Just under the bound still calculates to finite figures
+        let params = PPh21Params::builder().gross_income(DEFAULT_MAX_MONTHLY_INCOME - 1.0).build().unwrap();
+        let result = calculate_pph21_result(&params, Pph21Method::Progressive, &TaxConfig::default());
+        assert!(result.monthly_tax.is_finite() && result.monthly_tax > 0.0);
+        assert!(result.annual_tax < result.gross_monthly * 12.0);
+    }
+
+    #[test]
+    fn test_request_code_round_trip() {
+        let params = PPh21Params::builder()
+            .gross_income(6_045_340.5)
+            .married(true)
+            .dependents(2)
+            .build()
+            .unwrap();
+        
+        let code = encode_request(&params);
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(decode_request(&code).unwrap(), params);
+        assert!(decode_request("not a code!").is_err());
+        assert!(decode_request(&URL_SAFE_NO_PAD.encode("v9:1:0:0")).is_err());
+    }
+
+    #[test]
+    fn test_request_code_keeps_every_input() {
+        let params = PPh21Params::builder()
+            .gross_income(12_500_000.5)
+            .married(true)
+            .spouse_income_combined(true)
+            .dependents(5)
+            .disability_adjustment(true)
+            .has_npwp(false)
+            .zakat(1_250_000.0)
+            .overtime(750_000.0)
+            .pension_contribution(200_000.0)
+            .months_per_year(13)
+            .months_worked(7)
+            .build()
+            .unwrap();
+        
+        let code = encode_request(&params);
+        assert!(String::from_utf8(URL_SAFE_NO_PAD.decode(&code).unwrap()).unwrap().starts_with("v2:"));
+        assert_eq!(decode_request(&code).unwrap(), params);
+        
+        // v1 codes still decode, with the fields they lack at their defaults
+        let v1 = decode_request(&URL_SAFE_NO_PAD.encode("v1:6000000:1:2:0")).unwrap();
+        assert_eq!(v1, PPh21Params::builder().gross_income(6_000_000.0).married(true).dependents(2).has_npwp(false).build().unwrap());
+    }
+
+    #[test]
+    fn test_request_code_inputs_are_validated() {
+        let decode = |payload: &str| decode_request(&URL_SAFE_NO_PAD.encode(payload));
+        
+        assert!(matches!(decode("v1:100000000:1:99"), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(decode("v1:-5000000:0:0"), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(decode("v1:1e300:0:0"), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(decode("v1:6000000:3:0"), Err(CalcError::Parse(_))));
+        // Within the sanity limit, dependents are capped for PTKP as in the builder
+        assert_eq!(decode("v1:6000000:1:5").unwrap().num_dependents, 3);
+    }
+
+    #[test]
+    fn test_income_tax_with_pkp_rounding() {
+        let brackets = get_tax_brackets();
+        let rounded = calculate_income_tax_with_rounding(68_123_456.0, &brackets, true);
+        let unrounded = calculate_income_tax_with_rounding(68_123_456.0, &brackets, false);
+        
+        // 60,000,000 x 5% + 8,123,000 x 15%
+        assert_eq!(rounded, 4_218_450.0);
+        // The extra 456 is taxed at 15% (68.4) when not rounded
+        assert_eq!(unrounded, 4_218_518.4);
+    }
+
+    #[test]
+    fn test_gross_and_gross_up_worksheets_together() {
+        let params = PPh21Params::builder()
+            .gross_income(6_000_000.0)
+            .build()
+            .unwrap();
+        
+        let output = render_gross_and_gross_up(&params, &TaxConfig::default(), Lang::Id).unwrap();
+        
+        assert!(output.contains("=== HASIL PERHITUNGAN PPh 21 ==="));
+        assert!(output.contains("=== HASIL PERHITUNGAN GROSS UP ==="));
+        assert!(output.contains(&render_gross_worksheet(&params, Pph21Method::Progressive, &TaxConfig::default(), Lang::Id)));
+        assert!(output.contains(&render_gross_up_worksheet(6_000_000.0, gross_up(6_000_000.0, &params, &TaxConfig::default()).unwrap(), &params, &TaxConfig::default(), Lang::Id)));
+    }
+
+    #[test]
+    fn test_check_self_computed() {
+        let params = PPh21Params::builder()
+            .gross_income(6_000_000.0)
+            .build()
+            .unwrap();
+        
+        let matching = check_self_computed(&params, 60_000.0, SELF_CHECK_TOLERANCE, &TaxConfig::default());
+        assert!(matching.matches);
+        assert_eq!(matching.difference, 0.0);
+        
+        let mismatching = check_self_computed(&params, 45_000.0, SELF_CHECK_TOLERANCE, &TaxConfig::default());
+        assert!(!mismatching.matches);
+        assert_eq!(mismatching.expected, 60_000.0);
+        assert_eq!(mismatching.difference, -15_000.0);
+    }
+
+    #[test]
+    fn test_grossup_premium_percent() {
+        let config = TaxConfig::default();
+        let params = PPh21Params::builder().build().unwrap();
+        
+        // Grossing up a 6,000,000 net costs 6,062,992: the absorbed 62,992
+        let premium = grossup_premium_percent(6_000_000.0, &params, &config).unwrap();
+        assert!((premium - 62_992.0 / 6_000_000.0 * 100.0).abs() < 1e-9, "{}", premium);
+        assert!(premium > 0.0);
+        
+        // Below PTKP there is no tax to absorb
+        assert_eq!(grossup_premium_percent(4_000_000.0, &params, &config).unwrap(), 0.0);
+        assert_eq!(grossup_premium_percent(0.0, &params, &config).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_plan_compensation_recovers_the_target_net() {
+        let params = PPh21Params::builder().build().unwrap();
+        let plan = plan_compensation(6_000_000.0, 10_000_000.0, &params, &TaxConfig::default()).unwrap();
+        
+        // Gross 6,062,992 less its PPh 21 of 62,992 leaves the target net
+        assert_eq!(plan.required_gross, 6_062_992.0);
+        assert_eq!(plan.required_gross - plan.monthly_tax, 6_000_000.0);
+        
+        // Salary alone: PKP 72,755,904 - 3,637,795 - 54,000,000 -> 15,118,000,
+        // tax 755,900. With the bonus: 82,755,904 - 4,137,795 - 54,000,000 ->
+        // 24,618,000, tax 1,230,900, so the bonus adds 475,000
+        assert_eq!(plan.annual_tax, 755_900.0);
+        assert_eq!(plan.bonus_tax, 475_000.0);
+        assert_eq!(plan.employer_cost, 72_755_904.0 + 10_000_000.0);
+        
+        // The year's pay less both taxes is twelve target nets plus the bonus
+        // after its tax; the 4 over is the monthly tax rounding
+        assert_eq!(plan.annual_net(), 12.0 * 6_000_000.0 + 10_000_000.0 - 475_000.0 + 4.0);
+    }
+
+    #[test]
+    fn test_gross_up_company_cost_delta() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        let gross_salary = gross_up(6_000_000.0, &params, &TaxConfig::default()).unwrap();
+        assert_eq!(gross_salary, 6_062_992.0);
+        
+        let worksheet = render_gross_up_worksheet(6_000_000.0, gross_salary, &params, &TaxConfig::default(), Lang::Id);
+        let delta_line = worksheet
+            .lines()
+            .find(|line| line.starts_with("Tambahan biaya perusahaan"))
+            .expect("gross-up worksheet should show the company cost delta");
+        
+        // The delta equals the absorbed PPh 21 (62,992)
+        assert!(delta_line.contains("62.992"), "{}", delta_line);
+        assert!(worksheet.contains("PPh 21                    :         Rp 62.992,00"));
+        assert_eq!(company_cost_delta(6_000_000.0, 6_062_992.0), 62_992.0);
+    }
+
+    #[test]
+    fn test_pph21_progressive_first_bracket() {
+        let params = PPh21Params::builder()
+            .gross_income(6_000_000.0)
+            .build()
+            .unwrap();
+        
+        let (annual_tax, monthly_tax, ptkp, pkp, _) = calculate_pph21_progressive(&params, &TaxConfig::default());
+        
+        // PKP 14,400,000 x 5% = 720,000 per year
+        assert_eq!(ptkp, 54_000_000.0);
+        assert_eq!(pkp, 14_400_000.0);
+        assert_eq!(annual_tax, 720_000.0);
+        assert_eq!(monthly_tax, 60_000.0);
+    }
+
+    #[test]
+    fn test_pph21_progressive_multiple_brackets() {
+        let params = PPh21Params::builder()
+            .gross_income(25_000_000.0)
+            .build()
+            .unwrap();
+        
+        let (annual_tax, monthly_tax, _, pkp, _) = calculate_pph21_progressive(&params, &TaxConfig::default());
+        
+        // PKP 240,000,000: 60,000,000 x 5% + 180,000,000 x 15% = 30,000,000
+        assert_eq!(pkp, 240_000_000.0);
+        assert_eq!(annual_tax, 30_000_000.0);
+        assert_eq!(monthly_tax, 2_500_000.0);
+        
+        // The flat method is still available and differs
+        let (flat_annual, _, _, _, _) = calculate_pph21_with_method(&params, Pph21Method::Flat, &TaxConfig::default());
+        assert_eq!(flat_annual, 2_250_000.0);
+    }
+
+    #[test]
+    fn test_pph21_progressive_below_ptkp() {
+        let params = PPh21Params::builder()
+            .gross_income(4_000_000.0)
+            .married(true)
+            .dependents(1)
+            .build()
+            .unwrap();
+        
+        let (annual_tax, monthly_tax, _, pkp, _) = calculate_pph21_progressive(&params, &TaxConfig::default());
+        
+        assert_eq!(pkp, 0.0);
+        assert_eq!(annual_tax, 0.0);
+        assert_eq!(monthly_tax, 0.0);
+    }
+
+    #[test]
+    fn test_biaya_jabatan_capped() {
+        // 5% below the cap: 8,000,000 x 12 x 5% = 4,800,000
+        assert_eq!(calculate_biaya_jabatan(96_000_000.0), 4_800_000.0);
+        // The cap is reached exactly at Rp 10,000,000 per month
+        assert_eq!(calculate_biaya_jabatan(120_000_000.0), 6_000_000.0);
+        
+        let params = PPh21Params::builder()
+            .gross_income(15_000_000.0)
+            .build()
+            .unwrap();
+        let (_, _, _, pkp, biaya_jabatan) = calculate_pph21(&params, &TaxConfig::default());
+        
+        // 5% x 180,000,000 = 9,000,000, capped at 6,000,000
+        assert_eq!(biaya_jabatan, 6_000_000.0);
+        // 180,000,000 - 6,000,000 - 54,000,000 = 120,000,000
+        assert_eq!(pkp, 120_000_000.0);
+    }
+
+    #[test]
+    fn test_ter_category_from_status() {
+        assert_eq!(TerCategory::from_status(false, 0), TerCategory::A);
+        assert_eq!(TerCategory::from_status(false, 1), TerCategory::A);
+        assert_eq!(TerCategory::from_status(true, 0), TerCategory::A);
+        assert_eq!(TerCategory::from_status(false, 2), TerCategory::B);
+        assert_eq!(TerCategory::from_status(true, 1), TerCategory::B);
+        assert_eq!(TerCategory::from_status(true, 2), TerCategory::B);
+        assert_eq!(TerCategory::from_status(true, 3), TerCategory::C);
+        assert_eq!(TerCategory::from_status(true, 5), TerCategory::C);
+    }
+
+    #[test]
+    fn test_ter_year_end_adjustment_completes_the_annual_tax() {
+        let config = TaxConfig::default();
+        let params = PPh21Params::builder().build().unwrap();
+        for salary in [5_000_000.0, 10_000_000.0, 25_000_000.0, 60_000_000.0] {
+            let at_salary = PPh21Params { gross_income: salary, ..params };
+            let ter = calculate_pph21_ter(&at_salary, TerCategory::A, &config);
+            let december = ter_year_end_adjustment(salary, &params, &config);
+            
+            // Eleven TER months plus the December true-up are the year's tax
+            let (annual_tax, _, _, _, _) = calculate_pph21_progressive(&at_salary, &config);
+            assert_eq!(11.0 * ter + december, annual_tax, "salary {}", salary);
+        }
+        
+        // 10,000,000 for TK/0: 3,000,000 for the year less 11 x 200,000
+        assert_eq!(ter_year_end_adjustment(10_000_000.0, &params, &config), 800_000.0);
+        // 7,000,000 for K/0 owes 84,000,000 - 4,200,000 - 58,500,000 = 21,300,000
+        // x 5% = 1,065,000 against 11 x 1.25% x 7,000,000 = 962,500 withheld
+        let married = PPh21Params::builder().married(true).build().unwrap();
+        assert_eq!(ter_year_end_adjustment(7_000_000.0, &married, &config), 102_500.0);
+        
+        // 17,000,000 for K/0: 204,000,000 - 6,000,000 - 58,500,000 = 139,500,000
+        // owes 3,000,000 + 79,500,000 x 15% = 14,925,000, but TER A at 8% took
+        // 11 x 1,360,000 = 14,960,000, so December returns 35,000
+        assert_eq!(ter_year_end_adjustment(17_000_000.0, &married, &config), -35_000.0);
+    }
+
+    #[test]
+    fn test_pph21_period_total_for_a_roster() {
+        let config = TaxConfig::default();
+        let roster = vec![
+            PPh21Params::builder().gross_income(10_000_000.0).build().unwrap(),
+            PPh21Params::builder().gross_income(6_000_000.0).married(true).build().unwrap(),
+        ];
+        
+        // Both are category A: 2% of 10,000,000 plus 0.75% of 6,000,000
+        assert_eq!(pph21_period_total(&roster, 3, &config).unwrap(), 200_000.0 + 45_000.0);
+        assert_eq!(pph21_period_total(&roster, 11, &config).unwrap(), 245_000.0);
+        
+        // December: TK/0 owes 3,000,000 for the year less 11 x 200,000; K/0
+        // owes 72,000,000 - 3,600,000 - 58,500,000 = 9,900,000 x 5% = 495,000,
+        // exactly the 11 x 45,000 already withheld
+        assert_eq!(pph21_period_total(&roster, 12, &config).unwrap(), 800_000.0);
+        
+        assert_eq!(pph21_period_total(&[], 12, &config).unwrap(), 0.0);
+        assert!(matches!(pph21_period_total(&roster, 13, &config), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_pph21_ter_category_a() {
+        let ter = |gross_income: f64| calculate_pph21_ter(
+            &PPh21Params::builder().gross_income(gross_income).build().unwrap(),
+            TerCategory::A,
+            &TaxConfig::default(),
+        );
+        
+        // Upper bound of the 0% bracket is inclusive
+        assert_eq!(ter(5_400_000.0), 0.0);
+        // 1.25% x 7,000,000
+        assert_eq!(ter(7_000_000.0), 87_500.0);
+        // 7% x 15,500,000
+        assert_eq!(ter(15_500_000.0), 1_085_000.0);
+        // Top bracket: 34%
+        assert_eq!(ter(2_000_000_000.0), 680_000_000.0);
+    }
+
+    #[test]
+    fn test_pph21_ter_category_b() {
+        let ter = |gross_income: f64| calculate_pph21_ter(
+            &PPh21Params::builder().gross_income(gross_income).married(true).dependents(1).build().unwrap(),
+            TerCategory::B,
+            &TaxConfig::default(),
+        );
+        
+        assert_eq!(ter(6_200_000.0), 0.0);
+        // 1% x 8,000,000
+        assert_eq!(ter(8_000_000.0), 80_000.0);
+        // 9% x 25,000,000
+        assert_eq!(ter(25_000_000.0), 2_250_000.0);
+    }
+
+    #[test]
+    fn test_pph21_ter_category_c() {
+        let ter = |gross_income: f64| calculate_pph21_ter(
+            &PPh21Params::builder().gross_income(gross_income).married(true).dependents(3).build().unwrap(),
+            TerCategory::C,
+            &TaxConfig::default(),
+        );
+        
+        assert_eq!(ter(6_600_000.0), 0.0);
+        // 6,600,001 falls into the 0.25% bracket
+        assert_eq!(ter(6_600_001.0), 16_500.0);
+        // 1.5% x 10,000,000
+        assert_eq!(ter(10_000_000.0), 150_000.0);
+        // 21% x 70,000,000
+        assert_eq!(ter(70_000_000.0), 14_700_000.0);
+    }
+
+    #[test]
+    fn test_ter_category_table_matches_get_ter_tables() {
+        let tables = get_ter_tables();
+        for category in [TerCategory::A, TerCategory::B, TerCategory::C] {
+            assert!(std::ptr::eq(category.table(), tables[category.label()]));
+        }
+    }
+
+    #[test]
+    fn test_ter_tables_ascending() {
+        for table in get_ter_tables().values() {
+            assert!(table.windows(2).all(|pair| pair[0].0 < pair[1].0 && pair[0].1 < pair[1].1));
+            assert_eq!(table.last(), Some(&(f64::MAX, 34.0)));
+        }
+    }
+
+    #[test]
+    fn test_bukti_potong_number() {
+        let slip = bukti_potong("01.234.567.8-901.000", 3, 2024, 42).unwrap();
+        
+        assert_eq!(slip.number, "1.1-03.24-0000042");
+        assert_eq!(slip.npwp, "012345678901000");
+        assert_eq!(bukti_potong("3171234567890001", 12, 2023, 1).unwrap().number, "1.1-12.23-0000001");
+        assert!(matches!(bukti_potong("3171234567890001", 13, 2023, 1), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(bukti_potong("3171234567890001", 1, 2023, 0), Err(CalcError::OutOfRange(_))));
+        assert_eq!(parse_period("10/2024").unwrap(), (10, 2024));
+        assert!(parse_period("2024-10").is_err());
+    }
+
+    #[test]
+    fn test_bukti_potong_rejects_malformed_npwp() {
+        for npwp in ["", "12345678901234", "12345678901234567", "01.234.567.8-901.00A"] {
+            assert!(matches!(bukti_potong(npwp, 1, 2024, 1), Err(CalcError::Parse(_))), "{}", npwp);
+        }
+    }
+
+    #[test]
+    fn test_pesangon_tax_at_bracket_boundaries() {
+        assert_eq!(calculate_pesangon_tax(50_000_000.0), 0.0);
+        // 50,000,000 x 5%
+        assert_eq!(calculate_pesangon_tax(100_000_000.0), 2_500_000.0);
+        // 2,500,000 + 400,000,000 x 15%
+        assert_eq!(calculate_pesangon_tax(500_000_000.0), 62_500_000.0);
+        // 62,500,000 + 100,000,000 x 25%
+        assert_eq!(calculate_pesangon_tax(600_000_000.0), 87_500_000.0);
+        assert_eq!(calculate_pesangon_tax(50_001_000.0), 50.0);
+        assert!(check_bracket_contiguity(&get_pesangon_brackets()));
+    }
+
+    #[test]
+    fn test_daily_wage_at_threshold_is_untaxed() {
+        assert_eq!(calculate_pph21_daily(450_000.0, 450_000.0, &TaxConfig::default()), 0.0);
+        assert_eq!(calculate_pph21_daily(300_000.0, 4_500_000.0, &TaxConfig::default()), 0.0);
+    }
+
+    #[test]
+    fn test_daily_wage_above_threshold() {
+        // 5% x (500,000 - 450,000)
+        assert_eq!(calculate_pph21_daily(500_000.0, 1_000_000.0, &TaxConfig::default()), 2_500.0);
+    }
+
+    #[test]
+    fn test_daily_wage_once_month_passes_threshold() {
+        // 5% x (300,000 - 150,000 daily PTKP)
+        assert_eq!(calculate_pph21_daily(300_000.0, 4_800_000.0, &TaxConfig::default()), 7_500.0);
+        assert_eq!(calculate_pph21_daily(100_000.0, 4_800_000.0, &TaxConfig::default()), 0.0);
+    }
+
+    #[test]
+    fn test_bpjs_below_ceilings() {
+        let bpjs = calculate_bpjs(6_000_000.0, &TaxConfig::default());
+        
+        assert_eq!(bpjs.kesehatan, 60_000.0);
+        assert_eq!(bpjs.jht, 120_000.0);
+        assert_eq!(bpjs.jp, 60_000.0);
+        assert_eq!(bpjs.total, 240_000.0);
+    }
+
+    #[test]
+    fn test_employer_contributions_for_a_low_salary() {
+        let employer = employer_contributions(5_000_000.0, JkkClass::VeryLow, &TaxConfig::default());
+        
+        // 0.24% + 0.3% + 3.7% + 2% + 4% of 5,000,000, all below the ceilings
+        assert_eq!(employer.jkk, 12_000.0);
+        assert_eq!(employer.jkm, 15_000.0);
+        assert_eq!(employer.jht, 185_000.0);
+        assert_eq!(employer.jp, 100_000.0);
+        assert_eq!(employer.kesehatan, 200_000.0);
+        assert_eq!(employer.total, 512_000.0);
+    }
+
+    #[test]
+    fn test_employer_contributions_capped_for_a_high_salary() {
+        let employer = employer_contributions(25_000_000.0, JkkClass::VeryHigh, &TaxConfig::default());
+        
+        assert_eq!(employer.jkk, 435_000.0);
+        assert_eq!(employer.jht, 925_000.0);
+        // 2% of the 10,042,300 JP ceiling and 4% of the 12,000,000 Kesehatan ceiling
+        assert_eq!(employer.jp, 200_846.0);
+        assert_eq!(employer.kesehatan, 480_000.0);
+        assert_eq!(employer.total, 435_000.0 + 75_000.0 + 925_000.0 + 200_846.0 + 480_000.0);
+        
+        assert_eq!(JkkClass::parse("5"), Ok(JkkClass::VeryHigh));
+        assert!(JkkClass::parse("6").is_err());
+        
+        let params = PPh21Params::builder().gross_income(25_000_000.0).build().unwrap();
+        let report = full_payroll_with_risk_class(&params, JkkClass::VeryHigh, &TaxConfig::default());
+        assert_eq!(report.employer, employer);
+        assert_eq!(report.company_cost, 25_000_000.0 + employer.total);
+        assert_eq!(full_payroll(&params, &TaxConfig::default()).employer.risk_class, JkkClass::VeryLow);
+    }
+
+    #[test]
+    fn test_bpjs_jp_capped_for_high_earners() {
+        let bpjs = calculate_bpjs(25_000_000.0, &TaxConfig::default());
+        
+        // JP: 1% of the 2024 10,042,300 ceiling; Kesehatan: 1% of the 12,000,000 ceiling
+        assert_eq!(bpjs.jp, 100_423.0);
+        assert_eq!(bpjs.kesehatan, 120_000.0);
+        // JHT has no ceiling
+        assert_eq!(bpjs.jht, 500_000.0);
+        assert_eq!(bpjs.total, 720_423.0);
+    }
+
+    #[test]
+    fn test_bpjs_jp_ceiling_follows_the_tax_year() {
+        let config_2022 = TaxConfig::for_year(2022).unwrap();
+        let config_2024 = TaxConfig::for_year(2024).unwrap();
+        
+        // 1% of the 9,077,600 and 10,042,300 JP ceilings
+        assert_eq!(calculate_bpjs(25_000_000.0, &config_2022).jp, 90_776.0);
+        assert_eq!(calculate_bpjs(25_000_000.0, &config_2024).jp, 100_423.0);
+        assert_eq!(employer_contributions(25_000_000.0, JkkClass::VeryLow, &config_2022).jp, 181_552.0);
+        assert!(bpjs_jp_salary_cap_for_year(2099).is_err());
+        
+        // A config file can set its own ceilings
+        let fixture = include_str!("../tests/fixtures/tax_config.toml");
+        let loaded = TaxConfig::from_toml(&format!("bpjs_jp_salary_cap = 11_000_000.0\n{}", fixture)).unwrap();
+        assert_eq!(calculate_bpjs(25_000_000.0, &loaded).jp, 110_000.0);
+        assert_eq!(TaxConfig::from_toml(fixture).unwrap().bpjs_jp_salary_cap, 10_042_300.0);
+    }
+
+    #[test]
+    fn test_trace_lists_each_operation_in_order() {
+        let params = PPh21Params::builder().gross_income(25_000_000.0).married(true).dependents(2).has_npwp(false).build().unwrap();
+        let trace = trace_pph21(&params, &TaxConfig::default(), Lang::Id);
+        
+        let labels: Vec<&str> = trace.steps.iter().map(|step| step.label).collect();
+        assert_eq!(
+            labels,
+            [
+                "Gaji bruto sebulan",
+                "Disetahunkan",
+                "Dikurangi biaya jabatan",
+                "Dikurangi PTKP",
+                "Dibulatkan ke bawah ke ribuan",
+                "Tarif lapisan",
+                "Tarif lapisan",
+                "Tambahan tanpa NPWP",
+                "Dibagi bulan bekerja",
+            ]
+        );
+        // 300,000,000 - 6,000,000 - 67,500,000 = 226,500,000 across two brackets
+        assert_eq!(trace.steps[3].running, 226_500_000.0);
+        assert_eq!(trace.steps[6].running, 27_975_000.0);
+        assert_eq!(trace.steps.last().unwrap().running, trace.result.monthly_tax);
+        assert_eq!(trace.steps[7].running, trace.result.annual_tax);
+        
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["steps"][1]["label"], "Disetahunkan");
+        assert_eq!(json["monthly_tax"], trace.result.monthly_tax);
+    }
+
+    #[test]
+    fn test_nett_costs_the_company_less_than_gross_up() {
+        let params = PPh21Params::builder().build().unwrap();
+        let config = TaxConfig::default();
+        let nett = calculate_pph21_nett(10_000_000.0, &params, &config);
+        let gross_up_cost = gross_up(10_000_000.0, &params, &config).unwrap();
+        
+        // TK/0 on 10,000,000: PKP 60,000,000 x 5% = 3,000,000 a year
+        assert_eq!(nett.monthly_tax, 250_000.0);
+        assert_eq!(nett.take_home, 10_000_000.0);
+        assert_eq!(nett.company_cost, 10_250_000.0);
+        // Gross-up also taxes the allowance itself, so it costs more for the same take-home
+        assert!(gross_up_cost > nett.company_cost, "{} vs {}", gross_up_cost, nett.company_cost);
+        assert_eq!(compare_schemes(10_000_000.0, &params, &config).unwrap()[2], nett);
+    }
+
+    #[test]
+    fn test_compare_schemes_gross_up_costs_more_than_gross() {
+        let params = PPh21Params::builder().married(true).dependents(1).build().unwrap();
+        let comparisons = compare_schemes(15_000_000.0, &params, &TaxConfig::default()).unwrap();
+        
+        let schemes: Vec<PayrollScheme> = comparisons.iter().map(|comparison| comparison.scheme).collect();
+        assert_eq!(schemes, vec![PayrollScheme::Gross, PayrollScheme::GrossUp, PayrollScheme::Nett]);
+        let (gross, gross_up, nett) = (&comparisons[0], &comparisons[1], &comparisons[2]);
+        assert!(gross_up.company_cost > gross.company_cost);
+        assert_eq!(gross.take_home, 15_000_000.0 - gross.monthly_tax);
+        // Both company-borne schemes leave the employee the agreed salary
+        assert_rupiah_eq(gross_up.take_home, 15_000_000.0);
+        assert_eq!(nett.take_home, 15_000_000.0);
+        assert_eq!(nett.company_cost, 15_000_000.0 + nett.monthly_tax);
+        
+        let table = render_scheme_comparison(&comparisons, Lang::Id);
+        assert!(table.starts_with("Skema    | Biaya Perusahaan"), "{}", table);
+        assert_eq!(table.lines().count(), 5);
+    }
+
+    #[test]
+    fn test_render_markdown_table() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        let config = TaxConfig::default();
+        
+        let result = calculate_pph21_result(&params, Pph21Method::Progressive, &config);
+        let markdown = render_markdown_table(&pph21_result_rows(&result, Lang::Id), Lang::Id);
+        assert!(markdown.starts_with("| Komponen | Jumlah |\n|---|---:|\n"), "{}", markdown);
+        assert!(markdown.contains("| PKP | Rp 14.400.000,00 |"), "{}", markdown);
+        assert!(markdown.contains("| PPh 21 Sebulan | Rp 60.000,00 |"), "{}", markdown);
+        
+        let markdown = render_markdown_table(&payroll_rows(&full_payroll(&params, &config), Lang::Id), Lang::Id);
+        assert!(markdown.contains("| BPJS JHT (-) | Rp 120.000,00 |"), "{}", markdown);
+        // 6,000,000 - 240,000 BPJS - 60,000 PPh 21
+        assert!(markdown.contains("| Take Home Pay | Rp 5.700.000,00 |"), "{}", markdown);
+    }
+
+    #[test]
+    fn test_zakat_lowers_pkp_and_tax() {
+        let builder = PPh21Params::builder().gross_income(10_000_000.0);
+        let without_zakat = builder.clone().build().unwrap();
+        let with_zakat = builder.zakat(3_000_000.0).build().unwrap();
+        let config = TaxConfig::default();
+        
+        // 120,000,000 - 6,000,000 - 54,000,000 = 60,000,000, less 3,000,000 zakat
+        let (tax_without, _, _, pkp_without, _) = calculate_pph21_progressive(&without_zakat, &config);
+        let (tax_with, _, _, pkp_with, _) = calculate_pph21_progressive(&with_zakat, &config);
+        assert_eq!(pkp_without, 60_000_000.0);
+        assert_eq!(pkp_with, 57_000_000.0);
+        assert_eq!(tax_without, 3_000_000.0);
+        assert_eq!(tax_with, 2_850_000.0);
+        
+        let worksheet = render_gross_worksheet(&with_zakat, Pph21Method::Progressive, &config, Lang::Id);
+        assert!(worksheet.contains("Zakat               :      Rp 3.000.000,00 (-)"), "{}", worksheet);
+        assert!(PPh21Params::builder().zakat(-1.0).build().is_err());
+    }
+
+    #[test]
+    fn test_disability_adjustment_counts_dependents_beyond_three() {
+        let builder = PPh21Params::builder().gross_income(20_000_000.0).married(true).dependents(5);
+        let standard = builder.clone().build().unwrap();
+        let adjusted = builder.disability_adjustment(true).build().unwrap();
+        let config = TaxConfig::default();
+        
+        // Without the flag the dependents are capped at K/3
+        assert_eq!(standard.ptkp_label(), "K/3");
+        assert_eq!(standard.annual_ptkp(&config), 72_000_000.0);
+        
+        // Two extra dependents at Rp 4,500,000 each on top of K/3
+        assert_eq!(adjusted.ptkp_label(), "K/3+2");
+        assert_eq!(adjusted.annual_ptkp(&config), 81_000_000.0);
+        let (_, _, ptkp, pkp, _) = calculate_pph21_progressive(&adjusted, &config);
+        let (_, _, _, standard_pkp, _) = calculate_pph21_progressive(&standard, &config);
+        assert_eq!(ptkp, 81_000_000.0);
+        assert_eq!(standard_pkp - pkp, 9_000_000.0);
+        
+        let worksheet = render_gross_worksheet(&adjusted, Pph21Method::Progressive, &config, Lang::Id);
+        assert!(worksheet.contains("Status K/3+2:     Rp 81.000.000,00 per tahun"), "{}", worksheet);
+        
+        // Up to three dependents the flag changes nothing
+        let small_family = PPh21Params::builder().married(true).dependents(2).disability_adjustment(true).build().unwrap();
+        assert_eq!(small_family.ptkp_label(), "K/2");
+        assert_eq!(small_family.annual_ptkp(&config), 67_500_000.0);
+    }
+
+    #[test]
+    fn test_pension_contribution_lowers_pkp() {
+        let builder = PPh21Params::builder().gross_income(10_000_000.0);
+        let without_pension = builder.clone().build().unwrap();
+        let with_pension = builder.pension_contribution(200_000.0).build().unwrap();
+        let config = TaxConfig::default();
+        
+        // 120,000,000 - 6,000,000 - 2,400,000 pension - 54,000,000 = 57,600,000
+        let (_, _, _, pkp_without, _) = calculate_pph21_progressive(&without_pension, &config);
+        let (tax_with, _, _, pkp_with, biaya_jabatan) = calculate_pph21_progressive(&with_pension, &config);
+        assert_eq!(pkp_without, 60_000_000.0);
+        assert_eq!(pkp_with, 57_600_000.0);
+        assert_eq!(biaya_jabatan, 6_000_000.0);
+        assert_eq!(tax_with, 2_880_000.0);
+        
+        let worksheet = render_gross_worksheet(&with_pension, Pph21Method::Progressive, &config, Lang::Id);
+        assert!(worksheet.contains("Iuran Pensiun       :      Rp 2.400.000,00 (-)"), "{}", worksheet);
+        assert!(worksheet.contains("Gaji Setahun - Biaya Jabatan - Iuran Pensiun - PTKP:"), "{}", worksheet);
+        assert!(PPh21Params::builder().pension_contribution(-1.0).build().is_err());
+    }
+
+    #[test]
+    fn test_pension_contribution_does_not_push_pkp_below_zero() {
+        let params = PPh21Params::builder().gross_income(5_000_000.0).pension_contribution(4_000_000.0).build().unwrap();
+        let (annual_tax, monthly_tax, _, pkp, _) = calculate_pph21_progressive(&params, &TaxConfig::default());
+        
+        assert_eq!(pkp, 0.0);
+        assert_eq!(annual_tax, 0.0);
+        assert_eq!(monthly_tax, 0.0);
+    }
+
+    #[test]
+    fn test_overtime_is_taxed_with_the_salary() {
+        let builder = PPh21Params::builder().gross_income(6_000_000.0);
+        let without_overtime = builder.clone().build().unwrap();
+        let with_overtime = builder.overtime(1_000_000.0).build().unwrap();
+        let config = TaxConfig::default();
+        
+        // 84,000,000 - 4,200,000 - 54,000,000 = 25,800,000 x 5% = 1,290,000
+        let (_, monthly_without, _, _, _) = calculate_pph21_progressive(&without_overtime, &config);
+        let (annual_with, monthly_with, _, pkp_with, biaya_jabatan_with) = calculate_pph21_progressive(&with_overtime, &config);
+        assert_eq!(with_overtime.annual_gross(), 84_000_000.0);
+        assert_eq!(biaya_jabatan_with, 4_200_000.0);
+        assert_eq!(pkp_with, 25_800_000.0);
+        assert_eq!(annual_with, 1_290_000.0);
+        assert_eq!(monthly_with - monthly_without, 47_500.0);
+        
+        let worksheet = render_gross_worksheet(&with_overtime, Pph21Method::Progressive, &config, Lang::Id);
+        assert!(worksheet.contains("Uang Lembur Setahun :     Rp 12.000.000,00 (+)"), "{}", worksheet);
+        assert!(PPh21Params::builder().overtime(-1.0).build().is_err());
+    }
+
+    #[test]
+    fn test_thirteenth_month_salary_raises_annual_tax() {
+        let builder = PPh21Params::builder().gross_income(10_000_000.0);
+        let twelve = builder.clone().build().unwrap();
+        let thirteen = builder.months_per_year(13).build().unwrap();
+        let config = TaxConfig::default();
+        
+        assert_eq!(twelve.annual_gross(), 120_000_000.0);
+        assert_eq!(thirteen.annual_gross(), 130_000_000.0);
+        
+        // 130,000,000 - 6,000,000 - 54,000,000 = 70,000,000
+        let (tax_twelve, _, _, pkp_twelve, _) = calculate_pph21_progressive(&twelve, &config);
+        let (tax_thirteen, _, _, pkp_thirteen, _) = calculate_pph21_progressive(&thirteen, &config);
+        assert_eq!(pkp_twelve, 60_000_000.0);
+        assert_eq!(pkp_thirteen, 70_000_000.0);
+        assert_eq!(tax_twelve, 3_000_000.0);
+        assert_eq!(tax_thirteen, 4_500_000.0);
+        
+        // The flat rate applies to the larger annual gross as well
+        assert_eq!(calculate_pph21(&twelve, &config).0, 900_000.0);
+        assert_eq!(calculate_pph21(&thirteen, &config).0, 975_000.0);
+        
+        let worksheet = render_gross_worksheet(&thirteen, Pph21Method::Progressive, &config, Lang::Id);
+        assert!(worksheet.contains("Penghasilan Bruto setahun:     Rp 130.000.000,00"), "{}", worksheet);
+        assert!(PPh21Params::builder().months_per_year(14).build().is_err());
+    }
+
+    #[test]
+    fn test_full_year_of_work_matches_default() {
+        let builder = PPh21Params::builder().gross_income(10_000_000.0).married(true).dependents(1);
+        let config = TaxConfig::default();
+        
+        assert_eq!(
+            calculate_pph21_progressive(&builder.clone().months_worked(12).build().unwrap(), &config),
+            calculate_pph21_progressive(&builder.build().unwrap(), &config),
+        );
+    }
+
+    #[test]
+    fn test_mid_year_start_prorates_ptkp_and_biaya_jabatan() {
+        // Started in July: 6 months of 10,000,000
+        let params = PPh21Params::builder().gross_income(10_000_000.0).months_worked(6).build().unwrap();
+        let (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) = calculate_pph21_progressive(&params, &TaxConfig::default());
+        
+        assert_eq!(params.annual_gross(), 60_000_000.0);
+        // 54,000,000 x 6/12 and the Rp 500,000 monthly cap x 6
+        assert_eq!(ptkp, 27_000_000.0);
+        assert_eq!(biaya_jabatan, 3_000_000.0);
+        // 60,000,000 - 3,000,000 - 27,000,000
+        assert_eq!(pkp, 30_000_000.0);
+        assert_eq!(annual_tax, 1_500_000.0);
+        assert_eq!(monthly_tax, 250_000.0);
+        
+        assert!(PPh21Params::builder().months_worked(0).build().is_err());
+        assert!(PPh21Params::builder().months_worked(13).build().is_err());
+    }
+
+    #[test]
+    fn test_full_payroll_net_is_gross_minus_deductions() {
+        let params = PPh21Params::builder().gross_income(25_000_000.0).married(true).dependents(2).build().unwrap();
+        let report = full_payroll(&params, &TaxConfig::default());
+        
+        // PKP 300,000,000 - 6,000,000 - 67,500,000 = 226,500,000
+        assert_eq!(report.biaya_jabatan, 6_000_000.0);
+        assert_eq!(report.ptkp, 67_500_000.0);
+        assert_eq!(report.pkp, 226_500_000.0);
+        assert_eq!(report.net_monthly, report.gross_monthly - report.bpjs.total - report.monthly_tax);
+        assert_eq!(report.net_monthly, 25_000_000.0 - 720_423.0 - report.monthly_tax);
+        
+        let table = render_payroll(&report, Lang::Id);
+        assert!(table.contains("Take Home Pay"), "{}", table);
+        assert!(table.contains(&format_rupiah(report.net_monthly)), "{}", table);
+    }
+
+    #[test]
+    fn test_gross_net_of_bpjs_is_not_deducted_again() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        let config = TaxConfig::default();
+        let included = full_payroll_with_risk_class(&params, JkkClass::default(), &config);
+        let net_of_bpjs = full_payroll_net_of_bpjs(&params, JkkClass::default(), &config);
+        
+        // Same PPh 21 either way; only the 240,000 BPJS share is left in
+        assert_eq!(net_of_bpjs.monthly_tax, included.monthly_tax);
+        assert_eq!(included.net_monthly, 6_000_000.0 - 240_000.0 - 60_000.0);
+        assert_eq!(net_of_bpjs.net_monthly, 6_000_000.0 - 60_000.0);
+        assert_eq!(net_of_bpjs.net_monthly - included.net_monthly, included.bpjs.total);
+        assert_eq!(net_of_bpjs.bpjs.total, 0.0);
+        assert_eq!(net_of_bpjs.net_before_deductions(), net_of_bpjs.net_monthly);
+    }
+
+    #[test]
+    fn test_post_tax_deductions_come_off_the_take_home_pay() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        let deductions = vec![parse_post_tax_deduction("Kasbon=500000").unwrap(), parse_post_tax_deduction("Cicilan koperasi=Rp250.000").unwrap()];
+        let report = full_payroll(&params, &TaxConfig::default()).with_post_tax_deductions(deductions);
+        
+        // 6,000,000 - 240,000 BPJS - 60,000 PPh 21 = 5,700,000, less 750,000
+        assert_eq!(report.net_before_deductions(), 5_700_000.0);
+        assert_eq!(report.post_tax_deductions_total(), 750_000.0);
+        assert_eq!(report.net_monthly, 4_950_000.0);
+        assert!(!report.deductions_exceed_net());
+        let table = render_payroll(&report, Lang::Id);
+        let kasbon = table.find("Kasbon").unwrap();
+        assert!(table.find("PPh 21 Sebulan (-)").unwrap() < kasbon && kasbon < table.find("Take Home Pay").unwrap(), "{}", table);
+        assert!(table.contains("Cicilan koperasi") && table.contains(&format_rupiah(250_000.0)), "{}", table);
+        
+        // More than the pay leaves Rp 0 rather than a negative take-home
+        let report = full_payroll(&params, &TaxConfig::default()).with_post_tax_deductions(vec![("Kasbon".to_string(), 6_000_000.0)]);
+        assert_eq!(report.net_monthly, 0.0);
+        assert!(report.deductions_exceed_net());
+        
+        assert!(parse_post_tax_deduction("Kasbon").is_err());
+        assert!(parse_post_tax_deduction("=500000").is_err());
+        assert!(parse_post_tax_deduction("Kasbon=-5").is_err());
+    }
+
+    #[test]
+    fn test_result_display_shows_ptkp_and_pkp() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        let config = TaxConfig::default();
+        
+        let result = calculate_pph21_result(&params, Pph21Method::Progressive, &config).to_string();
+        assert!(result.lines().any(|line| line.starts_with("PTKP ") && line.ends_with("Rp 54.000.000,00")), "{}", result);
+        assert!(result.lines().any(|line| line.starts_with("PKP ") && line.ends_with("Rp 14.400.000,00")), "{}", result);
+        
+        let report = full_payroll(&params, &config);
+        assert_eq!(report.to_string(), render_payroll(&report, Lang::Id));
+        assert!(report.to_string().lines().any(|line| line.starts_with("PTKP (-)")), "{}", report);
+        
+        let vat = calculate_vat_with_status(1_000_000.0, VatStatus::Standard(11.0));
+        assert!(vat.to_string().contains("PPN:        Rp 110.000,00"), "{}", vat);
+    }
+
+    #[test]
+    fn test_gross_worksheet_shows_take_home_pay() {
+        let params = PPh21Params::builder()
+            .gross_income(6_000_000.0)
+            .build()
+            .unwrap();
+        let worksheet = render_gross_worksheet(&params, Pph21Method::Progressive, &TaxConfig::default(), Lang::Id);
+        
+        // 6,000,000 - 240,000 BPJS - 60,000 PPh 21
+        assert!(worksheet.contains("BPJS JHT (2%)       :        Rp 120.000,00 (-)"), "{}", worksheet);
+        assert!(worksheet.contains("Take Home Pay       :      Rp 5.700.000,00"), "{}", worksheet);
+    }
+
+    #[test]
+    fn test_pph21_result_json_round_trip() {
+        let params = PPh21Params::builder()
+            .gross_income(6_000_000.0)
+            .build()
+            .unwrap();
+        let json = serde_json::to_string(&calculate_pph21_result(&params, Pph21Method::Progressive, &TaxConfig::default())).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        
+        assert_eq!(parsed["gross_monthly"], 6_000_000.0);
+        assert_eq!(parsed["ptkp"], 54_000_000.0);
+        assert_eq!(parsed["pkp"], 14_400_000.0);
+        assert_eq!(parsed["annual_tax"], 720_000.0);
+        assert_eq!(parsed["monthly_tax"], 60_000.0);
+    }
+
+    #[test]
+    fn test_vat_result_json() {
+        let json = serde_json::to_string(&calculate_vat_with_status(100_000.0, VatStatus::Standard(11.0))).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        
+        assert_eq!(parsed["status"]["standard"], 11.0);
+        assert_eq!(parsed["base"], 100_000.0);
+        assert_eq!(parsed["vat"], 11_000.0);
+    }
+
+    #[test]
+    fn test_tax_config_overrides_tables() {
+        let config = TaxConfig::from_toml(include_str!("../tests/fixtures/tax_config.toml")).unwrap();
+        assert_eq!(config.brackets.last().map(|bracket| bracket.upper_bound), Some(f64::MAX));
+        
+        let params = PPh21Params::builder()
+            .gross_income(6_000_000.0)
+            .build()
+            .unwrap();
+        let (annual_tax, monthly_tax, ptkp, pkp, _) = calculate_pph21_progressive(&params, &config);
+        
+        // TK/0 raised to 60,000,000 and a 10% first bracket:
+        // 72,000,000 - 3,600,000 - 60,000,000 = 8,400,000 x 10%
+        assert_eq!(ptkp, 60_000_000.0);
+        assert_eq!(pkp, 8_400_000.0);
+        assert_eq!(annual_tax, 840_000.0);
+        assert_eq!(monthly_tax, 70_000.0);
+        
+        assert!(TaxConfig::from_toml("ptkp = 1").is_err());
+    }
+
+    #[test]
+    fn test_compare_years_has_one_entry_per_year() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        let results = compare_years(&params, &[2024, 2022, 2019, 2023]);
+        
+        let years: Vec<u16> = results.iter().map(|result| result.year).collect();
+        assert_eq!(years, [2024, 2022, 2019, 2023]);
+        assert!(results[2].result.as_ref().unwrap_err().to_string().contains("2019"));
+        assert_eq!(results[0].result.as_ref().unwrap().monthly_tax, 60_000.0);
+        
+        let table = render_year_comparison(&results, Lang::Id);
+        assert_eq!(table.lines().count(), 2 + results.len());
+        assert!(table.lines().any(|line| line.starts_with("2019") && line.contains(" - ")));
+    }
+
+    #[test]
+    fn test_annual_summary_reconciles_flat_withholding() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        let summary = recurring_annual_summary(&params, Pph21Method::Flat, &TaxConfig::default());
+        
+        // 72,000,000 - 3,600,000 - 54,000,000 = 14,400,000 x 5% = 720,000 due,
+        // against 12 x 0.75% x 6,000,000 = 540,000 withheld
+        assert_eq!(summary.total_gross, 72_000_000.0);
+        assert_eq!(summary.pkp, 14_400_000.0);
+        assert_eq!(summary.annual_tax, 720_000.0);
+        assert_eq!(summary.total_withheld, 540_000.0);
+        assert_eq!(summary.reconciliation(), 180_000.0);
+        
+        let output = render_annual_summary(&summary, Lang::Id);
+        assert!(output.contains(&format!("{:<27}: {:>20}", "PPh 21 Kurang Bayar", format_rupiah(180_000.0))));
+    }
+
+    #[test]
+    fn test_non_employee_rate_rises_with_cumulative_gross() {
+        let config = TaxConfig::default();
+        let tax_on_next = |prior: f64| calculate_pph21_non_employee(10_000_000.0, prior + 10_000_000.0, &config);
+        
+        // Each Rp 10,000,000 honorarium at the bracket its cumulative total falls in
+        assert_eq!(tax_on_next(0.0), 500_000.0);
+        assert_eq!(tax_on_next(60_000_000.0), 1_500_000.0);
+        assert_eq!(tax_on_next(300_000_000.0), 2_500_000.0);
+        assert_eq!(tax_on_next(600_000_000.0), 3_000_000.0);
+        // A payment crossing a boundary is split between the two rates
+        assert_eq!(tax_on_next(55_000_000.0), 250_000.0 + 750_000.0);
+    }
+
+    #[test]
+    fn test_non_employee_payments_add_up_to_the_tax_on_the_total() {
+        let config = TaxConfig::default();
+        let payments = [40_000_000.0, 75_000_000.0, 150_000_000.0, 300_000_000.0];
+        let mut cumulative = 0.0;
+        let mut withheld = 0.0;
+        for payment in payments {
+            cumulative += payment;
+            withheld += calculate_pph21_non_employee(payment, cumulative, &config);
+        }
+        
+        assert_eq!(withheld, calculate_income_tax(cumulative, &config.brackets));
+        // No biaya jabatan or PTKP: the first payment is taxed from the first rupiah
+        assert_eq!(calculate_pph21_non_employee(1_000_000.0, 1_000_000.0, &config), 50_000.0);
+    }
+
+    #[test]
+    fn test_director_salary_and_quarterly_fees() {
+        let config = TaxConfig::default();
+        let params = PPh21Params::builder().gross_income(20_000_000.0).build().unwrap();
+        let withholding = director_withholding(&params, &[50_000_000.0; 4], &config);
+        
+        // Salary: 240,000,000 - 6,000,000 - 54,000,000 = 180,000,000, taxed
+        // 3,000,000 + 120,000,000 x 15%
+        assert_eq!(withholding.salary_tax, 21_000_000.0);
+        // Fees: the second quarter crosses Rp 60,000,000 (500,000 + 6,000,000),
+        // the rest fall at 15%
+        assert_eq!(withholding.fee_taxes, vec![2_500_000.0, 6_500_000.0, 7_500_000.0, 7_500_000.0]);
+        assert_eq!(withholding.fee_tax(), calculate_income_tax(200_000_000.0, &config.brackets));
+        assert_eq!(withholding.total(), 21_000_000.0 + 24_000_000.0);
+        
+        assert_eq!(director_withholding(&params, &[], &config).total(), 21_000_000.0);
+    }
+
+    #[test]
+    fn test_december_withholds_the_shortfall() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+        
+        // 720,000 due for the year; 11 x 45,000 at the flat rate is 495,000
+        assert_eq!(calculate_pph21_december(495_000.0, &params, &TaxConfig::default()), 225_000.0);
+        assert_eq!(calculate_pph21_december(660_000.0, &params, &TaxConfig::default()), 60_000.0);
+    }
+
+    #[test]
+    fn test_december_returns_over_withholding() {
+        let params = PPh21Params::builder().gross_income(6_000_000.0).married(true).dependents(1).build().unwrap();
+        
+        // K/1: 72,000,000 - 3,600,000 - 63,000,000 = 5,400,000 x 5% = 270,000 due
+        assert_eq!(calculate_pph21_december(495_000.0, &params, &TaxConfig::default()), -225_000.0);
+        assert_eq!(calculate_pph21_december(270_000.0, &params, &TaxConfig::default()), 0.0);
+    }
+
+    #[test]
+    fn test_annual_return_credits_withholding_and_reports_final_tax_apart() {
+        let config = TaxConfig::default();
+        let inputs = AnnualReturnInputs {
+            salary: PPh21Params::builder().gross_income(10_000_000.0).build().unwrap(),
+            other_net_income: 0.0,
+            final_income: vec![FinalIncome { object: PphFinal42Object::Rental, gross: 50_000_000.0 }],
+            credits: TaxCredits { pph21: 2_750_000.0, ..TaxCredits::default() },
+        };
+        let filed = annual_return(&inputs, &config);
+        
+        // 120,000,000 - 6,000,000 - 54,000,000 = 60,000,000 x 5% = 3,000,000,
+        // less 2,750,000 withheld; the rental is not part of the PKP
+        assert_eq!(filed.net_income, 114_000_000.0);
+        assert_eq!(filed.pkp, 60_000_000.0);
+        assert_eq!(filed.progressive_tax, 3_000_000.0);
+        assert_eq!(filed.tax_due(), 250_000.0);
+        // 10% final tax on the rent, on top of the progressive tax
+        assert_eq!(filed.final_tax, 5_000_000.0);
+        assert_eq!(filed.total_tax(), 8_000_000.0);
+        
+        // Other income joins the PKP; credits above the tax are a refund
+        let filed = annual_return(&AnnualReturnInputs {
+            other_net_income: 20_000_000.0,
+            credits: TaxCredits { pph21: 3_000_000.0, pph22: 0.0, pph23: 400_000.0, pph25: 3_000_000.0 },
+            ..inputs
+        }, &config);
+        // 60,000,000 x 5% + 20,000,000 x 15%
+        assert_eq!(filed.progressive_tax, 6_000_000.0);
+        assert_eq!(filed.tax_due(), -400_000.0);
+    }
+
+    #[test]
+    fn test_withholding_slips_feed_the_annual_return_credits() {
+        let config = TaxConfig::default();
+        let salary = PPh21Params::builder().gross_income(10_000_000.0).build().unwrap();
+        let slips = [
+            pph21_withholding(&salary, &config),
+            // 2% of a 10,000,000 consulting fee, twice
+            pph23_withholding(10_000_000.0, Pph23Object::Services, true, &config),
+            pph23_withholding(10_000_000.0, Pph23Object::Services, true, &config),
+            Withholding { kind: WithholdingKind::Pph22, gross: 50_000_000.0, withheld: 750_000.0 },
+        ];
+        assert_eq!(slips[1].withheld, 200_000.0);
+        
+        let credits = TaxCredits::from_withholdings(&slips);
+        assert_eq!(credits, TaxCredits { pph21: 3_000_000.0, pph22: 750_000.0, pph23: 400_000.0, pph25: 0.0 });
+        
+        // The consulting fees add 20,000,000 x 15% = 3,000,000 to the 3,000,000
+        // on the salary; the credits leave 1,850,000 to pay
+        let filed = annual_return(&AnnualReturnInputs {
+            salary,
+            other_net_income: 20_000_000.0,
+            final_income: Vec::new(),
+            credits,
+        }, &config);
+        assert_eq!(filed.credits, 4_150_000.0);
+        assert_eq!(filed.tax_due(), 6_000_000.0 - 4_150_000.0);
+    }
+
+    #[test]
+    fn test_annual_summary_of_varying_months() {
+        let month = |gross| PPh21Params::builder().gross_income(gross).build().unwrap();
+        let mut months = vec![month(6_000_000.0); 11];
+        months.push(month(30_000_000.0));
+        let summary = annual_summary(&months, Pph21Method::Progressive, &TaxConfig::default()).unwrap();
+        
+        // A December raise is taxed progressively on the year's total, but
+        // each month withheld on its own pay annualized: overwithheld
+        assert_eq!(summary.total_gross, 96_000_000.0);
+        assert_eq!(summary.pkp, 37_200_000.0);
+        assert_eq!(summary.annual_tax, 1_860_000.0);
+        assert!(summary.reconciliation() < 0.0);
+        let refund = -summary.reconciliation();
+        let rendered = render_annual_summary(&summary, Lang::Id);
+        assert!(rendered.contains(&format!("{:<27}: {:>20}", "PPh 21 Lebih Bayar (refund)", format_rupiah(refund))), "{}", rendered);
+        assert!(!rendered.contains("-Rp") && !rendered.contains("Rp -"));
+        assert!(render_annual_summary(&summary, Lang::En).contains("PPh 21 Overpaid (refund)"));
+        
+        // A steady progressive withholding leaves nothing to settle
+        let steady = recurring_annual_summary(&month(6_000_000.0), Pph21Method::Progressive, &TaxConfig::default());
+        assert_eq!(steady.reconciliation(), 0.0);
+        
+        assert!(annual_summary(&[], Pph21Method::Progressive, &TaxConfig::default()).is_err());
+        assert!(annual_summary(&vec![month(1.0); 13], Pph21Method::Progressive, &TaxConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_severance_stays_out_of_the_salary_brackets() {
+        let months = vec![PPh21Params::builder().gross_income(10_000_000.0).build().unwrap(); 12];
+        let summary = annual_summary_with_severance(&months, 200_000_000.0, Pph21Method::Progressive, &TaxConfig::default()).unwrap();
+        
+        // The salary alone: 120,000,000 - 6,000,000 - 54,000,000 = 60,000,000 x 5%
+        assert_eq!(summary.salary, annual_summary(&months, Pph21Method::Progressive, &TaxConfig::default()).unwrap());
+        assert_eq!(summary.salary.pkp, 60_000_000.0);
+        assert_eq!(summary.salary.annual_tax, 3_000_000.0);
+        
+        // Pesangon: 0% on the first 50,000,000, 5% on the next 50,000,000 and
+        // 15% on the last 100,000,000
+        assert_eq!(summary.severance_tax, 17_500_000.0);
+        assert_eq!(summary.total_withheld(), 3_000_000.0 + 17_500_000.0);
+        assert_eq!(summary.total_tax(), 20_500_000.0);
+        
+        // Folded into the PKP it would reach the 25% bracket instead
+        let folded = calculate_income_tax(260_000_000.0, &TaxConfig::default().brackets);
+        assert!(folded > summary.total_tax(), "{}", folded);
+    }
+
+    #[test]
+    fn test_display_currency_converts_annual_tax() {
+        let params = PPh21Params::builder().gross_income(25_000_000.0).married(true).dependents(2).build().unwrap();
+        let result = calculate_pph21_result(&params, Pph21Method::Progressive, &TaxConfig::default());
+        let usd = DisplayCurrency::new("usd", 15_800.0).unwrap();
+        
+        assert_eq!(usd.code, "USD");
+        // Converted after the rupiah math, so only float division separates them
+        assert!((usd.convert(result.annual_tax) - result.annual_tax / 15_800.0).abs() < 1e-9);
+        assert_eq!(usd.format(1_234_567.891), "USD 1,234,567.89");
+        assert_eq!(usd.format(-0.5), "-USD 0.50");
+        
+        let output = render_converted_result(&result, &usd, Lang::Id);
+        assert!(output.contains("Kurs: Rp 15.800,00 per USD"));
+        assert!(output.contains(&usd.format(result.annual_tax / 15_800.0)));
+        
+        assert!(DisplayCurrency::new("US", 15_800.0).is_err());
+        assert!(DisplayCurrency::new("USD", 0.0).is_err());
+    }
+
+    #[test]
+    fn test_brackets_csv_replaces_built_in_brackets() {
+        let path = std::path::Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/brackets.csv"));
+        let brackets = load_brackets_csv(path).unwrap();
+        
+        // The fixture is the pre-2022 UU 36/2008 scale
+        assert_eq!(brackets.len(), 4);
+        assert_eq!(brackets[0], TaxBracket { lower_bound: 0.0, upper_bound: 50_000_000.0, rate: 0.05 });
+        assert_eq!(brackets[3].upper_bound, f64::MAX);
+        // 50,000,000 x 5% + 10,000,000 x 15% under the loaded first bracket,
+        // against 60,000,000 all at 5% under the built-in one
+        assert_eq!(calculate_income_tax(60_000_000.0, &brackets), 4_000_000.0);
+        assert_eq!(calculate_income_tax(60_000_000.0, &get_tax_brackets()), 3_000_000.0);
+    }
+
+    #[test]
+    fn test_brackets_csv_rejects_bad_tables() {
+        let dir = std::env::temp_dir().join(format!("tax_calculator_brackets_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let load = |name: &str, contents: &str| {
+            let path = dir.join(name);
+            std::fs::write(&path, contents).unwrap();
+            load_brackets_csv(&path).map_err(|err| err.to_string())
+        };
+        
+        let gap = load("gap.csv", "lower_bound,upper_bound,rate\n0,50000000,0.05\n60000000,,0.15\n");
+        let rate = load("rate.csv", "lower_bound,upper_bound,rate\n0,max,5\n");
+        let header = load("header.csv", "from,to,rate\n0,max,0.05\n");
+        let blank_top = load("blank.csv", "lower_bound,upper_bound,rate\n0,50.000.000,0.05\n50.000.000,,0.15\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+        
+        assert!(gap.unwrap_err().to_string().contains("menyambung"));
+        assert!(rate.unwrap_err().to_string().contains("Baris 2: rate '5'"));
+        assert!(header.unwrap_err().to_string().contains("lower_bound,upper_bound,rate"));
+        assert_eq!(blank_top.unwrap()[1].upper_bound, f64::MAX);
+    }
+
+    #[test]
+    fn test_tax_config_vat_rate_is_optional() {
+        let fixture = include_str!("../tests/fixtures/tax_config.toml");
+        assert_eq!(TaxConfig::from_toml(fixture).unwrap().vat_rate, None);
+        
+        let config = TaxConfig::from_toml(&format!("vat_rate = 12.0\n{}", fixture)).unwrap();
+        assert_eq!(config.vat_rate, Some(12.0));
+    }
+
+    #[test]
+    fn test_tax_config_rejects_unusable_tables() {
+        let fixture = include_str!("../tests/fixtures/tax_config.toml");
+        
+        let missing_k2 = TaxConfig::from_toml(&fixture.replace("\"K/2\" = 73_500_000.0\n", ""));
+        assert!(missing_k2.unwrap_err().to_string().contains("PTKP K/2 tidak ada"));
+        
+        let gap = TaxConfig::from_toml(&fixture.replace("lower_bound = 250_000_000.0", "lower_bound = 300_000_000.0"));
+        assert!(gap.unwrap_err().to_string().contains("lapisan tarif harus dimulai dari 0"));
+    }
+
+    #[test]
+    fn test_ptkp_spouse_income_combined() {
+        let expected = [(0, 112_500_000.0), (1, 117_000_000.0), (2, 121_500_000.0), (3, 126_000_000.0)];
+        let ptkp_values = ptkp_table();
+        
+        for (num_dependents, ptkp) in expected {
+            let params = PPh21Params::builder()
+                .gross_income(20_000_000.0)
+                .married(true)
+                .dependents(num_dependents)
+                .spouse_income_combined(true)
+                .build()
+                .unwrap();
+            assert_eq!(params.ptkp_status().to_string(), format!("K/I/{}", num_dependents));
+            assert_eq!(ptkp_values[&params.ptkp_status()], ptkp);
+            
+            // K/I is the married amount plus another TK/0
+            let married = PtkpStatus::new(true, false, num_dependents);
+            assert_eq!(ptkp, ptkp_values[&married] + 54_000_000.0);
+            
+            let (_, _, actual, _, _) = calculate_pph21(&params, &TaxConfig::default());
+            assert_eq!(actual, ptkp);
+        }
+    }
+
+    #[test]
+    fn test_request_code_keeps_spouse_income_combined() {
+        let params = PPh21Params::builder()
+            .gross_income(20_000_000.0)
+            .married(true)
+            .dependents(1)
+            .spouse_income_combined(true)
+            .build()
+            .unwrap();
+        
+        assert_eq!(decode_request(&encode_request(&params)).unwrap(), params);
+    }
+
+    #[test]
+    fn test_parse_dependents_empty_means_none() {
+        assert_eq!(Dependents::parse("").unwrap(), Dependents::Count(0));
+        assert_eq!(Dependents::parse("  \n").unwrap(), Dependents::Count(0));
+        assert!(matches!(Dependents::parse("-1"), Err(CalcError::Parse(_))));
+        assert!(matches!(Dependents::parse("2.5"), Err(CalcError::Parse(_))));
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount(" 6000000\n").unwrap(), 6_000_000.0);
+        assert_eq!(parse_amount("0").unwrap(), 0.0);
+        assert!(matches!(parse_amount("enam juta"), Err(CalcError::Parse(_))));
+        assert!(matches!(parse_amount(""), Err(CalcError::Parse(_))));
+        assert!(matches!(parse_amount("-1"), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(parse_amount("NaN"), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_parse_in_range() {
+        assert_eq!(parse_in_range("1", 1, 31).unwrap(), 1);
+        assert_eq!(parse_in_range(" 31 ", 1, 31).unwrap(), 31);
+        assert!(matches!(parse_in_range("0", 1, 31), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(parse_in_range("32", 1, 31), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(parse_in_range("lima", 1, 12), Err(CalcError::Parse(_))));
+    }
+
+    #[test]
+    fn test_progressive_tax_uses_floored_pkp() {
+        let params = PPh21Params::builder()
+            .gross_income(11_375_062.5)
+            .married(true)
+            .spouse_income_combined(true)
+            .build()
+            .unwrap();
+        let (annual_tax, _, _, pkp, _) = calculate_pph21_progressive(&params, &TaxConfig::default());
+        
+        // 136,500,750 - 6,000,000 - 112,500,000 = 18,000,750 -> 18,000,000
+        assert_eq!(pkp, 18_000_000.0);
+        // 18,000,000 x 5%, not 18,000,750 x 5% = 900,037.5
+        assert_eq!(annual_tax, 900_000.0);
+    }
+
+    #[test]
+    fn test_pph23_services_with_and_without_npwp() {
+        // 10,000,000 x 2%
+        assert_eq!(calculate_pph23(10_000_000.0, Pph23Object::Services, true, &TaxConfig::default()), 200_000.0);
+        // 10,000,000 x 4% without NPWP
+        assert_eq!(calculate_pph23(10_000_000.0, Pph23Object::Services, false, &TaxConfig::default()), 400_000.0);
+    }
+
+    #[test]
+    fn test_pph23_passive_income_rate() {
+        assert_eq!(calculate_pph23(1_000_000.0, Pph23Object::Dividends, true, &TaxConfig::default()), 150_000.0);
+        assert_eq!(calculate_pph23(1_000_000.0, Pph23Object::Royalties, false, &TaxConfig::default()), 300_000.0);
+    }
+
+    #[test]
+    fn test_pph26_default_and_treaty_rate() {
+        // 50,000,000 x 20%
+        let default_rate = parse_pph26_rate("").unwrap();
+        assert_eq!(calculate_pph26(50_000_000.0, default_rate, &TaxConfig::default()), 10_000_000.0);
+        // 50,000,000 x 10% under a treaty
+        let treaty_rate = parse_pph26_rate("10").unwrap();
+        assert_eq!(calculate_pph26(50_000_000.0, treaty_rate, &TaxConfig::default()), 5_000_000.0);
+        
+        assert_eq!(parse_pph26_rate("0%").unwrap(), 0.0);
+        assert!(matches!(parse_pph26_rate("25"), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(parse_pph26_rate("-5"), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_pph_final_42_rental() {
+        // 120,000,000 x 10%
+        assert_eq!(calculate_pph_final_42(120_000_000.0, PphFinal42Object::Rental, &TaxConfig::default()), 12_000_000.0);
+    }
+
+    #[test]
+    fn test_pph_final_42_construction_tiers() {
+        let small = PphFinal42Object::Construction(ConstructionQualification::Small);
+        let medium_large = PphFinal42Object::Construction(ConstructionQualification::MediumLarge);
+        let unqualified = PphFinal42Object::Construction(ConstructionQualification::Unqualified);
+        
+        // 200,000,000 x 1.75% / 2.65% / 4%
+        assert_eq!(calculate_pph_final_42(200_000_000.0, small, &TaxConfig::default()), 3_500_000.0);
+        assert_eq!(calculate_pph_final_42(200_000_000.0, medium_large, &TaxConfig::default()), 5_300_000.0);
+        assert_eq!(calculate_pph_final_42(200_000_000.0, unqualified, &TaxConfig::default()), 8_000_000.0);
+        // Land sale: 1,000,000,000 x 2.5%
+        assert_eq!(calculate_pph_final_42(1_000_000_000.0, PphFinal42Object::LandSale, &TaxConfig::default()), 25_000_000.0);
+    }
+
+    #[test]
+    fn test_config_rounding_applies_to_other_taxes() {
+        let down = TaxConfig { rounding: RoundingMode::Down, ..TaxConfig::default() };
+        
+        // 10,000,025 x 2% = 200,000.5
+        assert_eq!(calculate_pph23(10_000_025.0, Pph23Object::Services, true, &TaxConfig::default()), 200_001.0);
+        assert_eq!(calculate_pph23(10_000_025.0, Pph23Object::Services, true, &down), 200_000.0);
+        // 1,000,005 x 10% = 100,000.5
+        assert_eq!(calculate_pph_final_42(1_000_005.0, PphFinal42Object::Rental, &down), 100_000.0);
+        assert_eq!(calculate_pph26(1_000_005.0, 10.0, &down), 100_000.0);
+        // JHT 5,000,025 x 2% = 100,000.5
+        assert_eq!(calculate_bpjs(5_000_025.0, &TaxConfig::default()).jht, 100_001.0);
+        assert_eq!(calculate_bpjs(5_000_025.0, &down).jht, 100_000.0);
+        // 5% x (460,010 - 450,000) = 500.5
+        assert_eq!(calculate_pph21_daily(460_010.0, 460_010.0, &down), 500.0);
+    }
+
+    #[test]
+    fn test_pph21_without_npwp_is_20_percent_higher() {
+        let with_npwp = PPh21Params::builder()
+            .gross_income(10_000_000.0)
+            .build()
+            .unwrap();
+        let without_npwp = PPh21Params { has_npwp: false, ..with_npwp };
+        let config = TaxConfig::default();
+        
+        // PKP 120,000,000 - 6,000,000 - 54,000,000 = 60,000,000 -> 3,000,000 a year
+        let (annual, _, _, _, _) = calculate_pph21_progressive(&with_npwp, &config);
+        let (annual_no_npwp, monthly_no_npwp, _, _, _) = calculate_pph21_progressive(&without_npwp, &config);
+        assert_eq!(annual, 3_000_000.0);
+        assert_eq!(annual_no_npwp, 3_600_000.0);
+        assert_eq!(monthly_no_npwp, 300_000.0);
+        
+        // Flat method: 75,000 -> 90,000 a month
+        let (_, monthly_flat, _, _, _) = calculate_pph21(&with_npwp, &config);
+        let (_, monthly_flat_no_npwp, _, _, _) = calculate_pph21(&without_npwp, &config);
+        assert_eq!(monthly_flat, 75_000.0);
+        assert_eq!(monthly_flat_no_npwp, 90_000.0);
+    }
+
+    #[test]
+    fn test_request_code_keeps_missing_npwp() {
+        let params = PPh21Params::builder()
+            .gross_income(8_000_000.0)
+            .married(true)
+            .dependents(1)
+            .has_npwp(false)
+            .build()
+            .unwrap();
+        
+        assert_eq!(decode_request(&encode_request(&params)).unwrap(), params);
+    }
+
+    #[test]
+    fn test_pph21_bonus_differential() {
+        let params = PPh21Params::builder()
+            .gross_income(10_000_000.0)
+            .build()
+            .unwrap();
+        
+        // Regular: PKP 120,000,000 - 6,000,000 - 54,000,000 = 60,000,000 -> 3,000,000
+        // With bonus: PKP 130,000,000 - 6,000,000 - 54,000,000 = 70,000,000 -> 4,500,000
+        let bonus_tax = calculate_pph21_bonus(10_000_000.0, 10_000_000.0, &params, &TaxConfig::default());
+        assert_eq!(bonus_tax, 1_500_000.0);
+        
+        // Below PTKP even with the bonus, nothing is due
+        let low = calculate_pph21_bonus(3_000_000.0, 3_000_000.0, &params, &TaxConfig::default());
+        assert_eq!(low, 0.0);
+    }
+
+    #[test]
+    fn test_pph21_bonus_partial_year_caps_biaya_jabatan() {
+        let params = PPh21Params::builder()
+            .gross_income(20_000_000.0)
+            .months_worked(6)
+            .build()
+            .unwrap();
+        
+        // Six months worked: biaya jabatan is capped at 6 x 500,000 = 3,000,000
+        // and PTKP TK/0 is prorated to 27,000,000
+        // Regular: PKP 120,000,000 - 3,000,000 - 27,000,000 = 90,000,000 -> 7,500,000
+        // With bonus: PKP 140,000,000 - 3,000,000 - 27,000,000 = 110,000,000 -> 10,500,000
+        let bonus_tax = calculate_pph21_bonus(20_000_000.0, 20_000_000.0, &params, &TaxConfig::default());
+        assert_eq!(bonus_tax, 3_000_000.0);
+    }
+
+    #[test]
+    fn test_partial_year_with_a_bonus_in_month_six() {
+        let config = TaxConfig::default();
+        let params = PPh21Params::builder().gross_income(10_000_000.0).months_worked(7).build().unwrap();
+        let schedule = bonus_withholding_schedule(10_000_000.0, 10_000_000.0, 6, &params, &config).unwrap();
+        
+        // Seven months: biaya jabatan 5% of 70,000,000 = 3,500,000 (at the
+        // 7 x 500,000 cap), PTKP 54,000,000 x 7/12 = 31,500,000, so PKP
+        // 35,000,000 -> 1,750,000, or 250,000 a month. With the bonus the
+        // biaya jabatan stays capped: PKP 45,000,000 -> 2,250,000, so the bonus adds 500,000
+        assert_eq!(schedule, vec![250_000.0, 250_000.0, 250_000.0, 250_000.0, 250_000.0, 750_000.0, 250_000.0]);
+        assert_eq!(schedule.iter().sum::<f64>(), 2_250_000.0);
+        
+        // Over a full year the salary alone fills the 5% bracket, so the same
+        // bonus falls at 15% and adds 1,500,000
+        assert_eq!(calculate_pph21_bonus(10_000_000.0, 10_000_000.0, &PPh21Params { months_worked: 12, ..params }, &config), 1_500_000.0);
+        
+        assert!(matches!(bonus_withholding_schedule(10_000_000.0, 10_000_000.0, 8, &params, &config), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(bonus_withholding_schedule(10_000_000.0, 10_000_000.0, 0, &params, &config), Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_vat_exclusive_mode() {
+        let result = calculate_vat_with_mode(1_000_000.0, VatStatus::Standard(11.0), VatMode::Exclusive);
+        
+        assert_eq!(result.base, 1_000_000.0);
+        assert_eq!(result.vat, 110_000.0);
+    }
+
+    #[test]
+    fn test_vat_inclusive_mode() {
+        // 1,110,000 - 1,110,000 / 1.11 = 110,000
+        let result = calculate_vat_with_mode(1_110_000.0, VatStatus::Standard(11.0), VatMode::Inclusive);
+        assert_eq!(result.base, 1_000_000.0);
+        assert_eq!(result.vat, 110_000.0);
+        
+        // 500,000 / 1.11 = 450,450.45 -> VAT 49,550 after rounding
+        assert_eq!(extract_vat(500_000.0, 11.0), 49_550.0);
+        
+        // Exempt supplies carry no VAT, so the total is the base
+        let exempt = calculate_vat_with_mode(1_110_000.0, VatStatus::Exempt, VatMode::Inclusive);
+        assert_eq!(exempt.base, 1_110_000.0);
+        assert_eq!(exempt.vat, 0.0);
+    }
+
+    #[test]
+    fn test_vat_with_ppnbm() {
+        let base = 100_000_000.0;
+        let vat = calculate_vat(base, 11.0);
+        let ppnbm = calculate_ppnbm(base, 20.0);
+        
+        // 11,000,000 VAT + 20,000,000 PPnBM on a 100,000,000 base
+        assert_eq!(vat, 11_000_000.0);
+        assert_eq!(ppnbm, 20_000_000.0);
+        assert_eq!(base + vat + ppnbm, 131_000_000.0);
+    }
+
+    #[test]
+    fn test_parse_ppnbm_rate_range() {
+        assert_eq!(parse_ppnbm_rate("20").unwrap(), 20.0);
+        assert_eq!(parse_ppnbm_rate("200%").unwrap(), 200.0);
+        assert!(matches!(parse_ppnbm_rate("201"), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(parse_ppnbm_rate("-5"), Err(CalcError::OutOfRange(_))));
+        assert!(matches!(parse_ppnbm_rate(""), Err(CalcError::Parse(_))));
+    }
+
+    #[test]
+    fn test_invoice_with_three_lines() {
+        let lines = vec![
+            InvoiceLine { description: "Kertas A4".to_string(), quantity: 10, unit_price: 50_000.0 },
+            InvoiceLine { description: "Tinta printer".to_string(), quantity: 2, unit_price: 250_000.0 },
+            InvoiceLine { description: "Jasa servis".to_string(), quantity: 1, unit_price: 1_000_000.0 },
+        ];
+        let summary = calculate_invoice(&lines, 11.0);
+        
+        // 500,000 + 500,000 + 1,000,000 = 2,000,000; VAT 11% once on the subtotal
+        assert_eq!(summary.subtotal, 2_000_000.0);
+        assert_eq!(summary.vat, 220_000.0);
+        assert_eq!(summary.total, 2_220_000.0);
+        
+        let invoice = render_invoice(&lines, &summary, Lang::Id);
+        assert!(invoice.contains("Tinta printer"));
+        assert!(invoice.contains("Rp 2.220.000,00"));
+    }
+
+    #[test]
+    fn test_invoice_vat_per_line_reconciles_to_vat_on_subtotal() {
+        let lines = vec![
+            InvoiceLine { description: "Pulpen".to_string(), quantity: 3, unit_price: 3_333.0 },
+            InvoiceLine { description: "Map".to_string(), quantity: 1, unit_price: 4_545.0 },
+            InvoiceLine { description: "Stapler".to_string(), quantity: 1, unit_price: 27_272.0 },
+        ];
+        let summary = calculate_invoice_per_line(&lines, 11.0, RoundingMode::HalfUp);
+        
+        // Lines 9,999 / 4,545 / 27,272: VAT 1,099.89 / 499.95 / 2,999.92 rounds
+        // to 1,100 + 500 + 3,000 = 4,600, but 11% of 41,816 is 4,599.76 -> 4,600
+        assert_eq!(summary.method, InvoiceVatMethod::PerLineReconciled);
+        assert_eq!(summary.vat, round_rupiah(calculate_vat(summary.subtotal, 11.0), RoundingMode::HalfUp));
+        assert_eq!(summary.line_vat.iter().sum::<f64>(), summary.vat);
+        assert_eq!(summary.adjustment, 0.0);
+        
+        // Ten lines of Rp 1,005 each round up by 0.45 on every line: 111 x 10 =
+        // 1,110 against 11% of 10,050 = 1,105.5 -> 1,106, so the first line takes -4
+        let lines: Vec<InvoiceLine> = (1..=10)
+            .map(|item| InvoiceLine { description: format!("Barang {}", item), quantity: 1, unit_price: 1_005.0 })
+            .collect();
+        let summary = calculate_invoice_per_line(&lines, 11.0, RoundingMode::HalfUp);
+        assert_eq!(summary.vat, 1_106.0);
+        assert_eq!(summary.adjustment, -4.0);
+        assert_eq!(summary.line_vat[0], 107.0);
+        assert_eq!(summary.line_vat[1..], [111.0; 9]);
+        assert_eq!(summary.line_vat.iter().sum::<f64>(), summary.vat);
+        assert!(render_invoice(&lines, &summary, Lang::Id).contains("PPN 11% (dibulatkan per baris)"));
+        
+        assert_eq!(calculate_invoice(&lines, 11.0).method, InvoiceVatMethod::Subtotal);
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        assert_eq!(round_rupiah(45_340.5, RoundingMode::HalfUp), 45_341.0);
+        assert_eq!(round_rupiah(45_340.5, RoundingMode::HalfEven), 45_340.0);
+        assert_eq!(round_rupiah(45_340.5, RoundingMode::Down), 45_340.0);
+        
+        // Half-even only differs from half-up when the whole part is even
+        assert_eq!(round_rupiah(45_341.5, RoundingMode::HalfUp), 45_342.0);
+        assert_eq!(round_rupiah(45_341.5, RoundingMode::HalfEven), 45_342.0);
+        assert_eq!(round_rupiah(45_341.5, RoundingMode::Down), 45_341.0);
+        assert_eq!(round_rupiah(45_340.6, RoundingMode::Down), 45_340.0);
+        
+        assert_eq!(RoundingMode::default(), RoundingMode::HalfUp);
+    }
+
+    #[test]
+    fn test_round_to_multiple() {
+        assert_eq!(round_to_multiple(45_340.0, 100), 45_300.0);
+        assert_eq!(round_to_multiple(45_340.0, 1000), 45_000.0);
+        assert_eq!(round_to_multiple(45_350.0, 100), 45_400.0);
+        assert_eq!(round_to_multiple(45_500.0, 1000), 46_000.0);
+        assert_eq!(round_to_multiple(45_340.35, 0), 45_340.35);
+        
+        // PPN on 412,185 at 11% is 45,340.35; only the displayed PPN changes
+        let vat = calculate_vat_with_status(412_185.0, VatStatus::Standard(11.0));
+        let rounded = vat.rounded_to(100);
+        assert_eq!(rounded.vat, 45_300.0);
+        assert_eq!(rounded.base, vat.base);
+        assert_eq!(vat.vat, 45_340.35);
+    }
+
+    #[test]
+    fn test_rounded_pph21_and_payroll_keep_intermediate_figures() {
+        let params = PPh21Params::builder().gross_income(6_045_340.0).build().unwrap();
+        let config = TaxConfig::default();
+        
+        let result = calculate_pph21_result(&params, Pph21Method::Progressive, &config);
+        let rounded = result.rounded_to(1000);
+        assert_eq!((rounded.monthly_tax, rounded.annual_tax), (62_000.0, 746_000.0));
+        assert_eq!(rounded.pkp, result.pkp);
+        
+        let report = full_payroll(&params, &config);
+        let rounded = report.rounded_to(100);
+        assert_eq!((rounded.monthly_tax, rounded.net_monthly), (62_200.0, 5_741_400.0));
+        assert_eq!(rounded.bpjs, report.bpjs);
+    }
+
+    #[test]
+    fn test_config_rounding_mode_applies_to_pph21() {
+        // PKP 25,911,000 x 5% = 1,295,550 a year, 107,962.5 a month
+        let params = PPh21Params::builder().gross_income(7_010_000.0).zakat(3_000.0).build().unwrap();
+        let monthly_tax = |rounding| calculate_pph21_progressive(&params, &TaxConfig { rounding, ..TaxConfig::default() }).1;
+        
+        assert_eq!(monthly_tax(RoundingMode::HalfUp), 107_963.0);
+        assert_eq!(monthly_tax(RoundingMode::HalfEven), 107_962.0);
+        assert_eq!(monthly_tax(RoundingMode::Down), 107_962.0);
+        
+        let config = TaxConfig::from_toml(&format!("rounding = \"half-even\"\n{}", include_str!("../tests/fixtures/tax_config.toml")));
+        assert_eq!(config.unwrap().rounding, RoundingMode::HalfEven);
+    }
+
+    #[test]
+    fn test_money_math_is_exact() {
+        // In plain f64, 99,999.95 x 11 / 100 = 10,999.994499999999
+        assert_eq!(calculate_vat(99_999.95, 11.0), 10_999.994_5);
+        assert_eq!(calculate_vat(1_000_000.1, 12.0), 120_000.012);
+        
+        // Round-half-up to whole rupiah
+        assert_eq!(round_decimal(Decimal::new(25, 1), RoundingMode::HalfUp), Decimal::from(3));
+        assert_eq!(round_decimal(Decimal::new(24_999, 4), RoundingMode::HalfUp), Decimal::from(2));
+    }
+
+    #[test]
+    fn test_params_builder_caps_dependents() {
+        let params = PPh21Params::builder()
+            .gross_income(10_000_000.0)
+            .married(true)
+            .dependents(5)
+            .build()
+            .unwrap();
+        
+        assert_eq!(params.num_dependents, 3);
+        assert!(params.has_npwp);
+        assert_eq!(params.ptkp_status(), PtkpStatus::K3);
+        
+        // Beyond the sanity limit is a typo, not a large family
+        let typo = PPh21Params::builder().married(true).dependents(99).build();
+        assert!(matches!(typo, Err(CalcError::OutOfRange(_))));
+    }
+
+    #[test]
+    fn test_params_builder_rejects_invalid_input() {
+        let negative = PPh21Params::builder().gross_income(-1.0).build();
+        assert!(matches!(negative, Err(CalcError::OutOfRange(_))));
+        
+        assert!(PPh21Params::builder().gross_income(f64::NAN).build().is_err());
+        assert!(PPh21Params::builder().spouse_income_combined(true).build().is_err());
+    }
+
+    #[test]
+    fn test_hpp_brackets_apply_from_2022() {
+        let edges = |year| -> Vec<(f64, f64)> {
+            tax_brackets_for_year(year).unwrap().iter().map(|bracket| (bracket.upper_bound, bracket.rate)).collect()
+        };
+        // UU HPP: 5% to 60 million, 15% to 250 million, 25% to 500 million,
+        // 30% to 5 billion and 35% above
+        let hpp = vec![
+            (60_000_000.0, 0.05),
+            (250_000_000.0, 0.15),
+            (500_000_000.0, 0.25),
+            (5_000_000_000.0, 0.30),
+            (f64::MAX, 0.35),
+        ];
+        for year in SUPPORTED_TAX_YEARS {
+            assert_eq!(edges(year), hpp, "{}", year);
+        }
+        
+        // 10,000,000 a month for TK/0: PKP 60,000,000, all at 5%
+        let params = PPh21Params::builder().gross_income(10_000_000.0).build().unwrap();
+        assert_eq!(calculate_pph21_progressive(&params, &TaxConfig::for_year(2022).unwrap()).0, 3_000_000.0);
+        
+        // The TK/0 PTKP has been Rp 54,000,000 since 2016; defaults are the latest year
+        assert_eq!(ptkp_values_for_year(2022).unwrap()[&PtkpStatus::Tk0], 54_000_000.0);
+        assert_eq!(TaxConfig::default().brackets, TaxConfig::for_year(TAX_TABLES_YEAR as u16).unwrap().brackets);
+        assert_eq!(TaxConfig::default().ptkp, TaxConfig::for_year(TAX_TABLES_YEAR as u16).unwrap().ptkp);
+    }
+
+    #[test]
+    fn test_unknown_tax_year_is_an_error() {
+        let err = TaxConfig::for_year(2021).unwrap_err().to_string();
+        assert!(err.contains("2021"));
+        assert!(err.contains("2022, 2023, 2024"));
+        assert!(tax_brackets_for_year(2025).is_err());
+        assert_eq!(tables_fingerprint(2019), None);
+    }
+
+    #[test]
+    fn test_zero_income() {
+        let params = PPh21Params::builder()
+            .gross_income(0.0)
+            .build()
+            .unwrap();
+        
+        let (annual_tax, monthly_tax, _, _, _) = calculate_pph21(&params, &TaxConfig::default());
+        
+        assert_eq!(annual_tax, 0.0);
+        assert_eq!(monthly_tax, 0.0);
+    }
+}
+
+// calculate_income_tax at and around the bracket edges; each bracket is
+// half-open, so income exactly on a lower bound adds nothing at the higher rate
+#[cfg(test)]
+mod income_tax_tests {
+    use super::*;
+
+    #[test]
+    fn test_income_tax_at_first_bracket_ceiling() {
+        // 60,000,000 x 5% = 3,000,000
+        assert_eq!(calculate_income_tax(60_000_000.0, &get_tax_brackets()), 3_000_000.0);
+    }
+
+    #[test]
+    fn test_income_tax_at_second_bracket_ceiling() {
+        // 3,000,000 + 190,000,000 x 15% = 31,500,000
+        assert_eq!(calculate_income_tax(250_000_000.0, &get_tax_brackets()), 31_500_000.0);
+    }
+
+    #[test]
+    fn test_income_tax_at_third_bracket_ceiling() {
+        // 31,500,000 + 250,000,000 x 25% = 94,000,000
+        assert_eq!(calculate_income_tax(500_000_000.0, &get_tax_brackets()), 94_000_000.0);
+    }
+
+    #[test]
+    fn test_income_tax_in_top_bracket() {
+        // 94,000,000 + 250,000,000 x 30% = 169,000,000
+        assert_eq!(calculate_income_tax(750_000_000.0, &get_tax_brackets()), 169_000_000.0);
+        // 94,000,000 + 4,500,000,000 x 30% + 1,000,000,000 x 35% = 1,794,000,000
+        assert_eq!(calculate_income_tax(6_000_000_000.0, &get_tax_brackets()), 1_794_000_000.0);
+    }
+
+    #[test]
+    fn test_income_tax_just_past_a_lower_bound() {
+        let brackets = get_tax_brackets();
+        
+        // The rupiah after 60,000,000 is the first taxed at 15%
+        assert_eq!(calculate_income_tax(60_001_000.0, &brackets), 3_000_150.0);
+        assert_eq!(brackets[1].tax_in_bracket(60_000_000.0), 0.0);
+        assert!(brackets[1].contains(60_000_000.0));
+        assert!(!brackets[0].contains(60_000_000.0));
+    }
+
+    #[test]
+    fn test_income_tax_from_net_inverts_income_tax() {
+        let brackets = get_tax_brackets();
+        
+        for pkp in [0.0, 10_000_000.0, 50_000_000.0, 123_456_789.0, 250_000_000.0, 499_999_999.0, 750_000_000.0, 2_000_000_000.0] {
+            let net = pkp - calculate_income_tax(pkp, &brackets);
+            assert_eq!(income_tax_from_net(net, &brackets, RoundingMode::default()), pkp, "net {}", net);
+        }
+    }
+
+    #[test]
+    fn test_income_tax_from_net_within_a_bracket() {
+        // 100,000,000 PKP pays 9,000,000 tax: 57,000,000 net from the first
+        // bracket plus 34,000,000 / 85% from the second
+        assert_eq!(income_tax_from_net(91_000_000.0, &get_tax_brackets(), RoundingMode::default()), 100_000_000.0);
+        assert_eq!(income_tax_from_net(-1.0, &get_tax_brackets(), RoundingMode::default()), 0.0);
+    }
+
+    #[test]
+    fn test_income_tax_breakdown_sums_to_total() {
+        let brackets = get_tax_brackets();
+        let contributions = income_tax_breakdown(300_000_000.0, &brackets);
+        
+        // 60,000,000 x 5% + 190,000,000 x 15% + 50,000,000 x 25%
+        let taxable: Vec<f64> = contributions.iter().map(|contribution| contribution.taxable).collect();
+        let tax: Vec<f64> = contributions.iter().map(|contribution| contribution.tax).collect();
+        assert_eq!(taxable, vec![60_000_000.0, 190_000_000.0, 50_000_000.0]);
+        assert_eq!(tax, vec![3_000_000.0, 28_500_000.0, 12_500_000.0]);
+        assert_eq!(tax.iter().sum::<f64>(), calculate_income_tax(300_000_000.0, &brackets));
+        
+        assert!(income_tax_breakdown(0.0, &brackets).is_empty());
+    }
+}
+        
+// Cross-check calculate_pph21 against an independent, deliberately simple
+// reference implementation written straight from the rules
+#[cfg(test)]
+mod reference_tests {
+    use super::*;
+
+    // PTKP: Rp 54,000,000 for the taxpayer, Rp 4,500,000 for a spouse and per dependent (max 3)
+    fn reference_ptkp(is_married: bool, num_dependents: u32) -> f64 {
+        let mut ptkp = 54_000_000.0;
+        if is_married {
+            ptkp += 4_500_000.0;
+            ptkp += 4_500_000.0 * num_dependents as f64;
+        }
+        ptkp
+    }
+
+    // Returns (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) like calculate_pph21
+    fn reference_pph21(gross_income: f64, is_married: bool, num_dependents: u32) -> (f64, f64, f64, f64, f64) {
+        let annual_gross = gross_income * 12.0;
+        let ptkp = reference_ptkp(is_married, num_dependents);
+        let mut biaya_jabatan = annual_gross * 0.05;
+        if biaya_jabatan > 6_000_000.0 {
+            biaya_jabatan = 6_000_000.0;
+        }
+
+        let mut pkp = annual_gross - biaya_jabatan - ptkp;
+        if pkp < 0.0 {
+            pkp = 0.0;
+        }
+        pkp = pkp - pkp % 1000.0;
+
+        let monthly_tax = (gross_income * 0.0075).round();
+        let annual_tax = monthly_tax * 12.0;
+        (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan)
+    }
+
+    #[test]
+    fn test_pph21_matches_reference_across_grid() {
+        let incomes = [0.0, 1_000_000.0, 4_500_000.0, 6_000_000.0, 10_000_000.0, 25_500_750.0, 100_000_000.0, 1_000_000_000.0];
+        let statuses = [(false, 0), (true, 0), (true, 1), (true, 2), (true, 3)];
+
+        for &gross_income in &incomes {
+            for &(is_married, num_dependents) in &statuses {
+                let params = PPh21Params::builder().gross_income(gross_income).married(is_married).dependents(num_dependents).build().unwrap();
+                let actual = calculate_pph21(&params, &TaxConfig::default());
+                let expected = reference_pph21(gross_income, is_married, num_dependents);
+
+                let close = |a: f64, b: f64| (a - b).abs() < 0.01;
+                assert!(
+                    close(actual.0, expected.0) && close(actual.1, expected.1) && close(actual.2, expected.2) && close(actual.3, expected.3) && close(actual.4, expected.4),
+                    "gross {} married {} dependents {}: got {:?}, reference {:?}",
+                    gross_income,
+                    is_married,
+                    num_dependents,
+                    actual,
+                    expected
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+// Shared assertion helpers and test doubles for tests, including downstream
+// tests built with the `testing` feature
+
+use chrono::NaiveDate;
+
+use crate::tax::Clock;
+
+// Clock fixed at a given date
+pub struct MockClock(pub NaiveDate);
+
+impl Clock for MockClock {
+    fn today(&self) -> NaiveDate {
+        self.0
+    }
+}
+
+// Exact comparison for whole-rupiah amounts
+pub fn assert_rupiah_eq(actual: f64, expected: f64) {
+    assert!(
+        actual.fract() == 0.0 && expected.fract() == 0.0,
+        "Assertion failed: Rp{} or Rp{} is not a whole rupiah amount",
+        actual,
+        expected
+    );
+    assert!(
+        actual == expected,
+        "Assertion failed: Rp{} is not equal to Rp{}",
+        actual,
+        expected
+    );
+}
+
+/// Whether `a` and `b` differ by less than `epsilon`, for amounts that went
+/// through f64 math; use an epsilon of 0.01 for rupiah with cents.
+///
+/// ```
+/// use tax_calculator::tax::calculate_vat;
+/// use tax_calculator::test_utils::approx_eq;
+///
+/// assert!(approx_eq(calculate_vat(99_999.95, 11.0), 10_999.99, 0.01));
+/// assert!(!approx_eq(45_000.5, 45_000.0, 0.01));
+/// ```
+pub fn approx_eq(a: f64, b: f64, epsilon: f64) -> bool {
+    (a - b).abs() < epsilon
+}
+
+// Helper function for floating-point comparison (legacy float paths)
+pub fn assert_approx_eq(a: f64, b: f64) {
+    assert!(
+        approx_eq(a, b, 0.01),
+        "Assertion failed: {} is not approximately equal to {}",
+        a,
+        b
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assert_rupiah_eq_accepts_equal_amounts() {
+        assert_rupiah_eq(45_000.0, 45_000.0);
+        assert_rupiah_eq(0.0, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not equal to")]
+    fn test_assert_rupiah_eq_rejects_off_by_one() {
+        assert_rupiah_eq(45_001.0, 45_000.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a whole rupiah")]
+    fn test_assert_rupiah_eq_rejects_fractions() {
+        assert_rupiah_eq(45_000.5, 45_000.5);
+    }
+
+    #[test]
+    fn test_approx_eq_is_strict_at_epsilon() {
+        assert!(approx_eq(10.0, 10.5, 1.0));
+        assert!(approx_eq(10.5, 10.0, 1.0));
+        assert!(!approx_eq(10.0, 11.0, 1.0));
+    }
+
+    #[test]
+    fn test_assert_approx_eq_within_epsilon() {
+        assert_approx_eq(45_000.001, 45_000.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not approximately equal")]
+    fn test_assert_approx_eq_outside_epsilon() {
+        assert_approx_eq(45_000.5, 45_000.0);
+    }
+}
@@ -0,0 +1,83 @@
+// Browser entry points, exported with wasm-bindgen under the `wasm` feature:
+//
+//     cargo rustc --lib --crate-type cdylib --target wasm32-unknown-unknown --release --features wasm
+//
+// Each export takes plain numbers and booleans and returns a JSON string: the
+// result object on success, or `{"error": "..."}` for invalid input
+
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::tax::{calculate_pph21_result, calculate_vat_with_status, PPh21Params, Pph21Method, TaxConfig, VatStatus};
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+// Monthly PPh 21 (progressive method, built-in tables) as a Pph21Result object
+pub fn pph21_json(gross_income: f64, married: bool, dependents: u32, spouse_income_combined: bool, has_npwp: bool) -> String {
+    let params = PPh21Params::builder()
+        .gross_income(gross_income)
+        .married(married)
+        .dependents(dependents)
+        .spouse_income_combined(spouse_income_combined)
+        .has_npwp(has_npwp)
+        .build();
+    
+    match params {
+        Ok(params) => {
+            let result = calculate_pph21_result(&params, Pph21Method::Progressive, &TaxConfig::default());
+            serde_json::to_string(&result).expect("hasil perhitungan selalu dapat diserialisasi")
+        },
+        Err(err) => error_json(&err.to_string()),
+    }
+}
+
+// PPN on a price before tax as a VatResult object; `rate` is a percentage
+pub fn vat_json(amount: f64, rate: f64) -> String {
+    if !amount.is_finite() || amount < 0.0 {
+        return error_json("Jumlah harga harus Rp 0 atau lebih.");
+    }
+    if !rate.is_finite() || !(0.0..=100.0).contains(&rate) {
+        return error_json("Persentase PPN harus antara 0 dan 100.");
+    }
+    
+    serde_json::to_string(&calculate_vat_with_status(amount, VatStatus::Standard(rate))).expect("hasil perhitungan selalu dapat diserialisasi")
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = calculatePph21)]
+pub fn calculate_pph21(gross_income: f64, married: bool, dependents: u32, spouse_income_combined: bool, has_npwp: bool) -> String {
+    pph21_json(gross_income, married, dependents, spouse_income_combined, has_npwp)
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen(js_name = calculateVat)]
+pub fn calculate_vat(amount: f64, rate: f64) -> String {
+    vat_json(amount, rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn test_pph21_json_returns_result_or_error() {
+        let result: Value = serde_json::from_str(&pph21_json(6_000_000.0, false, 0, false, true)).unwrap();
+        assert_eq!(result["ptkp"], 54_000_000.0);
+        assert_eq!(result["monthly_tax"], 60_000.0);
+        
+        let error: Value = serde_json::from_str(&pph21_json(-1.0, false, 0, false, true)).unwrap();
+        assert!(error["error"].as_str().unwrap().contains("Penghasilan bruto"));
+    }
+
+    #[test]
+    fn test_vat_json_returns_result_or_error() {
+        let result: Value = serde_json::from_str(&vat_json(1_000_000.0, 11.0)).unwrap();
+        assert_eq!(result["vat"], 110_000.0);
+        
+        let error: Value = serde_json::from_str(&vat_json(1_000_000.0, 150.0)).unwrap();
+        assert!(error.get("error").is_some());
+    }
+}
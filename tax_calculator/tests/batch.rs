@@ -0,0 +1,65 @@
+// Batch PPh 21 over the sample employee CSV
+
+use tax_calculator::batch::{run_batch, validate_batch};
+use tax_calculator::tax::TaxConfig;
+
+#[test]
+fn test_batch_fixture_reports_malformed_row() {
+    let input = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/employees.csv")).unwrap();
+    let mut output = Vec::new();
+
+    let report = run_batch(input.as_slice(), &mut output, &TaxConfig::default()).unwrap();
+
+    assert_eq!(report.processed, 3);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].line, 4);
+
+    let output = String::from_utf8(output).unwrap();
+    let rows: Vec<&str> = output.lines().collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], "name,gross_income,is_married,num_dependents,ptkp,pkp,monthly_tax,annual_tax");
+    assert!(rows[1].starts_with("Budi Santoso,6000000,false,0,54000000,"));
+    assert!(rows.iter().all(|row| !row.starts_with("Agus")));
+}
+
+#[test]
+fn test_validate_reports_every_invalid_row() {
+    let input = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/employees_invalid.csv")).unwrap();
+    
+    let report = validate_batch(input.as_slice()).unwrap();
+    
+    assert_eq!(report.processed, 2);
+    let faults: Vec<(u64, Option<&str>)> = report.skipped.iter().map(|error| (error.line, error.field)).collect();
+    assert_eq!(
+        faults,
+        [
+            (3, Some("gross_income")),
+            (4, Some("is_married")),
+            (5, Some("num_dependents")),
+            (6, Some("gross_income")),
+            (7, None),
+        ]
+    );
+    assert!(report.skipped[4].message.to_string().contains("4 kolom"));
+}
+
+#[test]
+fn test_status_column_reports_malformed_statuses() {
+    let input = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/employees_status.csv")).unwrap();
+    let mut output = Vec::new();
+
+    let report = run_batch(input.as_slice(), &mut output, &TaxConfig::default()).unwrap();
+
+    // K/5 and "kawin" are not PTKP statuses; neither falls back to TK/0
+    assert_eq!(report.processed, 3);
+    let faults: Vec<(u64, Option<&str>)> = report.skipped.iter().map(|error| (error.line, error.field)).collect();
+    assert_eq!(faults, [(4, Some("status")), (6, Some("status"))]);
+    assert!(report.skipped[0].message.to_string().contains("Status PTKP 'K/5' tidak dikenal"));
+    assert_eq!(validate_batch(input.as_slice()).unwrap(), report);
+
+    let output = String::from_utf8(output).unwrap();
+    let rows: Vec<&str> = output.lines().collect();
+    assert!(rows[2].starts_with("Sari Dewi,15000000,true,2,67500000,"), "{}", rows[2]);
+    // K/I/1: the married amount plus another TK/0
+    assert!(rows[3].starts_with("Rina Putri,25000000,true,1,117000000,"), "{}", rows[3]);
+}
@@ -0,0 +1,32 @@
+// ANSI color is opt-in for piped output
+
+use std::process::Command;
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_tax_calculator")).args(args).env_remove("NO_COLOR").output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_color_never_has_no_escape_sequences() {
+    let output = run(&["--color", "never", "pph21", "--gross", "6000000"]);
+
+    assert!(output.contains("PPh 21 Sebulan"), "{}", output);
+    assert!(!output.contains('\u{1b}'), "{:?}", output);
+}
+
+#[test]
+fn test_piped_output_is_uncolored_by_default() {
+    assert!(!run(&["pph21", "--gross", "6000000"]).contains('\u{1b}'));
+    assert!(!run(&["ppn", "--amount=-5"]).contains('\u{1b}'));
+}
+
+#[test]
+fn test_color_always_highlights_tax_and_errors() {
+    let output = run(&["--color", "always", "pph21", "--gross", "6000000"]);
+    let tax_line = output.lines().find(|line| line.contains("PPh 21 Sebulan")).unwrap();
+    assert!(tax_line.starts_with('\u{1b}'), "{:?}", tax_line);
+
+    // Out-of-range input is reported in red (ANSI 31)
+    assert!(run(&["--color", "always", "ppn", "--amount=-5"]).contains("\u{1b}[31m"));
+}
@@ -0,0 +1,29 @@
+// Exit codes of the non-interactive commands: 1 for a failed check, 2 for
+// invalid input, 3 for a file or config that cannot be read or written
+
+use std::process::Command;
+
+fn exit_code(args: &[&str]) -> Option<i32> {
+    Command::new(env!("CARGO_BIN_EXE_tax_calculator")).args(args).output().unwrap().status.code()
+}
+
+#[test]
+fn test_bad_gross_argument_exits_with_invalid_input() {
+    assert_eq!(exit_code(&["pph21", "--gross", "enam juta"]), Some(2));
+    assert_eq!(exit_code(&["pph21", "--gross", "6000000", "--npwp", "123"]), Some(2));
+    assert_eq!(exit_code(&["pph21", "--gross", "5000000000000"]), Some(2));
+    assert_eq!(exit_code(&["pph21", "--gross", "6000000"]), Some(0));
+}
+
+#[test]
+fn test_unreadable_files_exit_with_io_error() {
+    assert_eq!(exit_code(&["--config", "tidak_ada.toml", "pph21", "--gross", "6000000"]), Some(3));
+    assert_eq!(exit_code(&["batch", "--input", "tidak_ada.csv", "--output", "hasil.csv"]), Some(3));
+}
+
+#[test]
+fn test_failed_validation_exits_with_check_failed() {
+    let invalid = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/employees_invalid.csv");
+    assert_eq!(exit_code(&["batch", "--input", invalid, "--validate"]), Some(1));
+    assert_eq!(exit_code(&["selfcheck"]), Some(0));
+}
@@ -0,0 +1,36 @@
+// --format toml prints the result in a form that reads straight back
+
+use std::process::Command;
+
+use tax_calculator::tax::{calculate_pph21_result, PPh21Params, Pph21Method, Pph21Result, TaxConfig, VatResult};
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_tax_calculator")).args(args).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_toml_pph21_output_reads_back_as_the_result() {
+    let printed: Pph21Result = toml::from_str(&run(&["--format", "toml", "pph21", "--gross", "6000000"])).unwrap();
+    let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+
+    assert_eq!(printed, calculate_pph21_result(&params, Pph21Method::Progressive, &TaxConfig::default()));
+}
+
+#[test]
+fn test_toml_ppn_output_reads_back_as_the_result() {
+    let printed: VatResult = toml::from_str(&run(&["ppn", "--amount", "412185", "--format", "toml"])).unwrap();
+
+    assert_eq!(printed.vat, 45_340.35);
+}
+
+#[test]
+fn test_json_pph21_output_carries_the_loaded_tables_fingerprint() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tax_config.toml");
+    let printed: Pph21Result = serde_json::from_str(&run(&["--config", path, "--format", "json", "pph21", "--gross", "6000000"])).unwrap();
+    let loaded = TaxConfig::load(std::path::Path::new(path)).unwrap();
+
+    assert_eq!(printed.tables_fingerprint, loaded.fingerprint());
+    assert_ne!(printed.tables_fingerprint, TaxConfig::default().fingerprint());
+}
@@ -0,0 +1,78 @@
+// Formatted output compared in full against files in tests/fixtures/golden;
+// set UPDATE_GOLDEN=1 to rewrite them after an intended change. The menu
+// session in interactive_session.txt is checked by the binary's own tests,
+// since run_interactive is not part of the library
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use tax_calculator::i18n::Lang;
+use tax_calculator::tax::{
+    calculate_vat_with_mode, gross_up, render_gross_up_worksheet, render_gross_worksheet, render_vat, PPh21Params,
+    Pph21Method, RateChange, TaxConfig, VatMode, VatStatus,
+};
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden").join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+    assert_eq!(actual, expected, "output differs from {}", path.display());
+}
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_tax_calculator")).args(args).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_pph21_gross_worksheets() {
+    let config = TaxConfig::default();
+    let single = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+    let married = PPh21Params::builder()
+        .gross_income(25_000_000.0)
+        .married(true)
+        .dependents(2)
+        .has_npwp(false)
+        .zakat(2_500_000.0)
+        .build()
+        .unwrap();
+
+    assert_golden("pph21_gross_tk0.txt", &render_gross_worksheet(&single, Pph21Method::Progressive, &config, Lang::Id));
+    assert_golden("pph21_gross_k2_no_npwp.txt", &render_gross_worksheet(&married, Pph21Method::Progressive, &config, Lang::Id));
+    assert_golden("pph21_gross_flat.txt", &render_gross_worksheet(&single, Pph21Method::Flat, &config, Lang::Id));
+}
+
+#[test]
+fn test_pph21_gross_up_worksheet() {
+    let config = TaxConfig::default();
+    let status = PPh21Params::builder().married(true).dependents(1).build().unwrap();
+    let gross = gross_up(10_000_000.0, &status, &config).unwrap();
+
+    assert_golden("pph21_gross_up_k1.txt", &render_gross_up_worksheet(10_000_000.0, gross, &status, &config, Lang::Id));
+}
+
+#[test]
+fn test_ppn_results() {
+    let exclusive = calculate_vat_with_mode(1_000_000.0, VatStatus::Standard(11.0), VatMode::Exclusive);
+    let inclusive = calculate_vat_with_mode(1_110_000.0, VatStatus::Standard(11.0), VatMode::Inclusive);
+    let exempt = calculate_vat_with_mode(1_000_000.0, VatStatus::Exempt, VatMode::Exclusive);
+    let change = RateChange { effective_month: 7, new_rate: 12.0 };
+
+    assert_golden("ppn_exclusive.txt", &render_vat(&exclusive, None, Lang::Id));
+    assert_golden("ppn_inclusive.txt", &render_vat(&inclusive, None, Lang::Id));
+    assert_golden("ppn_exempt.txt", &render_vat(&exempt, None, Lang::Id));
+    assert_golden("ppn_rate_change.txt", &render_vat(&exclusive, Some(&change), Lang::Id));
+}
+
+#[test]
+fn test_cli_stdout() {
+    assert_golden("cli_pph21.txt", &run(&["pph21", "--gross", "6000000"]));
+    assert_golden("cli_pph21_take_home.txt", &run(&["pph21", "--gross", "15000000", "--married", "--take-home"]));
+    assert_golden("cli_ppn.txt", &run(&["ppn", "--amount", "1000000"]));
+}
@@ -0,0 +1,32 @@
+// Calculations run from the command line are appended to the history file
+
+use std::process::Command;
+
+use tax_calculator::history::HistoryEntry;
+
+#[test]
+fn test_two_calculations_append_two_lines() {
+    let path = std::env::temp_dir().join(format!("tax_calculator_cli_history_{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    
+    for args in [&["pph21", "--gross", "6000000"][..], &["ppn", "--amount", "1000000"][..]] {
+        let status = Command::new(env!("CARGO_BIN_EXE_tax_calculator"))
+            .args(args)
+            .arg("--history")
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+    
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let entries: Vec<HistoryEntry> = contents.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].kind, "pph21");
+    assert_eq!(entries[0].inputs["gross_income"], 6_000_000.0);
+    assert_eq!(entries[0].outputs["monthly_tax"], 60_000.0);
+    assert_eq!(entries[1].kind, "ppn");
+    assert_eq!(entries[1].outputs["vat"], 110_000.0);
+}
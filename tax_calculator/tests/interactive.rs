@@ -0,0 +1,61 @@
+// The interactive menu driven through a pipe with --interactive
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn run_menu(input: &str) -> std::process::Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tax_calculator"))
+        .args(["--interactive", "--color", "never"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_input_ending_mid_prompt_exits_cleanly() {
+    // Stops at the marital status prompt of menu 1
+    let output = run_menu("1\n6000000\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Status Perkawinan:"));
+    assert!(stdout.trim_end().ends_with("Terima kasih telah menggunakan kalkulator pajak!"));
+    assert!(!stdout.contains("Gagal membaca input"));
+}
+
+#[test]
+fn test_input_ending_at_the_menu_exits_cleanly() {
+    let output = run_menu("4\n1000000\n\n\n\n\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    // The menu is shown once before and once after the PPN calculation
+    assert_eq!(stdout.matches("Pilih jenis perhitungan:").count(), 2);
+    assert!(stdout.trim_end().ends_with("Terima kasih telah menggunakan kalkulator pajak!"));
+}
+
+#[test]
+fn test_h_at_the_menu_prints_help_in_the_chosen_language() {
+    let output = run_menu("h\n27\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("=== PENJELASAN PILIHAN MENU ==="));
+    assert!(stdout.contains("Perusahaan memberi tunjangan pajak"));
+    assert_eq!(stdout.matches("Pilih jenis perhitungan:").count(), 2);
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tax_calculator"))
+        .args(["--interactive", "--color", "never", "--lang", "en"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"H\n27\n").unwrap();
+    let stdout = String::from_utf8(child.wait_with_output().unwrap().stdout).unwrap();
+    assert!(stdout.contains("=== MENU OPTIONS EXPLAINED ==="));
+    assert!(stdout.contains("Gross Up"));
+}
@@ -0,0 +1,57 @@
+// --lang en prints subcommand results and error messages in English
+
+use std::process::Command;
+
+fn run(args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_tax_calculator")).args(args).output().unwrap();
+    let mut printed = String::from_utf8(output.stdout).unwrap();
+    printed.push_str(&String::from_utf8(output.stderr).unwrap());
+    (output.status.success(), printed)
+}
+
+fn assert_no_indonesian(output: &str) {
+    for indonesian in ["HASIL PERHITUNGAN", "Hasil Perhitungan", "Penghasilan Bruto", "Sebulan", "Setahun", "Komponen", "tidak valid", "harus"] {
+        assert!(!output.contains(indonesian), "{:?} in English output:\n{}", indonesian, output);
+    }
+}
+
+#[test]
+fn test_english_pph21_and_ppn_results() {
+    let (success, output) = run(&["--lang", "en", "pph21", "--gross", "10000000"]);
+    assert!(success);
+    assert!(output.contains("=== PPh 21 RESULT ==="), "{}", output);
+    assert!(output.contains("Gross income per month"));
+    assert_no_indonesian(&output);
+
+    let (success, output) = run(&["--lang", "en", "--format", "markdown", "pph21", "--gross", "10000000", "--npwp", "123456789012345", "--period", "10/2024"]);
+    assert!(success);
+    assert!(output.contains("| Component | Amount |"));
+    assert!(output.contains("**Withholding Slip Number:** 1.1-10.24-0000001"));
+    assert_no_indonesian(&output);
+
+    let (success, output) = run(&["--lang", "en", "pph21", "--gross", "10000000", "--trace"]);
+    assert!(success);
+    assert!(output.contains("Running Value") && output.contains("Less job expense"));
+    assert_no_indonesian(&output);
+
+    let (success, output) = run(&["--lang", "en", "ppn", "--amount", "1000000"]);
+    assert!(success);
+    assert!(output.contains("PPN Result (11%):"));
+    assert_no_indonesian(&output);
+}
+
+#[test]
+fn test_english_error_messages() {
+    let (success, output) = run(&["--lang", "en", "pph21", "--gross=-5"]);
+    assert!(!success);
+    assert!(output.contains("Gross income must be Rp 0 or more (entered: -5)."), "{}", output);
+    assert_no_indonesian(&output);
+
+    let (success, output) = run(&["--lang", "en", "--preset", "nope"]);
+    assert!(!success);
+    assert!(output.contains("Preset 'nope' not found."), "{}", output);
+
+    // Indonesian stays the default
+    let (_, output) = run(&["pph21", "--gross=-5"]);
+    assert!(output.contains("Penghasilan bruto harus Rp 0 atau lebih"), "{}", output);
+}
@@ -0,0 +1,38 @@
+// Log records from the batch processor, captured with an in-memory logger
+
+use std::sync::Mutex;
+
+use log::{Level, Log, Metadata, Record};
+use tax_calculator::batch::run_batch;
+use tax_calculator::tax::TaxConfig;
+
+// Every record logged in this test process, as (level, message)
+struct CapturingLogger(Mutex<Vec<(Level, String)>>);
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+    
+    fn log(&self, record: &Record) {
+        self.0.lock().unwrap().push((record.level(), record.args().to_string()));
+    }
+    
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+
+#[test]
+fn test_invalid_batch_row_logs_a_warning() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+    let input = "name,gross_income,is_married,num_dependents\nBudi,6000000,false,0\nAgus,abc,true,1\n";
+    
+    run_batch(input.as_bytes(), Vec::new(), &TaxConfig::default()).unwrap();
+    
+    let records = LOGGER.0.lock().unwrap();
+    assert!(records.iter().any(|(level, message)| *level == Level::Info && message.starts_with("Baris 2: Budi")));
+    let warnings: Vec<&String> = records.iter().filter(|(level, _)| *level == Level::Warn).map(|(_, message)| message).collect();
+    assert_eq!(warnings, ["Baris 3 dilewati: gross_income 'abc' tidak valid"]);
+}
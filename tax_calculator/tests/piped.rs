@@ -0,0 +1,59 @@
+// Piped (non-TTY) stdin is answered without menu prompts
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn run_with_stdin(input: &str) -> std::process::Output {
+    run_with_args_and_stdin(&[], input)
+}
+
+fn run_with_args_and_stdin(args: &[&str], input: &str) -> std::process::Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tax_calculator"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_piped_pph21_prints_only_the_result() {
+    let output = run_with_stdin("1\n6000000\n1\n");
+
+    assert!(output.status.success());
+    // TK/0 on 6,000,000 a month: PKP 14,400,000 x 5% / 12
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "60000\n");
+}
+
+#[test]
+fn test_piped_input_accepts_spaces_and_dependents() {
+    let output = run_with_stdin("4 1000000 12");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "120000\n");
+
+    let married = run_with_stdin("1 6000000 2 2");
+    assert!(married.status.success());
+    assert!(!String::from_utf8(married.stdout).unwrap().contains("KALKULATOR"));
+}
+
+#[test]
+fn test_piped_invalid_input_exits_with_error() {
+    let output = run_with_stdin("1 abc 1");
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn test_piped_income_tax_uses_brackets_csv() {
+    let brackets = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/brackets.csv");
+
+    let built_in = run_with_stdin("3 60000000");
+    let loaded = run_with_args_and_stdin(&["--brackets", brackets], "3 60000000");
+
+    assert_eq!(String::from_utf8(built_in.stdout).unwrap(), "3000000\n");
+    assert_eq!(String::from_utf8(loaded.stdout).unwrap(), "4000000\n");
+}
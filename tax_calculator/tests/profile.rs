@@ -0,0 +1,30 @@
+// A profile saved with --save-profile reproduces the calculation with --profile
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run(profiles: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tax_calculator"))
+        .args(args)
+        .arg("--profiles")
+        .arg(profiles)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_saved_profile_only_needs_the_gross() {
+    let path = std::env::temp_dir().join(format!("tax_calculator_cli_profiles_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    
+    let saved = run(&path, &["pph21", "--gross", "25000000", "--married", "--dependents", "2", "--no-npwp", "--save-profile", "budi", "-q"]);
+    let loaded = run(&path, &["pph21", "--gross", "25000000", "--profile", "budi", "-q"]);
+    let missing = run(&path, &["pph21", "--gross", "25000000", "--profile", "andi", "-q"]);
+    std::fs::remove_file(&path).unwrap();
+    
+    assert!(saved.status.success());
+    assert!(String::from_utf8(saved.stderr).unwrap().contains("budi"));
+    assert!(loaded.status.success());
+    assert_eq!(loaded.stdout, saved.stdout);
+    assert_eq!(missing.status.code(), Some(2));
+}
@@ -0,0 +1,54 @@
+// Exercises the library the way a downstream crate would
+
+use tax_calculator::tax::{
+    calculate_income_tax, calculate_pph21, calculate_pph21_result, calculate_vat, get_tax_brackets, PPh21Params, Pph21Method,
+    Pph21Result, TaxConfig,
+};
+
+#[test]
+fn test_public_api_pph21_income_tax_and_vat() {
+    let params = PPh21Params::builder()
+        .gross_income(6_000_000.0)
+        .build()
+        .unwrap();
+    let (annual_tax, monthly_tax, ptkp, pkp, biaya_jabatan) = calculate_pph21(&params, &TaxConfig::default());
+
+    assert_eq!(annual_tax, 540_000.0);
+    assert_eq!(monthly_tax, 45_000.0);
+    assert_eq!(ptkp, 54_000_000.0);
+    assert_eq!(biaya_jabatan, 3_600_000.0);
+    assert_eq!(pkp, 14_400_000.0);
+
+    // 60,000,000 x 5% + 10,000,000 x 15%
+    assert_eq!(calculate_income_tax(70_000_000.0, &get_tax_brackets()), 4_500_000.0);
+
+    assert_eq!(calculate_vat(1_000_000.0, 11.0), 110_000.0);
+}
+
+#[test]
+fn test_pph21_result_built_outside_the_crate() {
+    // Pph21Result is non_exhaustive, so a downstream crate builds one instead
+    // of writing the struct literal
+    let config = TaxConfig::default();
+    let built = Pph21Result::builder()
+        .gross_monthly(6_000_000.0)
+        .biaya_jabatan(3_600_000.0)
+        .ptkp(54_000_000.0)
+        .pkp(14_400_000.0)
+        .annual_tax(720_000.0)
+        .monthly_tax(60_000.0)
+        .tables_fingerprint(&config.fingerprint())
+        .build();
+
+    let params = PPh21Params::builder().gross_income(6_000_000.0).build().unwrap();
+    assert_eq!(built, calculate_pph21_result(&params, Pph21Method::Progressive, &config));
+    assert_eq!(Pph21Result::builder().monthly_tax(60_000.0).build().annual_tax, 0.0);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_approx_eq_is_available_to_downstream_tests() {
+    use tax_calculator::test_utils::approx_eq;
+
+    assert!(approx_eq(calculate_vat(1_000_000.0, 11.0), 110_000.0, 0.01));
+}
@@ -0,0 +1,35 @@
+// --quiet prints the final figure alone, for scripts and spreadsheets
+
+use std::process::Command;
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_tax_calculator")).args(args).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// The output must be exactly one line holding a number
+fn parse_single_number(output: &str) -> f64 {
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 1, "{:?}", output);
+    lines[0].parse().unwrap_or_else(|_| panic!("not a number: {:?}", output))
+}
+
+#[test]
+fn test_quiet_prints_only_the_monthly_tax() {
+    assert_eq!(parse_single_number(&run(&["--quiet", "pph21", "--gross", "6000000"])), 60_000.0);
+    assert_eq!(parse_single_number(&run(&["pph21", "--gross", "6000000", "--flat", "-q"])), 45_000.0);
+}
+
+#[test]
+fn test_quiet_prints_only_the_vat() {
+    assert_eq!(parse_single_number(&run(&["ppn", "--amount", "1000000", "--quiet"])), 110_000.0);
+}
+
+#[test]
+fn test_round_to_rounds_the_printed_figure() {
+    // PPN on 412,185 at 11% is 45,340.35
+    assert_eq!(parse_single_number(&run(&["ppn", "--amount", "412185", "-q"])), 45_340.35);
+    assert_eq!(parse_single_number(&run(&["ppn", "--amount", "412185", "-q", "--round-to", "100"])), 45_300.0);
+    assert_eq!(parse_single_number(&run(&["--round-to", "1000", "ppn", "--amount", "412185", "-q"])), 45_000.0);
+}
@@ -0,0 +1,70 @@
+// HTTP API round trips against a live router (cargo test --features server)
+#![cfg(feature = "server")]
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+
+use serde_json::Value;
+use tax_calculator::server::serve;
+use tax_calculator::tax::TaxConfig;
+
+// Serve the API on a free local port for the rest of the test process
+fn start_server() -> SocketAddr {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || runtime.block_on(serve(listener, TaxConfig::default())));
+    addr
+}
+
+// POST a JSON body and return the status code and parsed response body
+fn post(addr: SocketAddr, path: &str, body: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        addr,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn test_post_pph21_returns_monthly_tax() {
+    let addr = start_server();
+
+    let (status, result) = post(addr, "/pph21", r#"{"gross_income": 6000000}"#);
+    assert_eq!(status, 200);
+    // TK/0: PKP 14,400,000 x 5% / 12
+    assert_eq!(result["monthly_tax"], 60_000.0);
+    assert_eq!(result["ptkp"], 54_000_000.0);
+
+    let (status, result) = post(addr, "/pph21", r#"{"gross_income": 25000000, "is_married": true, "num_dependents": 2}"#);
+    assert_eq!(status, 200);
+    assert_eq!(result["ptkp"], 67_500_000.0);
+
+    let (status, error) = post(addr, "/pph21", r#"{"gross_income": -1}"#);
+    assert_eq!(status, 400);
+    assert!(error["error"].as_str().unwrap().contains("Penghasilan bruto"));
+}
+
+#[test]
+fn test_post_ppn_returns_vat() {
+    let addr = start_server();
+
+    let (status, result) = post(addr, "/ppn", r#"{"amount": 1000000}"#);
+    assert_eq!(status, 200);
+    assert_eq!(result["vat"], 110_000.0);
+
+    let (status, result) = post(addr, "/ppn", r#"{"amount": 1110000, "inclusive": true}"#);
+    assert_eq!(status, 200);
+    assert_eq!(result["base"], 1_000_000.0);
+}
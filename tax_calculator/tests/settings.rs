@@ -0,0 +1,50 @@
+// Default PPN rate precedence through the binary: --rate, then
+// RUSTACEAN_VAT_RATE, then vat_rate in a discovered tax_calculator.toml, then 11%
+
+use std::path::Path;
+use std::process::Command;
+
+fn vat(dir: &Path, env: Option<&str>, args: &[&str]) -> f64 {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_tax_calculator"));
+    command.current_dir(dir).env_remove("RUSTACEAN_VAT_RATE");
+    if let Some(rate) = env {
+        command.env("RUSTACEAN_VAT_RATE", rate);
+    }
+    let output = command.args(["-q", "ppn", "--amount", "1000000"]).args(args).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap().trim().parse().unwrap()
+}
+
+#[test]
+fn test_vat_rate_precedence() {
+    let dir = std::env::temp_dir().join(format!("tax_calculator_vat_rate_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    
+    let built_in = vat(&dir, None, &[]);
+    std::fs::write(
+        dir.join("tax_calculator.toml"),
+        format!("vat_rate = 9.0\n{}", include_str!("fixtures/tax_config.toml")),
+    )
+    .unwrap();
+    let from_config = vat(&dir, None, &[]);
+    let from_env = vat(&dir, Some("10"), &[]);
+    let from_flag = vat(&dir, Some("10"), &["--rate", "12"]);
+    std::fs::remove_dir_all(&dir).unwrap();
+    
+    assert_eq!(built_in, 110_000.0);
+    assert_eq!(from_config, 90_000.0);
+    assert_eq!(from_env, 100_000.0);
+    assert_eq!(from_flag, 120_000.0);
+}
+
+#[test]
+fn test_invalid_env_vat_rate_is_an_error() {
+    let output = Command::new(env!("CARGO_BIN_EXE_tax_calculator"))
+        .env("RUSTACEAN_VAT_RATE", "sebelas")
+        .args(["ppn", "--amount", "1000000"])
+        .output()
+        .unwrap();
+    
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("RUSTACEAN_VAT_RATE"));
+}
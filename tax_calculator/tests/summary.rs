@@ -0,0 +1,24 @@
+// --summary prints the final figures alone, without the worksheet
+
+use std::process::Command;
+
+fn run(args: &[&str]) -> Vec<String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_tax_calculator")).args(args).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap().lines().map(str::to_string).collect()
+}
+
+#[test]
+fn test_summary_prints_the_monthly_tax_and_net_only() {
+    assert_eq!(run(&["--summary", "pph21", "--gross", "6000000"]), ["PPh 21 Sebulan: Rp 60.000,00", "Neto Sebulan: Rp 5.940.000,00"]);
+    assert_eq!(
+        run(&["pph21", "--gross", "6000000", "--take-home", "--summary"]),
+        ["PPh 21 Sebulan: Rp 60.000,00", "BPJS: Rp 240.000,00", "Take Home Pay: Rp 5.700.000,00"]
+    );
+    assert_eq!(run(&["--lang", "en", "--summary", "pph21", "--gross", "10000000"]), ["Monthly PPh 21: Rp 250.000,00", "Monthly Net: Rp 9.750.000,00"]);
+}
+
+#[test]
+fn test_summary_prints_the_vat_and_total() {
+    assert_eq!(run(&["ppn", "--amount", "1000000", "--summary"]), ["PPN (11%): Rp 110.000,00", "Total yang harus dibayar: Rp 1.110.000,00"]);
+}